@@ -0,0 +1,86 @@
+//! Telling the code blocks of a Markdown doc string apart from its text, one line at a time.
+
+/// What a line of a Markdown doc string is, given the lines before it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LineKind<'a> {
+    /// Opens a fenced code block. Holds the info string after the fence, like `rust,ignore`.
+    FenceOpen(&'a str),
+    /// Closes the open fenced code block.
+    FenceClose,
+    /// A line inside a fenced code block, or of an indented one.
+    Code,
+    /// Anything else, like paragraphs, headings and blank lines.
+    Text,
+}
+
+impl<'a> LineKind<'a> {
+    /// Whether the line is part of a code block, fences included.
+    pub fn is_code(&self) -> bool {
+        *self != LineKind::Text
+    }
+}
+
+/// Classifies the lines of a doc string in order. Fences are runs of three or more backticks or
+/// tildes, and a block is only closed by a run of the same character that's at least as long.
+/// Lines indented by four spaces are code unless they continue a paragraph.
+#[derive(Clone, Debug, Default)]
+pub struct LineClassifier {
+    /// The character and length of the fence that opened the current block.
+    fence: Option<(char, usize)>,
+    /// Whether the last line was paragraph text, which an indented line continues.
+    in_paragraph: bool,
+}
+
+impl LineClassifier {
+    pub fn new() -> LineClassifier {
+        LineClassifier::default()
+    }
+
+    pub fn classify<'a>(&mut self, line: &'a str) -> LineKind<'a> {
+        let trimmed = line.trim();
+        let run = fence_run(trimmed);
+
+        if let Some((fence_char, fence_len)) = self.fence {
+            match run {
+                Some((c, len)) if c == fence_char && len >= fence_len &&
+                    trimmed[len..].trim().is_empty() => {
+                    self.fence = None;
+                    return LineKind::FenceClose;
+                }
+                _ => return LineKind::Code,
+            }
+        }
+
+        if let Some((c, len)) = run {
+            // The info string of a backtick fence can't hold backticks. Such a line is a code
+            // span instead.
+            let info = &trimmed[len..];
+            if c == '~' || !info.contains('`') {
+                self.fence = Some((c, len));
+                self.in_paragraph = false;
+                return LineKind::FenceOpen(info.trim());
+            }
+        }
+
+        if !self.in_paragraph && !trimmed.is_empty() && line.starts_with("    ") {
+            return LineKind::Code;
+        }
+
+        self.in_paragraph = !trimmed.is_empty() && !trimmed.starts_with('#');
+        LineKind::Text
+    }
+}
+
+/// The character and length of the fence starting `line`, if it starts with one.
+fn fence_run(line: &str) -> Option<(char, usize)> {
+    let c = match line.chars().next() {
+        Some(c) if c == '`' || c == '~' => c,
+        _ => return None,
+    };
+    let len = line.chars().take_while(|&next| next == c).count();
+    if len >= 3 {
+        Some((c, len))
+    } else {
+        None
+    }
+}
@@ -1,10 +1,13 @@
 use store::StoreLocation;
-use std::collections::HashMap;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::fmt::{self, Display};
 
-use document::{CrateInfo, ModPath};
-use generation::ast_ty_wrappers::Attributes;
+use document::{CrateInfo, ModPath, SourceLocation};
+use generation::ast_ty_wrappers::{Attributes, FnKind};
 use store;
 
 use conversion::wrappers::*;
@@ -23,25 +26,89 @@ pub struct Documentation {
     pub mod_path: ModPath,
     pub inner_data: DocInnerData,
     pub visibility: Option<Visibility>,
-    // TODO: source code reference
+    /// Where the item is defined, if known.
+    pub source: Option<SourceLocation>,
     pub links: DocRelatedItems,
 }
 
 impl Display for Visibility {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let vis = match *self {
-            Visibility::Public => "pub",
-            _                  => "",
+        match *self {
+            Visibility::Public => write!(f, "pub"),
+            Visibility::Crate  => write!(f, "pub(crate)"),
+            Visibility::Restricted(ref path) => {
+                match path.to_string().as_str() {
+                    "self" | "super" => write!(f, "pub({})", path),
+                    _                => write!(f, "pub(in {})", path),
+                }
+            }
+            Visibility::Private |
+            Visibility::Inherited => Ok(()),
+        }
+    }
+}
+
+impl Display for FnHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut args = self.args();
+        if self.variadic {
+            args.push("...".to_string());
+        }
+        write!(f, "({})", args.join(", "))?;
+        match self.output {
+            Some(ref output) => write!(f, " -> {}", output),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Abi {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Abi::Cdecl             => "cdecl",
+            Abi::Stdcall           => "stdcall",
+            Abi::Fastcall          => "fastcall",
+            Abi::Vectorcall        => "vectorcall",
+            Abi::Aapcs             => "aapcs",
+            Abi::Win64             => "win64",
+            Abi::SysV64            => "sysv64",
+            Abi::PtxKernel         => "ptx-kernel",
+            Abi::Msp430Interrupt   => "msp430-interrupt",
+            Abi::X86Interrupt      => "x86-interrupt",
+            Abi::Rust              => "Rust",
+            Abi::C                 => "C",
+            Abi::System            => "system",
+            Abi::RustIntrinsic     => "rust-intrinsic",
+            Abi::RustCall          => "rust-call",
+            Abi::PlatformIntrinsic => "platform-intrinsic",
+            Abi::Unadjusted        => "unadjusted",
+            Abi::Thiscall          => "thiscall",
         };
-        write!(f, "{}", vis)
+        write!(f, "{}", name)
     }
 }
 
 impl Documentation {
+    /// The visibility the item actually has. Trait items take the visibility of their trait, the
+    /// crate root is public, and other items without a `pub` are private.
+    pub fn effective_visibility(&self) -> Visibility {
+        match self.visibility {
+            Some(Visibility::Inherited) | None => match self.inner_data {
+                TraitItemDoc(..) => Visibility::Public,
+                ModuleDoc(ref module) if module.is_crate => Visibility::Public,
+                _ => Visibility::Private,
+            },
+            Some(ref vis) => vis.clone(),
+        }
+    }
+
     pub fn get_type(&self) -> DocType {
         match self.inner_data {
-            DocInnerData::FnDoc(..) => {
-                DocType::Function
+            DocInnerData::FnDoc(ref func) => {
+                match func.kind {
+                    FnKind::MethodFromImpl | FnKind::MethodFromTrait => DocType::Method,
+                    _                      => DocType::Function,
+                }
             },
             DocInnerData::ModuleDoc(..) => {
                 DocType::Module
@@ -52,9 +119,21 @@ impl Documentation {
             DocInnerData::StructDoc(..) => {
                 DocType::Struct
             },
+            DocInnerData::UnionDoc(..) => {
+                DocType::Union
+            },
             DocInnerData::ConstDoc(..) => {
                 DocType::Const
             },
+            DocInnerData::StaticDoc(..) => {
+                DocType::Static
+            },
+            DocInnerData::TypedefDoc(..) => {
+                DocType::Typedef
+            },
+            DocInnerData::MacroDoc(..) => {
+                DocType::Macro
+            },
             DocInnerData::TraitDoc(..) => {
                 DocType::Trait
             },
@@ -80,25 +159,39 @@ impl Documentation {
                      DocType::Module,
                      DocType::Enum,
                      DocType::Struct,
+                     DocType::Union,
                      DocType::Trait,
-                     DocType::Const]
+                     DocType::Const,
+                     DocType::Static,
+                     DocType::Typedef,
+                     DocType::Macro]
             },
             DocInnerData::TraitDoc(..) => {
                 vec![DocType::AssocConst,
                      DocType::TraitItemMethod,
+                     DocType::ProvidedMethod,
                      DocType::AssocType,
-                     DocType::Macro]
+                     DocType::Macro,
+                     DocType::Implementor]
             },
-            DocInnerData::StructDoc(..) => {
+            DocInnerData::StructDoc(..) |
+            DocInnerData::UnionDoc(..) => {
                 vec![DocType::StructField,
-                     DocType::Function,
+                     DocType::AssocFn,
+                     DocType::Method,
                      DocType::AssocConst,
                      DocType::AssocType,
-                     DocType::Macro]
+                     DocType::Macro,
+                     DocType::ImplementedTrait]
             },
             DocInnerData::EnumDoc(..) => {
-                vec![DocType::Function,
-                     DocType::Variant]
+                vec![DocType::Variant,
+                     DocType::AssocFn,
+                     DocType::Method,
+                     DocType::AssocConst,
+                     DocType::AssocType,
+                     DocType::Macro,
+                     DocType::ImplementedTrait]
             },
             _  => vec![]
         };
@@ -123,6 +216,39 @@ impl Documentation {
         }
     }
 
+    /// Replaces the items listed under "See Also". An empty list removes the section.
+    pub fn with_see_also(mut self, links: Vec<DocLink>) -> Self {
+        if links.is_empty() {
+            self.links.remove(&DocType::SeeAlso);
+        } else {
+            self.links.insert(DocType::SeeAlso, links);
+        }
+        self
+    }
+
+    /// The items listed under "See Also".
+    pub fn see_also(&self) -> &[DocLink] {
+        self.links.get(&DocType::SeeAlso).map(|links| &links[..]).unwrap_or(&[])
+    }
+
+    /// The arguments and return type of a function or method, or `None` for other items.
+    pub fn fn_header(&self) -> Option<&FnHeader> {
+        match self.inner_data {
+            DocInnerData::FnDoc(ref func) => Some(&func.header),
+            DocInnerData::TraitItemDoc(TraitItem { node: TraitItemKind::Method(ref sig, _) }) => {
+                Some(&sig.header)
+            }
+            _ => None,
+        }
+    }
+
+    /// The first sentence of the item's docs as plain text, for showing next to search results.
+    /// Headings and code blocks before the first paragraph are skipped. Items without docs have
+    /// an empty summary.
+    pub fn summary(&self) -> String {
+        self.attrs.summary()
+    }
+
     pub fn to_store_location(&self) -> StoreLocation {
         StoreLocation {
             name: self.name.clone(),
@@ -133,9 +259,21 @@ impl Documentation {
     }
 
     pub fn save(&self) -> Result<()> {
-        let location = self.to_store_location();
-        let path = location.to_filepath();
+        self.save_as(store::StoreFormat::default())
+    }
+
+    /// The file the document is saved to.
+    pub fn store_path(&self) -> Result<PathBuf> {
+        self.to_store_location().to_filepath()
+    }
 
+    /// Writes the document to its store location in `format`.
+    pub fn save_as(&self, format: store::StoreFormat) -> Result<()> {
+        self.save_to(&self.store_path()?, format)
+    }
+
+    /// Writes the document to the file at `path` in `format`, creating its directory if needed.
+    pub fn save_to(&self, path: &Path, format: store::StoreFormat) -> Result<()> {
         {
             let parent_path = path.parent().unwrap();
 
@@ -143,13 +281,13 @@ impl Documentation {
                 .chain_err(|| format!("Failed to create directory {}", parent_path.display()))?;
         }
 
-        store::serialize_object(self, path)
+        store::serialize_object_as(self, path, format)
     }
 }
 
 /// A list of documentation locations related to the current item, like methods implemented on
 /// structs.
-pub type DocRelatedItems = HashMap<DocType, Vec<DocLink>>;
+pub type DocRelatedItems = BTreeMap<DocType, Vec<DocLink>>;
 
 #[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct DocLink
@@ -158,16 +296,19 @@ pub struct DocLink
     pub path: ModPath,
 }
 
-#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub enum DocType {
     Function,
-    // Method,
+    Method,
     Module,
     Enum,
     Variant,
     Struct,
+    Union,
     StructField,
     Const,
+    Static,
+    Typedef,
     Trait,
     AssocConst,
     TraitItemMethod,
@@ -176,48 +317,173 @@ pub enum DocType {
     TraitItemMacro,
     AssocType,
     Macro,
+    Implementor,
+    ImplementedTrait,
+    /// A trait method with a default body. Only used for links from the trait.
+    ProvidedMethod,
+    /// A function in an impl that takes no `self`, like `new`. Only used for links from the type,
+    /// since the function itself is documented as a `Method`.
+    AssocFn,
+    /// An item referred to by a link in the doc string.
+    IntraDocLink,
+    /// A related item worth reading alongside this one.
+    SeeAlso,
 }
 
+/// Every kind of document, in declaration order.
+pub const DOC_TYPES: [DocType; 25] = [
+    DocType::Function, DocType::Method, DocType::Module, DocType::Enum, DocType::Variant,
+    DocType::Struct, DocType::Union, DocType::StructField, DocType::Const, DocType::Static,
+    DocType::Typedef, DocType::Trait, DocType::AssocConst, DocType::TraitItemMethod,
+    DocType::TraitItemConst, DocType::TraitItemType, DocType::TraitItemMacro, DocType::AssocType,
+    DocType::Macro, DocType::Implementor, DocType::ImplementedTrait, DocType::ProvidedMethod,
+    DocType::IntraDocLink, DocType::SeeAlso, DocType::AssocFn,
+];
+
 impl DocType {
+    /// The name the kind of item is serialized under, like `trait_item_method`. These never
+    /// change, so stored link maps stay readable whatever order the variants are in.
+    pub fn key(&self) -> &'static str {
+        match *self {
+            DocType::Function => "function",
+            DocType::Method => "method",
+            DocType::Module => "module",
+            DocType::Enum => "enum",
+            DocType::Variant => "variant",
+            DocType::Struct => "struct",
+            DocType::Union => "union",
+            DocType::StructField => "struct_field",
+            DocType::Const => "const",
+            DocType::Static => "static",
+            DocType::Typedef => "typedef",
+            DocType::Trait => "trait",
+            DocType::AssocConst => "assoc_const",
+            DocType::TraitItemMethod => "trait_item_method",
+            DocType::TraitItemConst => "trait_item_const",
+            DocType::TraitItemType => "trait_item_type",
+            DocType::TraitItemMacro => "trait_item_macro",
+            DocType::AssocType => "assoc_type",
+            DocType::Macro => "macro",
+            DocType::Implementor => "implementor",
+            DocType::ImplementedTrait => "implemented_trait",
+            DocType::ProvidedMethod => "provided_method",
+            DocType::AssocFn => "assoc_fn",
+            DocType::IntraDocLink => "intra_doc_link",
+            DocType::SeeAlso => "see_also",
+        }
+    }
+
+    /// The kind of item serialized as `key`, the inverse of `DocType::key`.
+    pub fn from_key(key: &str) -> Option<DocType> {
+        DOC_TYPES.iter().find(|ty| ty.key() == key).cloned()
+    }
+
+    /// The kind of item as a single lowercase word, like `struct` or `fn`, as written in Rust
+    /// source where there is a keyword for it.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            DocType::Function => "fn",
+            DocType::Method => "method",
+            DocType::Module => "mod",
+            DocType::Enum => "enum",
+            DocType::Variant => "variant",
+            DocType::Struct => "struct",
+            DocType::Union => "union",
+            DocType::StructField => "field",
+            DocType::Const => "const",
+            DocType::Static => "static",
+            DocType::Typedef => "type",
+            DocType::Trait => "trait",
+            DocType::AssocConst => "assoc-const",
+            DocType::TraitItemConst => "trait-const",
+            DocType::TraitItemMethod => "trait-method",
+            DocType::ProvidedMethod => "provided-method",
+            DocType::AssocFn => "assoc-fn",
+            DocType::IntraDocLink => "link",
+            DocType::SeeAlso => "see-also",
+            DocType::TraitItemType => "trait-type",
+            DocType::TraitItemMacro => "trait-macro",
+            DocType::AssocType => "assoc-type",
+            DocType::Macro => "macro",
+            DocType::Implementor => "implementor",
+            DocType::ImplementedTrait => "impl",
+        }
+    }
+
     pub fn get_file_prefix(&self) -> &str {
         match *self {
             DocType::Function => "",
+            DocType::Method => "mtdesc-",
             DocType::Module => "mdesc-",
             DocType::Enum => "edesc-",
             DocType::Variant => "vdesc-",
             DocType::Struct => "sdesc-",
+            DocType::Union => "udesc-",
             DocType::StructField => "sfdesc-",
             DocType::Const => "cdesc-",
+            DocType::Static => "stdesc-",
+            DocType::Typedef => "tydesc-",
             DocType::Trait => "tdesc-",
             DocType::AssocConst  => &"acdesc-",
             DocType::TraitItemConst => &"tcdesc-",
             DocType::TraitItemMethod => &"tmcdesc-",
+            DocType::ProvidedMethod => &"pmdesc-",
+            DocType::AssocFn => &"afdesc-",
+            DocType::IntraDocLink => &"idldesc-",
+            DocType::SeeAlso => &"sadesc-",
             DocType::TraitItemType => &"ttcdesc-",
             DocType::TraitItemMacro => &"tmdesc-",
             DocType::AssocType   => &"atdesc-",
             DocType::Macro  => &"macdesc-",
+            DocType::Implementor => &"impldesc-",
+            DocType::ImplementedTrait => &"itdesc-",
         }
     }
 }
 
+impl Serialize for DocType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.key())
+    }
+}
+
+impl<'de> Deserialize<'de> for DocType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D)
+                                         -> ::std::result::Result<DocType, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        DocType::from_key(&key)
+            .ok_or_else(|| de::Error::custom(format!("unknown document type `{}`", key)))
+    }
+}
+
 impl Display for DocType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let name = match *self {
             DocType::Function => "Functions",
+            DocType::Method => "Methods",
             DocType::Module => "Modules",
             DocType::Enum => "Enums",
             DocType::Variant => "Variants",
             DocType::Struct => "Structs",
+            DocType::Union => "Unions",
             DocType::StructField => "Struct Fields",
             DocType::Const => "Constants",
+            DocType::Static => "Statics",
+            DocType::Typedef => "Type Definitions",
             DocType::Trait => "Traits",
             DocType::AssocConst  => &"Associated Constants",
             DocType::TraitItemConst => &"Trait Constants",
             DocType::TraitItemMethod => &"Trait Methods",
+            DocType::ProvidedMethod => &"Provided Methods",
+            DocType::AssocFn => &"Associated Functions",
+            DocType::IntraDocLink => &"Referenced Items",
+            DocType::SeeAlso => &"See Also",
             DocType::TraitItemType => &"Trait Types",
             DocType::TraitItemMacro => &"Trait Macros",
             DocType::AssocType   => &"Associated Types",
             DocType::Macro  => &"Macros",
+            DocType::Implementor => &"Implementors",
+            DocType::ImplementedTrait => &"Trait Implementations",
         };
         write!(f, "{}", name)
     }
@@ -231,9 +497,10 @@ pub enum DocInnerData {
     EnumDoc(Enum),
     StructDoc(Struct),
     ConstDoc(Constant),
-    //StaticDoc,
-    //Union,
-    //TypedefDoc,
+    StaticDoc(Static),
+    UnionDoc(Union),
+    TypedefDoc(Typedef),
+    MacroDoc(Macro),
     TraitDoc(Trait),
     TraitItemDoc(TraitItem),
 }
@@ -6,17 +6,22 @@ mod doc_containers;
 
 pub use conversion::doc_containers::*;
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 use syntax::abi;
 use syntax::ast;
+use syntax::codemap::{CodeMap, Span, DUMMY_SP, NO_EXPANSION};
+use syntax::parse::token;
 use syntax::print::pprust;
 use syntax::ptr::P;
+use syntax::symbol::keywords;
+use syntax::tokenstream::TokenTree;
 
 use generation::ast_ty_wrappers::{self, Impl, Ty, Attributes};
 use generation::visitor::OxidocVisitor;
-use document::{CrateInfo, ModPath};
+use document::{CrateInfo, ModPath, SourceLocation};
 
 pub use conversion::wrappers::*;
 
@@ -26,18 +31,84 @@ pub struct Context {
     pub crate_info: CrateInfo,
     /// Mapping from types to their implementations. Received from the AST visitor.
     pub impls_for_ty: HashMap<ModPath, Vec<Impl>>,
+    /// Mapping from traits to the impls of them found in the crate.
+    pub impls_for_trait: HashMap<ModPath, Vec<Impl>>,
+    /// Paths of items marked `#[doc(hidden)]`. Received from the AST visitor.
+    pub hidden_paths: HashSet<ModPath>,
+    /// Whether to document hidden items anyway, for internal tooling.
+    pub include_hidden: bool,
+    /// Paths of items that aren't public. Received from the AST visitor.
+    pub private_paths: HashSet<ModPath>,
+    /// Whether to document private items too, like `rustdoc --document-private-items`. Modules
+    /// are always documented, so public items re-exported from private modules are found.
+    pub document_private_items: bool,
+    /// The source files the crate was parsed from, used to find files included with
+    /// `#[doc(include)]` relative to the file they are included from.
+    pub codemap: Option<Rc<CodeMap>>,
+    /// Where each item is defined in the source. Received from the AST visitor.
+    pub spans: HashMap<ModPath, Span>,
 }
 
 impl Context {
     pub fn new(store_path: PathBuf,
                crate_info: CrateInfo,
-               impls_for_ty: HashMap<ModPath, Vec<Impl>>) -> Self {
+               impls_for_ty: HashMap<ModPath, Vec<Impl>>,
+               impls_for_trait: HashMap<ModPath, Vec<Impl>>,
+               hidden_paths: HashSet<ModPath>) -> Self {
         Context {
             store_path: store_path,
             crate_info: crate_info,
             impls_for_ty: impls_for_ty,
+            impls_for_trait: impls_for_trait,
+            hidden_paths: hidden_paths,
+            include_hidden: false,
+            private_paths: HashSet::new(),
+            document_private_items: false,
+            codemap: None,
+            spans: HashMap::new(),
         }
     }
+
+    /// The directory of the source file containing `span`, if the source files are known.
+    pub fn source_dir(&self, span: Span) -> Option<PathBuf> {
+        self.codemap.as_ref()
+            .and_then(|codemap| Path::new(&codemap.span_to_filename(span)).parent()
+                      .map(|dir| dir.to_path_buf()))
+    }
+
+    /// Where the item at `path` is defined, if the source files are known. Items created by macro
+    /// expansion have no location of their own.
+    pub fn source_location(&self, path: &ModPath) -> Option<SourceLocation> {
+        let span = match self.spans.get(path) {
+            Some(span) if span.ctxt == NO_EXPANSION && *span != DUMMY_SP => *span,
+            _ => return None,
+        };
+
+        self.codemap.as_ref().map(|codemap| {
+            let loc = codemap.lookup_char_pos(span.lo);
+            SourceLocation {
+                file: loc.file.name.to_string(),
+                line: loc.line,
+                col: loc.col.0,
+            }
+        })
+    }
+
+    /// Whether the item at `path` should be left out of the documentation, because it or one of
+    /// its parents is hidden, or is private and private items aren't documented.
+    pub fn is_hidden(&self, path: &ModPath) -> bool {
+        let mut current = Some(path.clone());
+        while let Some(path) = current {
+            if !self.include_hidden && self.hidden_paths.contains(&path) {
+                return true;
+            }
+            if !self.document_private_items && self.private_paths.contains(&path) {
+                return true;
+            }
+            current = path.parent();
+        }
+        false
+    }
 }
 
 pub trait Convert<T> {
@@ -80,12 +151,28 @@ impl Convert<Constness> for ast::Constness {
     }
 }
 
+impl Convert<Mutability> for ast::Mutability {
+    fn convert(&self, _context: &Context) -> Mutability {
+        match *self {
+            ast::Mutability::Mutable   => Mutability::Mutable,
+            ast::Mutability::Immutable => Mutability::Immutable,
+        }
+    }
+}
+
 impl Convert<Visibility> for ast::Visibility {
     fn convert(&self, _context: &Context) -> Visibility {
         match *self {
             ast::Visibility::Public    => Visibility::Public,
             ast::Visibility::Inherited => Visibility::Inherited,
-            _                          => Visibility::Private,
+            ast::Visibility::Crate(..) => Visibility::Crate,
+            ast::Visibility::Restricted { ref path, .. } => {
+                // `in` paths are absolute, and start with a segment for the crate root.
+                let segments = ModPath::from((**path).clone()).0.into_iter()
+                    .filter(|segment| segment.identifier != "{{root}}")
+                    .collect();
+                Visibility::Restricted(ModPath(segments))
+            }
         }
     }
 }
@@ -118,7 +205,10 @@ impl Convert<Abi> for abi::Abi {
 impl Convert<Vec<Documentation>> for OxidocVisitor {
     fn convert(&self, context: &Context) -> Vec<Documentation> {
         debug!("Converting store");
-        let documents = self.crate_module.convert(context);
+        // Conversion stays on one thread: the AST holds `Rc`s and its identifiers are interned
+        // per thread, so neither the wrappers nor the context can be shared with other threads.
+        let mut documents = self.crate_module.convert(context);
+        documents.sort_by(|a, b| (&a.mod_path, &a.name).cmp(&(&b.mod_path, &b.name)));
 
         for doc in &documents {
             debug!("{:?}", doc);
@@ -134,19 +224,26 @@ impl Convert<Vec<Documentation>> for ast_ty_wrappers::Module {
             debug!("in {:?}, {} => {}", self.ident, ident, path);
         }
 
+        if context.is_hidden(&self.path) {
+            return vec![];
+        }
+
+        let shown = |path: &ModPath| !context.is_hidden(path);
         let mut docs: Vec<Documentation> = vec![];
 
-        docs.extend(self.consts.iter().map(|x| x.convert(context)));
-        docs.extend(self.traits.iter().map(|x| x.convert(context)));
-        docs.extend(self.fns.iter().map(|x| x.convert(context)));
+        docs.extend(self.consts.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.traits.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.traits.iter().flat_map(|x| x.items.iter())
+                    .filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.fns.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
         docs.extend(self.mods.iter().flat_map(|x| x.convert(context)));
-        docs.extend(self.structs.iter().map(|x| x.convert(context)));
-        // unions
-        docs.extend(self.enums.iter().map(|x| x.convert(context)));
-        // foreigns
-        // typedefs
-        // statics
-        // macros
+        docs.extend(self.structs.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.unions.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.enums.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.foreigns.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.typedefs.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.statics.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
+        docs.extend(self.macros.iter().filter(|x| shown(&x.path)).map(|x| x.convert(context)));
         // def_traits
 
         let name = match self.ident {
@@ -158,12 +255,14 @@ impl Convert<Vec<Documentation>> for ast_ty_wrappers::Module {
             name: name.clone(),
             attrs: self.attrs.convert(context),
             mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
             crate_info: context.crate_info.clone(),
             visibility: Some(self.vis.convert(context)),
             inner_data: ModuleDoc(Module {
                 is_crate: self.is_crate,
+                reexports: self.imports.iter().map(|x| x.convert(context)).collect(),
             }),
-            links: HashMap::new(),
+            links: child_links(&self.path, &docs),
         };
 
         docs.push(mod_doc);
@@ -172,19 +271,162 @@ impl Convert<Vec<Documentation>> for ast_ty_wrappers::Module {
     }
 }
 
+impl Convert<Reexport> for ast_ty_wrappers::Import {
+    fn convert(&self, context: &Context) -> Reexport {
+        let module = if self.is_glob {
+            self.path.clone()
+        } else {
+            self.path.parent().unwrap_or_else(ModPath::new)
+        };
+
+        Reexport {
+            alias: self.path.clone(),
+            original: resolve_import_path(&self.original, &module, &context.crate_info.name),
+            is_glob: self.is_glob,
+        }
+    }
+}
+
 impl Convert<Documentation> for ast_ty_wrappers::Constant {
     fn convert(&self, context: &Context) -> Documentation {
         Documentation {
             name: self.ident.convert(context),
             attrs: self.attrs.convert(context),
             mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
             crate_info: context.crate_info.clone(),
             visibility: Some(self.vis.convert(context)),
             inner_data: ConstDoc(Constant {
                 ty: self.type_.clone(),
                 expr: self.expr.convert(context),
             }),
-            links: HashMap::new(),
+            links: BTreeMap::new(),
+        }
+    }
+}
+
+impl Convert<Documentation> for ast_ty_wrappers::Static {
+    fn convert(&self, context: &Context) -> Documentation {
+        Documentation {
+            name: self.ident.convert(context),
+            attrs: self.attrs.convert(context),
+            mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
+            crate_info: context.crate_info.clone(),
+            visibility: Some(self.vis.convert(context)),
+            inner_data: StaticDoc(Static {
+                ty: self.type_.clone(),
+                mutability: self.mutability.convert(context),
+                expr: Some(self.expr.convert(context)),
+                abi: None,
+            }),
+            links: BTreeMap::new(),
+        }
+    }
+}
+
+impl Convert<Documentation> for ast_ty_wrappers::ForeignItem {
+    fn convert(&self, context: &Context) -> Documentation {
+        let inner_data = match self.node {
+            ast::ForeignItemKind::Fn(ref decl, ref generics) => FnDoc(Function {
+                header: decl.convert(context),
+                generics: generics.convert(context),
+                // Calling a foreign function is always unsafe.
+                unsafety: Unsafety::Unsafe,
+                constness: Constness::NotConst,
+                abi: self.abi.convert(context),
+                kind: ast_ty_wrappers::FnKind::ItemFn,
+                is_foreign: true,
+                is_async: false,
+            }),
+            ast::ForeignItemKind::Static(ref ty, is_mutable) => StaticDoc(Static {
+                ty: Ty::from((**ty).clone()),
+                mutability: if is_mutable { Mutability::Mutable } else { Mutability::Immutable },
+                expr: None,
+                abi: Some(self.abi.convert(context)),
+            }),
+        };
+
+        Documentation {
+            name: self.ident.convert(context),
+            attrs: self.attrs.convert(context),
+            mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
+            crate_info: context.crate_info.clone(),
+            visibility: Some(self.vis.convert(context)),
+            inner_data: inner_data,
+            links: BTreeMap::new(),
+        }
+    }
+}
+
+impl Convert<Documentation> for ast_ty_wrappers::Macro {
+    fn convert(&self, context: &Context) -> Documentation {
+        Documentation {
+            name: self.ident.convert(context),
+            attrs: self.attrs.convert(context),
+            mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
+            crate_info: context.crate_info.clone(),
+            visibility: Some(self.vis.convert(context)),
+            inner_data: MacroDoc(Macro {
+                matchers: macro_matchers(&self.def),
+            }),
+            links: BTreeMap::new(),
+        }
+    }
+}
+
+/// Finds the matcher of each arm in a macro body, which is the token tree preceding each `=>`.
+fn macro_matchers(def: &ast::MacroDef) -> Vec<String> {
+    let trees: Vec<TokenTree> = def.stream().trees().collect();
+
+    trees.windows(2).filter_map(|pair| {
+        match pair[1] {
+            TokenTree::Token(_, token::FatArrow) => Some(matcher_to_string(&pair[0])),
+            _ => None,
+        }
+    }).collect()
+}
+
+/// Prints a macro matcher without the spacing `pprust` puts between every token, so `($x:expr)`
+/// reads the way it was written.
+fn matcher_to_string(tree: &TokenTree) -> String {
+    match *tree {
+        TokenTree::Token(_, ref tok) => pprust::token_to_string(tok),
+        TokenTree::Delimited(_, ref delimited) => {
+            let mut result = pprust::token_to_string(&delimited.open_token());
+            let mut prev = String::new();
+            for (i, inner) in delimited.stream().trees().enumerate() {
+                let next = matcher_to_string(&inner);
+                let glued = prev == "$" || prev == ":" ||
+                    [":", ",", ";", "*", "+", "?"].contains(&next.as_str());
+                if i > 0 && !glued {
+                    result.push(' ');
+                }
+                result.push_str(&next);
+                prev = next;
+            }
+            result.push_str(&pprust::token_to_string(&delimited.close_token()));
+            result
+        }
+    }
+}
+
+impl Convert<Documentation> for ast_ty_wrappers::Typedef {
+    fn convert(&self, context: &Context) -> Documentation {
+        Documentation {
+            name: self.ident.convert(context),
+            attrs: self.attrs.convert(context),
+            mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
+            crate_info: context.crate_info.clone(),
+            visibility: Some(self.vis.convert(context)),
+            inner_data: TypedefDoc(Typedef {
+                ty: self.ty.convert(context),
+                generics: self.generics.convert(context),
+            }),
+            links: BTreeMap::new(),
         }
     }
 }
@@ -195,17 +437,20 @@ impl Convert<Documentation> for ast_ty_wrappers::Function {
             name: self.ident.convert(context),
             attrs: self.attrs.convert(context),
             mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
             crate_info: context.crate_info.clone(),
             visibility: Some(self.vis.convert(context)),
             inner_data: FnDoc(Function {
                 header: self.decl.convert(context),
-                generics: Generics { } ,
+                generics: self.generics.convert(context),
                 unsafety: self.unsafety.convert(context),
                 constness: self.constness.convert(context),
                 abi: self.abi.convert(context),
                 kind: self.kind.clone(),
+                is_foreign: false,
+                is_async: false,
             }),
-            links: HashMap::new(),
+            links: BTreeMap::new(),
         }
     }
 }
@@ -223,16 +468,22 @@ impl Convert<MethodSig> for ast::MethodSig {
 
 impl Convert<Documentation> for ast_ty_wrappers::Trait {
     fn convert(&self, context: &Context) -> Documentation {
+        let mut links: DocRelatedItems = self.items.convert(context);
+        merge_links(&mut links, implementor_links(&self.path, context));
+
         Documentation {
             name: self.ident.convert(context),
             attrs: self.attrs.convert(context),
             mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
             crate_info: context.crate_info.clone(),
             visibility: Some(self.vis.convert(context)),
             inner_data: TraitDoc(Trait {
                 unsafety: self.unsafety.convert(context),
+                generics: self.generics.convert(context),
+                bounds: self.bounds.convert(context),
             }),
-            links: self.items.convert(context),
+            links: links,
         }
     }
 }
@@ -243,12 +494,13 @@ impl Convert<Documentation> for ast_ty_wrappers::TraitItem {
             name: self.ident.convert(context),
             attrs: self.attrs.convert(context),
             mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
             crate_info: context.crate_info.clone(),
             visibility: Some(Visibility::Inherited),
             inner_data: TraitItemDoc(TraitItem {
                 node: self.node.convert(context),
             }),
-            links: HashMap::new(),
+            links: BTreeMap::new(),
         }
     }
 }
@@ -257,13 +509,15 @@ impl Convert<DocRelatedItems> for [ast_ty_wrappers::TraitItem] {
     fn convert(&self, context: &Context) -> DocRelatedItems {
         let mut consts = Vec::new();
         let mut methods = Vec::new();
+        let mut provided = Vec::new();
         let mut types = Vec::new();
         let mut macros = Vec::new();
 
         for item in self {
             match item.node {
                 ast::TraitItemKind::Const(..) => consts.push(item.clone()),
-                ast::TraitItemKind::Method(..) => methods.push(item.clone()),
+                ast::TraitItemKind::Method(_, None) => methods.push(item.clone()),
+                ast::TraitItemKind::Method(_, Some(..)) => provided.push(item.clone()),
                 ast::TraitItemKind::Type(..) => types.push(item.clone()),
                 ast::TraitItemKind::Macro(..) => macros.push(item.clone()),
             }
@@ -278,9 +532,10 @@ impl Convert<DocRelatedItems> for [ast_ty_wrappers::TraitItem] {
             ).collect()
         };
 
-        let mut links = HashMap::new();
+        let mut links = BTreeMap::new();
         links.insert(DocType::AssocConst, conv(consts));
         links.insert(DocType::TraitItemMethod, conv(methods));
+        links.insert(DocType::ProvidedMethod, conv(provided));
         links.insert(DocType::AssocType, conv(types));
         links.insert(DocType::Macro, conv(macros));
         links
@@ -293,11 +548,11 @@ impl Convert<TraitItemKind> for ast::TraitItemKind {
             ast::TraitItemKind::Const(ref ty, ref expr) => {
                 TraitItemKind::Const(ty.convert(context), expr.convert(context))
             },
-            ast::TraitItemKind::Method(ref sig, ref _block) => {
-                TraitItemKind::Method(sig.convert(context))
+            ast::TraitItemKind::Method(ref sig, ref block) => {
+                TraitItemKind::Method(sig.convert(context), block.is_some())
             },
-            ast::TraitItemKind::Type(ref _bounds, ref ty) => {
-                TraitItemKind::Type(ty.convert(context))
+            ast::TraitItemKind::Type(ref bounds, ref ty) => {
+                TraitItemKind::Type(bounds.convert(context), ty.convert(context))
             },
             ast::TraitItemKind::Macro(ref mac) => {
                 TraitItemKind::Macro(mac.convert(context))
@@ -308,23 +563,45 @@ impl Convert<TraitItemKind> for ast::TraitItemKind {
 
 impl Convert<Documentation> for ast_ty_wrappers::Struct {
     fn convert(&self, context: &Context) -> Documentation {
-        let mut links: DocRelatedItems = self.fields.convert(context);
-        if let Some(impls) = context.impls_for_ty.get(&self.path) {
-            for impl_ in impls {
-                let impl_links = impl_.convert(context);
-                debug!("Impl found for {}!", self.path);
-                links.extend(impl_links);
-            }
-        }
+        let fields = convert_fields(&self.fields, &self.path, context);
+
+        let mut links: DocRelatedItems = field_links(&fields);
+        merge_links(&mut links, impl_links(&self.path, context));
 
         Documentation {
             name: self.ident.convert(context),
             attrs: self.attrs.convert(context),
             mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
             crate_info: context.crate_info.clone(),
             visibility: Some(self.vis.convert(context)),
             inner_data: StructDoc(Struct {
-                fields: self.fields.convert(context),
+                kind: self.kind.clone(),
+                fields: fields,
+                generics: self.generics.convert(context),
+            }),
+            links: links,
+        }
+    }
+}
+
+impl Convert<Documentation> for ast_ty_wrappers::Union {
+    fn convert(&self, context: &Context) -> Documentation {
+        let fields = convert_fields(&self.fields, &self.path, context);
+
+        let mut links: DocRelatedItems = field_links(&fields);
+        merge_links(&mut links, impl_links(&self.path, context));
+
+        Documentation {
+            name: self.ident.convert(context),
+            attrs: self.attrs.convert(context),
+            mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
+            crate_info: context.crate_info.clone(),
+            visibility: Some(self.vis.convert(context)),
+            inner_data: UnionDoc(Union {
+                fields: fields,
+                generics: self.generics.convert(context),
             }),
             links: links,
         }
@@ -332,15 +609,36 @@ impl Convert<Documentation> for ast_ty_wrappers::Struct {
 }
 
 impl Convert<DocRelatedItems> for ast_ty_wrappers::Impl {
+    /// Links to the items of an inherent impl, or to the trait of a trait impl. The items of trait
+    /// impls are documented with the trait, so they aren't listed as the type's own.
     fn convert(&self, context: &Context) -> DocRelatedItems {
+        let mut links = BTreeMap::new();
+        if let Some(ref trait_ref) = self.trait_ {
+            let trait_link = DocLink {
+                name: pprust::path_to_string(&trait_ref.path),
+                path: self.trait_path.clone()
+                    .unwrap_or_else(|| ModPath::from(trait_ref.path.clone())),
+            };
+            links.insert(DocType::ImplementedTrait, vec![trait_link]);
+            return links;
+        }
+
         let mut consts = Vec::new();
         let mut methods = Vec::new();
+        let mut assoc_fns = Vec::new();
         let mut types = Vec::new();
         let mut macros = Vec::new();
         for item in &self.items {
             match item.node {
                 ast::ImplItemKind::Const(..)  => consts.push(item.clone()),
-                ast::ImplItemKind::Method(..) => methods.push(item.clone()),
+                ast::ImplItemKind::Method(ref sig, _) => {
+                    let header: FnHeader = sig.decl.convert(context);
+                    if header.is_method() {
+                        methods.push(item.clone());
+                    } else {
+                        assoc_fns.push(item.clone());
+                    }
+                }
                 ast::ImplItemKind::Type(..)   => types.push(item.clone()),
                 ast::ImplItemKind::Macro(..)  => macros.push(item.clone()),
             }
@@ -355,40 +653,111 @@ impl Convert<DocRelatedItems> for ast_ty_wrappers::Impl {
                                         &ModPath::from(name))
                 }
             }
-            ).collect()
+            ).filter(|link| !context.is_hidden(&link.path)).collect()
         };
 
-        let mut links = HashMap::new();
         links.insert(DocType::AssocConst, conv(consts));
-        links.insert(DocType::Function, conv(methods));
+        links.insert(DocType::Method, conv(methods));
+        links.insert(DocType::AssocFn, conv(assoc_fns));
         links.insert(DocType::AssocType, conv(types));
         links.insert(DocType::Macro, conv(macros));
         links
     }
 }
 
-impl Convert<DocRelatedItems> for [ast::StructField] {
-    fn convert(&self, context: &Context) -> DocRelatedItems {
-        let mut fields = Vec::new();
-
-        for item in self {
-            if item.ident.is_none() {
-                continue;
+/// Links to each type the trait at `path` is implemented for, including generic forms like
+/// `Vec<T>`.
+fn implementor_links(path: &ModPath, context: &Context) -> DocRelatedItems {
+    let implementors = match context.impls_for_trait.get(path) {
+        Some(impls) => impls.iter().map(|impl_| {
+            DocLink {
+                name: ast_ty_wrappers::ty_to_string(&impl_.for_),
+                path: impl_.path.clone(),
             }
-            let field = item.convert(context);
-            let field_link = DocLink {
-                // TODO: Display nicely, with signature
-                name: field.ident.unwrap(),
-                path: field.path.clone(),
-            };
-            fields.push(field_link);
+        }).collect(),
+        None => Vec::new(),
+    };
+
+    let mut links = BTreeMap::new();
+    links.insert(DocType::Implementor, implementors);
+    links
+}
+
+/// Gathers the links to items from every impl found for the type at `path`.
+fn impl_links(path: &ModPath, context: &Context) -> DocRelatedItems {
+    let mut links = BTreeMap::new();
+    if let Some(impls) = context.impls_for_ty.get(path) {
+        for impl_ in impls {
+            debug!("Impl found for {}!", path);
+            merge_links(&mut links, impl_.convert(context));
         }
-        let mut links = HashMap::new();
-        links.insert(DocType::StructField, fields);
-        links
+    }
+    links
+}
+
+/// Adds the links in `other` to `links`, keeping any already present in the same category.
+fn merge_links(links: &mut DocRelatedItems, other: DocRelatedItems) {
+    for (doc_type, mut items) in other {
+        links.entry(doc_type).or_insert_with(Vec::new).append(&mut items);
     }
 }
 
+/// Converts the fields of a struct-like item, giving each named field a path under the item.
+fn convert_fields(fields: &[ast::StructField], parent: &ModPath, context: &Context) -> Vec<StructField> {
+    fields.iter().map(|item| {
+        let mut field: StructField = item.convert(context);
+        if let Some(ref ident) = field.ident {
+            field.path = parent.clone();
+            field.path.push_string(ident.clone());
+        }
+        field
+    }).collect()
+}
+
+/// Makes the path of a `use` in `module` absolute. Paths not starting with `self` or `super` are
+/// relative to the crate root, where any `extern crate`s also live, so `use std::fmt` resolves to
+/// `crate::std::fmt`.
+fn resolve_import_path(path: &ModPath, module: &ModPath, crate_name: &str) -> ModPath {
+    let mut resolved = module.clone();
+    for segment in path.segments() {
+        match segment.identifier.as_str() {
+            "{{root}}" => resolved = ModPath::from(crate_name.to_string()),
+            "self"     => (),
+            "super"    => resolved.pop(),
+            _          => resolved.push(segment.clone()),
+        }
+    }
+    resolved
+}
+
+/// Links the documents directly inside the module at `path` by their kind of item.
+fn child_links(path: &ModPath, docs: &[Documentation]) -> DocRelatedItems {
+    let mut links = BTreeMap::new();
+    for doc in docs.iter().filter(|doc| doc.mod_path.parent().as_ref() == Some(path)) {
+        links.entry(doc.get_type()).or_insert(Vec::new()).push(DocLink {
+            name: doc.name.clone(),
+            path: doc.mod_path.clone(),
+        });
+    }
+    links
+}
+
+fn field_links(fields: &[StructField]) -> DocRelatedItems {
+    let field_links = fields.iter()
+        .filter_map(|field| field.ident.clone().map(|ident| {
+            DocLink {
+                // TODO: Display nicely, with signature
+                name: ident,
+                path: field.path.clone(),
+            }
+        }))
+        .collect();
+
+    let mut links = BTreeMap::new();
+    links.insert(DocType::StructField, field_links);
+    links
+}
+
 impl Convert<StructField> for ast::StructField {
     fn convert(&self, context: &Context) -> StructField {
         StructField {
@@ -403,16 +772,38 @@ impl Convert<StructField> for ast::StructField {
 
 impl Convert<Documentation> for ast_ty_wrappers::Enum {
     fn convert(&self, context: &Context) -> Documentation {
+        let mut links: DocRelatedItems = self.variants.convert(context);
+        merge_links(&mut links, impl_links(&self.path, context));
+
         Documentation {
             name: self.ident.convert(context),
             attrs: self.attrs.convert(context),
             mod_path: self.path.clone(),
+            source: context.source_location(&self.path),
             crate_info: context.crate_info.clone(),
-            visibility: Some(Visibility::Inherited),
+            visibility: Some(self.vis.convert(context)),
             inner_data: EnumDoc(Enum {
                 variants: self.variants.convert(context),
+                generics: self.generics.convert(context),
             }),
-            links: self.variants.convert(context),
+            links: links,
+        }
+    }
+}
+
+impl Convert<Variant> for ast::Variant {
+    fn convert(&self, context: &Context) -> Variant {
+        let kind = match self.node.data {
+            ast::VariantData::Struct(ref fields, _) => VariantKind::Struct(fields.convert(context)),
+            ast::VariantData::Tuple(ref fields, _)  => VariantKind::Tuple(fields.convert(context)),
+            ast::VariantData::Unit(_)               => VariantKind::Unit,
+        };
+
+        Variant {
+            name: self.node.name.convert(context),
+            kind: kind,
+            discriminant: self.node.disr_expr.as_ref().map(|expr| expr.convert(context)),
+            attrs: self.node.attrs.convert(context),
         }
     }
 }
@@ -435,15 +826,121 @@ impl Convert<DocRelatedItems> for [ast::Variant] {
             };
             variants.push(variant_link);
         }
-        let mut links = HashMap::new();
+        let mut links = BTreeMap::new();
         links.insert(DocType::Variant, variants);
         links
     }
 }
 
-impl Convert<String> for ast::FnDecl {
+impl Convert<FnHeader> for ast::FnDecl {
+    fn convert(&self, context: &Context) -> FnHeader {
+        let output = match self.output {
+            ast::FunctionRetTy::Default(..) => None,
+            ast::FunctionRetTy::Ty(ref ty) => Some(ast_ty_wrappers::ty_to_string(ty)),
+        };
+        FnHeader::new(self.inputs.convert(context), output, self.variadic)
+    }
+}
+
+impl Convert<(String, String)> for ast::Arg {
+    fn convert(&self, _context: &Context) -> (String, String) {
+        if let Some(explicit_self) = self.to_self() {
+            let pat = match explicit_self.node {
+                ast::SelfKind::Value(ast::Mutability::Mutable) |
+                ast::SelfKind::Explicit(_, ast::Mutability::Mutable) => "mut self",
+                _ => "self",
+            };
+            let ty = match explicit_self.node {
+                ast::SelfKind::Value(..) => "Self".to_string(),
+                ast::SelfKind::Region(ref lifetime, mutability) => {
+                    let lifetime = match *lifetime {
+                        Some(ref lifetime) => format!("{} ", pprust::lifetime_to_string(lifetime)),
+                        None => String::new(),
+                    };
+                    let mutability = match mutability {
+                        ast::Mutability::Mutable => "mut ",
+                        ast::Mutability::Immutable => "",
+                    };
+                    format!("&{}{}Self", lifetime, mutability)
+                }
+                ast::SelfKind::Explicit(ref ty, _) => ast_ty_wrappers::ty_to_string(ty),
+            };
+            return (pat.to_string(), ty);
+        }
+
+        let pat = match self.pat.node {
+            ast::PatKind::Ident(_, ident, _) if ident.node.name == keywords::Invalid.name() => {
+                String::new()
+            }
+            _ => pprust::pat_to_string(&self.pat),
+        };
+        (pat, ast_ty_wrappers::ty_to_string(&self.ty))
+    }
+}
+
+impl Convert<Generics> for ast::Generics {
+    fn convert(&self, context: &Context) -> Generics {
+        Generics {
+            lifetimes: self.lifetimes.convert(context),
+            ty_params: self.ty_params.convert(context),
+            where_predicates: self.where_clause.predicates.convert(context),
+        }
+    }
+}
+
+impl Convert<String> for ast::WherePredicate {
+    fn convert(&self, context: &Context) -> String {
+        match *self {
+            ast::WherePredicate::BoundPredicate(ref pred) => {
+                let bounds: Vec<String> = pred.bounds.convert(context);
+                let mut bounded = ast_ty_wrappers::ty_to_string(&pred.bounded_ty);
+                if !pred.bound_lifetimes.is_empty() {
+                    let lifetimes: Vec<String> = pred.bound_lifetimes.convert(context);
+                    bounded = format!("for<{}> {}", lifetimes.join(", "), bounded);
+                }
+                format!("{}: {}", bounded, bounds.join(" + "))
+            },
+            ast::WherePredicate::RegionPredicate(ref pred) => {
+                let bounds = pred.bounds.iter()
+                    .map(|bound| pprust::lifetime_to_string(bound))
+                    .collect::<Vec<String>>();
+                format!("{}: {}", pprust::lifetime_to_string(&pred.lifetime), bounds.join(" + "))
+            },
+            ast::WherePredicate::EqPredicate(ref pred) => {
+                format!("{} = {}",
+                        ast_ty_wrappers::ty_to_string(&pred.lhs_ty),
+                        ast_ty_wrappers::ty_to_string(&pred.rhs_ty))
+            },
+        }
+    }
+}
+
+impl Convert<String> for ast::LifetimeDef {
+    fn convert(&self, _context: &Context) -> String {
+        let mut lifetime = pprust::lifetime_to_string(&self.lifetime);
+        if !self.bounds.is_empty() {
+            let bounds = self.bounds.iter()
+                .map(|bound| pprust::lifetime_to_string(bound))
+                .collect::<Vec<String>>();
+            lifetime = format!("{}: {}", lifetime, bounds.join(" + "));
+        }
+        lifetime
+    }
+}
+
+impl Convert<TyParam> for ast::TyParam {
+    fn convert(&self, context: &Context) -> TyParam {
+        TyParam {
+            name: self.ident.convert(context),
+            bounds: self.bounds.convert(context),
+            default: self.default.convert(context),
+        }
+    }
+}
+
+impl Convert<String> for ast::TyParamBound {
     fn convert(&self, _context: &Context) -> String {
-        pprust::to_string(|s| s.print_fn_args_and_ret(self))
+        pprust::bounds_to_string(&[self.clone()]).trim().to_string()
     }
 }
 
@@ -472,7 +969,7 @@ impl Convert<String> for ast::Mac {
 }
 
 impl Convert<Attributes> for [ast::Attribute] {
-    fn convert(&self, _context: &Context) -> Attributes {
-        Attributes::from_ast(self)
+    fn convert(&self, context: &Context) -> Attributes {
+        Attributes::from_ast_in(self, |span| context.source_dir(span))
     }
 }
@@ -2,19 +2,52 @@ use conversion::doc_containers::*;
 use document::ModPath;
 use generation::ast_ty_wrappers;
 
-#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+/// The lifetime and type parameters of an item, like `<'a, T: Clone = u32>`.
+#[derive(Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub struct Generics {
+    /// Lifetimes with their bounds, like `'a: 'b`.
+    pub lifetimes: Vec<String>,
+    pub ty_params: Vec<TyParam>,
+    /// The predicates of the `where` clause, like `T: Clone + Send`.
+    pub where_predicates: Vec<String>,
+}
+
+impl Generics {
+    /// Whether there are no parameters to render between angle brackets. The `where` clause is
+    /// not considered.
+    pub fn is_empty(&self) -> bool {
+        self.lifetimes.is_empty() && self.ty_params.is_empty()
+    }
+}
 
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TyParam {
+    pub name: String,
+    pub bounds: Vec<String>,
+    pub default: Option<ast_ty_wrappers::Ty>,
 }
 
+/// The shape of the data an enum variant holds.
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
-pub struct VariantStruct {
-    pub fields: DocRelatedItems,
+pub enum VariantKind {
+    Unit,
+    Tuple(Vec<StructField>),
+    Struct(Vec<StructField>),
+}
+
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Variant {
+    pub name: String,
+    pub kind: VariantKind,
+    /// The explicit discriminant, like `5` in `Qux = 5`.
+    pub discriminant: Option<String>,
+    pub attrs: ast_ty_wrappers::Attributes,
 }
 
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Enum {
-    pub variants: DocRelatedItems,
+    pub variants: Vec<Variant>,
+    pub generics: Generics,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -22,7 +55,104 @@ pub struct MethodSig {
     pub unsafety: Unsafety,
     pub constness: Constness,
     pub abi: Abi,
-    pub header: String,
+    pub header: FnHeader,
+}
+
+/// How a method takes `self`.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Receiver {
+    /// `self` or `mut self`.
+    Value,
+    /// `&self`, with or without a lifetime.
+    Ref,
+    /// `&mut self`, with or without a lifetime.
+    RefMut,
+    /// A receiver with its type written out, like `self: Box<Self>`.
+    Typed,
+}
+
+impl Receiver {
+    /// The receiver taken by an argument with pattern `pat` and type `ty`, if it is one. A
+    /// `self` receiver has its type spelled out as `Self` or `&'a mut Self` and so on.
+    pub fn from_input(pat: &str, ty: &str) -> Option<Receiver> {
+        if pat != "self" && pat != "mut self" {
+            return None;
+        }
+        if ty == "Self" {
+            return Some(Receiver::Value);
+        }
+        if ty == "&Self" || (ty.starts_with('&') && ty.ends_with(" Self")) {
+            let modifiers = &ty[1..ty.len() - "Self".len()];
+            if modifiers.split_whitespace().any(|word| word == "mut") {
+                return Some(Receiver::RefMut);
+            }
+            return Some(Receiver::Ref);
+        }
+        Some(Receiver::Typed)
+    }
+}
+
+/// The arguments and return type of a function.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct FnHeader {
+    /// The pattern and type of each argument, like `("x", "i32")`. A `self` receiver has the
+    /// pattern `self` or `mut self` and its type spelled out, like `&mut Self`. Arguments without
+    /// a name, as in `fn f(u8);` in a trait, have an empty pattern.
+    pub inputs: Vec<(String, String)>,
+    /// The return type, or `None` for `()`.
+    pub output: Option<String>,
+    /// Whether the argument list ends with `...`.
+    pub variadic: bool,
+    /// How the first argument takes `self`, or `None` for functions without a receiver, like
+    /// associated functions such as `new`.
+    pub receiver: Option<Receiver>,
+}
+
+impl FnHeader {
+    pub fn new(inputs: Vec<(String, String)>, output: Option<String>, variadic: bool) -> FnHeader {
+        let receiver = inputs.first().and_then(|&(ref pat, ref ty)| Receiver::from_input(pat, ty));
+        FnHeader {
+            inputs: inputs,
+            output: output,
+            variadic: variadic,
+            receiver: receiver,
+        }
+    }
+
+    /// Whether this is a method taking `self`, rather than an associated function.
+    pub fn is_method(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// The argument and return types without the argument names, like `(&Self, usize) -> bool`.
+    /// Functions returning `()` have no arrow.
+    pub fn type_signature(&self) -> String {
+        let types: Vec<&str> = self.inputs.iter().map(|&(_, ref ty)| ty.as_str()).collect();
+        match self.output {
+            Some(ref output) => format!("({}) -> {}", types.join(", "), output),
+            None => format!("({})", types.join(", ")),
+        }
+    }
+
+    /// Each argument the way it would be written in the signature, like `x: i32` or `&self`.
+    pub fn args(&self) -> Vec<String> {
+        self.inputs.iter().enumerate().map(|(i, &(ref pat, ref ty))| {
+            let receiver = if i == 0 { self.receiver.as_ref() } else { None };
+            match receiver {
+                Some(&Receiver::Value) => return pat.clone(),
+                // Keeps the lifetime, as in `&'a mut self`.
+                Some(&Receiver::Ref) | Some(&Receiver::RefMut) => {
+                    return format!("{}self", &ty[..ty.len() - "Self".len()]);
+                }
+                _ => (),
+            }
+            if pat.is_empty() {
+                ty.clone()
+            } else {
+                format!("{}: {}", pat, ty)
+            }
+        }).collect()
+    }
 }
 
 // There are redundant enums because it isn't possible to derive
@@ -39,11 +169,21 @@ pub enum Constness {
     NotConst,
 }
 
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Mutability {
+    Mutable,
+    Immutable,
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Visibility {
     Public,
     Private,
     Inherited,
+    /// `pub(crate)`
+    Crate,
+    /// `pub(in path)`, or `pub(self)` and `pub(super)`.
+    Restricted(ModPath),
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -73,7 +213,15 @@ pub enum Abi {
 
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Struct {
+    pub kind: ast_ty_wrappers::StructKind,
     pub fields: Vec<StructField>,
+    pub generics: Generics,
+}
+
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Union {
+    pub fields: Vec<StructField>,
+    pub generics: Generics,
 }
 
 // TODO: Should these have their own documentation?
@@ -89,6 +237,20 @@ pub struct StructField {
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Module {
     pub is_crate: bool,
+    pub reexports: Vec<Reexport>,
+}
+
+/// An item made available under another path with `pub use`.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Reexport {
+    /// The full path the item is exported at, like `crate::C` for `pub use a::B as C`. For glob
+    /// re-exports, the path of the exporting module.
+    pub alias: ModPath,
+    /// The full path of the original item, or of the module a glob re-exports from.
+    pub original: ModPath,
+    /// Whether this is a `pub use a::*`. Globs are recorded but not expanded into the names they
+    /// bring in.
+    pub is_glob: bool,
 }
 
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -97,21 +259,49 @@ pub struct Constant {
     pub expr: String,
 }
 
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Static {
+    pub ty: ast_ty_wrappers::Ty,
+    pub mutability: Mutability,
+    /// The initializer, or `None` for a static declared in an `extern` block.
+    pub expr: Option<String>,
+    /// The ABI of the containing `extern` block, for foreign statics.
+    pub abi: Option<Abi>,
+}
+
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Typedef {
+    pub ty: ast_ty_wrappers::Ty,
+    pub generics: Generics,
+}
+
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Macro {
+    /// The matcher of each macro arm, like `($x:expr)`.
+    pub matchers: Vec<String>,
+}
+
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Function {
-    pub header: String,
+    pub header: FnHeader,
     pub generics: Generics,
     pub unsafety: Unsafety,
     pub constness: Constness,
     pub abi: Abi,
     pub kind: ast_ty_wrappers::FnKind,
+    /// Whether the function is declared in an `extern` block rather than defined in Rust.
+    pub is_foreign: bool,
+    /// Whether the function is `async`. The parser predates `async fn`, so this is only set for
+    /// documentation imported from rustdoc's JSON output.
+    pub is_async: bool,
 }
 
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Trait {
     pub unsafety: Unsafety,
-    // pub generics: Generics,
-    // pub bounds: Vec<TyParamBound>,
+    pub generics: Generics,
+    /// Supertraits and lifetime bounds, like `Clone` and `'static` in `trait Foo: Clone + 'static`.
+    pub bounds: Vec<String>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -122,8 +312,11 @@ pub struct TraitItem {
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum TraitItemKind {
     Const(ast_ty_wrappers::Ty, Option<String>),
-    Method(MethodSig),
-    Type(Option<ast_ty_wrappers::Ty>),
+    /// A method, and whether it is provided with a default body.
+    Method(MethodSig, bool),
+    /// The bounds of an associated type, like `Iterator` in `type Item: Iterator;`, and its
+    /// default.
+    Type(Vec<String>, Option<ast_ty_wrappers::Ty>),
     Macro(String),
 }
 
@@ -2,12 +2,15 @@ use std::fmt::{self, Display};
 use std::path::PathBuf;
 use std::slice;
 
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
 use syntax::ast;
 use syntax::codemap::{Span};
 use syntax::print::pprust;
 
 /// Represents a single portion of a full module path.
-#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug, Serialize, Deserialize)]
 pub struct PathSegment {
     /// The identifier portion of this path segment.
     /// Only the string part of the identifier should be needed for the doc.
@@ -26,7 +29,7 @@ impl Display for PathSegment {
 /// Represents a module path, like `std::fmt`. Used for easily resolving crate modules to their
 /// on-disk documentation locations. It should be possible to locate a piece of corresponding
 /// documentation with a complete ModPath.
-#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
 pub struct ModPath(pub Vec<PathSegment>);
 
 impl ModPath {
@@ -93,14 +96,57 @@ impl ModPath {
         ModPath(tail.clone().to_vec())
     }
 
+    /// Whether `other` lies somewhere below this path. A path is not its own ancestor.
+    pub fn is_ancestor_of(&self, other: &ModPath) -> bool {
+        self.0.len() < other.0.len() && other.0.starts_with(&self.0)
+    }
+
+    /// Whether `other` lies directly below this path.
+    pub fn is_parent_of(&self, other: &ModPath) -> bool {
+        self.0.len() + 1 == other.0.len() && other.0.starts_with(&self.0)
+    }
+
+    /// Whether this path ends with all the segments of `suffix`, like `std::collections::HashMap`
+    /// ends with `collections::HashMap`. Every path ends with the empty path.
+    pub fn ends_with(&self, suffix: &ModPath) -> bool {
+        self.0.ends_with(&suffix.0)
+    }
+
+    /// The paths out of `paths` that lie directly below this one, in order and without duplicates.
+    pub fn children<'a, I>(&self, paths: I) -> Vec<ModPath>
+        where I: IntoIterator<Item = &'a ModPath>
+    {
+        let mut children: Vec<ModPath> = paths.into_iter()
+            .filter(|path| self.is_parent_of(path))
+            .cloned()
+            .collect();
+        children.sort();
+        children.dedup();
+        children
+    }
+
     pub fn join(first: &ModPath, other: &ModPath) -> ModPath {
         let mut result = first.clone();
         result.0.extend(other.0.iter().cloned());
         result
     }
 
+    /// The segments of the path joined with `sep`, like `std/fmt` for `/`.
+    pub fn join_with(&self, sep: &str) -> String {
+        self.0.iter().map(|seg| seg.identifier.as_str()).collect::<Vec<&str>>().join(sep)
+    }
+
+    /// A form of the path that can be used as a file name, with the segments escaped by
+    /// `escape_file_segment` and joined with `.`, like `std.fmt.Display`.
+    pub fn file_stem(&self) -> String {
+        self.0.iter()
+            .map(|seg| escape_file_segment(&seg.identifier))
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+
     pub fn to_filepath(&self) -> PathBuf {
-        self.0.iter().fold(PathBuf::new(), |res, s| res.join(s.identifier.clone()))
+        self.0.iter().fold(PathBuf::new(), |res, s| res.join(escape_file_segment(&s.identifier)))
     }
 
     pub fn segments(&self) -> slice::Iter<PathSegment> {
@@ -108,6 +154,22 @@ impl ModPath {
     }
 }
 
+/// Escapes the characters of a path segment that can't appear in file names on some platforms,
+/// along with `.` and `%`, as `%` followed by their hex code. Plain identifiers are unchanged.
+pub fn escape_file_segment(segment: &str) -> String {
+    let mut escaped = String::new();
+    for c in segment.chars() {
+        match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' | '.' | '%' => {
+                escaped.push_str(&format!("%{:02X}", c as u32))
+            }
+            c if c.is_control() => escaped.push_str(&format!("%{:02X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 impl From<String> for ModPath {
     fn from(s: String) -> ModPath {
         ModPath(s.split("::").map(|s| PathSegment { identifier: s.to_string() }).collect::<Vec<PathSegment>>())
@@ -128,9 +190,41 @@ impl From<ast::Path> for ModPath {
 
 impl Display for ModPath {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let s = self.0.iter().map(|i| i.identifier.clone()).collect::<Vec<String>>().join("::");
+        write!(f, "{}", self.join_with("::"))
+    }
+}
 
-        write!(f, "{}", s)
+// Module paths are stored as their `a::b::c` form so they stay readable in exported data.
+impl Serialize for ModPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ModPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ModPath, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s.is_empty() {
+            Ok(ModPath::new())
+        } else {
+            Ok(ModPath::from(s))
+        }
+    }
+}
+
+/// The place in the crate's source where an item is defined.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SourceLocation {
+    pub file: String,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 0-based column, in characters.
+    pub col: usize,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
     }
 }
 
@@ -138,18 +232,28 @@ impl Display for ModPath {
 #[derive(Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
 pub struct CrateInfo {
     pub name: String,
+    /// The version from the package metadata, or empty if it doesn't give one.
     pub version: String,
     pub lib_path: Option<String>,
 }
 
 impl CrateInfo {
     pub fn to_path_prefix(&self) -> PathBuf {
-        PathBuf::from(format!("{}-{}", self.name, self.version))
+        if self.version.is_empty() {
+            PathBuf::from(&self.name)
+        } else {
+            PathBuf::from(format!("{}-{}", self.name, self.version))
+        }
     }
 }
 
+/// Shows the name and version like `serde 1.0.130`, or just the name if there is no version.
 impl Display for CrateInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}-{}", self.name, self.version)
+        if self.version.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{} {}", self.name, self.version)
+        }
     }
 }
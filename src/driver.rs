@@ -20,7 +20,7 @@ impl Driver {
     }
 
     pub fn get_doc(location: &StoreLocation) -> Result<Documentation> {
-        let path = location.to_filepath();
+        let path = location.to_filepath()?;
         store::deserialize_object(path)
     }
 }
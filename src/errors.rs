@@ -25,5 +25,14 @@ error_chain! {
         NameEncodingError(name: String) {
             description("Failed to encode name: {}")
         }
+        InconsistentStore(problems: Vec<String>) {
+            description("documentation store is inconsistent")
+            display("Documentation store is inconsistent:\n{}", problems.join("\n"))
+        }
+        StoreVersionMismatch(found: u32, expected: u32) {
+            description("documentation store has an unsupported version")
+            display("Documentation store has version {}, but version {} is required. \
+                     Regenerate the documentation with `oxidoc -g`.", found, expected)
+        }
     }
 }
@@ -1,10 +1,16 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::{slice, vec};
 
 use syntax::ast;
 use syntax::abi;
+use syntax::codemap::Span;
 use syntax::print::pprust;
 
+use code_blocks::LineClassifier;
 use document::ModPath;
 
 // FIXME: Duplication from librustdoc
@@ -63,39 +69,152 @@ impl<I: IntoIterator<Item=ast::NestedMetaItem>> NestedAttributesExt for I {
     }
 }
 
+/// Attributes read into their own fields of `Attributes`, and so left out of `other_attrs`.
+const DISPLAYED_ATTRS: &[&str] = &["doc", "deprecated", "rustc_deprecated", "stable", "unstable",
+                                   "must_use", "cfg"];
+
+/// Joins the hints of several `#[repr(...)]` attributes into the first, so `#[repr(C)]` and
+/// `#[repr(packed)]` show as `#[repr(C, packed)]`.
+fn merge_repr_attrs(attrs: Vec<String>) -> Vec<String> {
+    fn repr_hints(attr: &str) -> Option<&str> {
+        let attr = attr.trim();
+        if attr.starts_with("#[repr(") && attr.ends_with(")]") {
+            Some(attr["#[repr(".len()..attr.len() - ")]".len()].trim())
+        } else {
+            None
+        }
+    }
+
+    let hints: Vec<&str> = attrs.iter().filter_map(|attr| repr_hints(attr)).collect();
+    if hints.len() < 2 {
+        return attrs;
+    }
+
+    let merged = format!("#[repr({})]", hints.join(", "));
+    let mut result = Vec::new();
+    let mut seen_repr = false;
+    for attr in &attrs {
+        if repr_hints(attr).is_none() {
+            result.push(attr.clone());
+        } else if !seen_repr {
+            result.push(merged.clone());
+            seen_repr = true;
+        }
+    }
+    result
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Attributes {
     pub doc_strings: Vec<String>,
+    pub deprecation: Option<Deprecation>,
+    pub stability: Option<Stability>,
+    /// The conditions under which the item is compiled, from its `#[cfg]` attributes.
+    pub cfg: Option<Cfg>,
+    /// The message from a `#[must_use]` attribute, which is empty if none was given.
+    pub must_use: Option<String>,
+    /// Other names for the item, from `#[doc(alias = "...")]`.
+    pub aliases: Vec<String>,
+    /// The outer attributes not kept anywhere else, like `#[repr(C)]`, in source order.
+    pub other_attrs: Vec<String>,
 }
 
 impl Attributes {
     pub fn new() -> Attributes {
         Attributes {
             doc_strings: Vec::new(),
+            deprecation: None,
+            stability: None,
+            cfg: None,
+            must_use: None,
+            aliases: Vec::new(),
+            other_attrs: Vec::new(),
         }
     }
 
     pub fn from_ast(attrs: &[ast::Attribute]) -> Attributes {
-        let mut doc_strings = vec![];
+        Attributes::from_ast_in(attrs, |_| None)
+    }
+
+    /// Like `from_ast`, but reads the files named by `#[doc(include = "...")]` relative to the
+    /// directory `source_dir` gives for the attribute's span.
+    pub fn from_ast_in<F>(attrs: &[ast::Attribute], source_dir: F) -> Attributes
+        where F: Fn(Span) -> Option<PathBuf>
+    {
+        // Each doc string, and whether it came from a `///` or `/** */` comment.
+        let mut fragments = vec![];
         let mut sp = None;
         let other_attrs: Vec<ast::Attribute> = attrs.iter().filter_map(|attr| {
+            let is_sugared = attr.is_sugared_doc;
             attr.with_desugared_doc(|attr| {
+                if !attr.check_name("doc") {
+                    return Some(attr.clone());
+                }
+
                 if let Some(value) = attr.value_str() {
-                    if attr.check_name("doc") {
-                        doc_strings.push(value.to_string());
-                        if sp.is_none() {
-                            sp = Some(attr.span);
-                        }
-                        return None;
+                    fragments.push((value.to_string(), is_sugared));
+                } else if let Some(path) = doc_include_path(attr) {
+                    let dir = source_dir(attr.span);
+                    match read_doc_include(&path, dir.as_ref().map(|dir| dir.as_path())) {
+                        Some(contents) => fragments.push((contents, false)),
+                        None => return None,
                     }
+                } else {
+                    return Some(attr.clone());
                 }
 
-                Some(attr.clone())
+                if sp.is_none() {
+                    sp = Some(attr.span);
+                }
+                None
             })
         }).collect();
+        let other_attrs = other_attrs.iter()
+            .filter(|attr| attr.style == ast::AttrStyle::Outer)
+            .filter(|attr| !DISPLAYED_ATTRS.iter().any(|name| attr.check_name(name)))
+            .map(pprust::attribute_to_string)
+            .collect::<Vec<_>>();
+        let other_attrs = merge_repr_attrs(other_attrs);
+
+        let deprecation = attrs.iter()
+            .find(|attr| attr.check_name("deprecated") || attr.check_name("rustc_deprecated"))
+            .map(Deprecation::from_ast);
+
+        let stability = attrs.iter()
+            .filter_map(Stability::from_ast)
+            .next();
+
+        let must_use = attrs.iter()
+            .find(|attr| attr.check_name("must_use"))
+            .map(|attr| attr.value_str().map(|s| s.to_string()).unwrap_or_default());
+
+        let aliases = attrs.lists("doc")
+            .filter(|item| item.check_name("alias"))
+            .filter_map(|item| item.value_str().map(|s| s.to_string()))
+            .collect();
+
+        // Several `#[cfg]` attributes on one item must all hold.
+        let mut cfgs: Vec<Cfg> = attrs.iter()
+            .filter(|attr| attr.check_name("cfg"))
+            .filter_map(|attr| attr.meta())
+            .filter_map(|meta| meta.meta_item_list()
+                        .and_then(|list| list.first().and_then(|item| item.meta_item()))
+                        .and_then(Cfg::from_meta))
+            .collect();
+        let cfg = match cfgs.len() {
+            0 => None,
+            1 => cfgs.pop(),
+            _ => Some(Cfg::All(cfgs)),
+        };
+
         Attributes {
-            doc_strings: doc_strings,
-            //other_attrs: other_attrs,
+            doc_strings: unindent_doc_fragments(fragments),
+            deprecation: deprecation,
+            stability: stability,
+            cfg: cfg,
+            must_use: must_use,
+            aliases: aliases,
+            other_attrs: other_attrs,
         }
     }
 
@@ -104,6 +223,248 @@ impl Attributes {
     pub fn doc_value<'a>(&'a self) -> Option<&'a str> {
         self.doc_strings.first().map(|s| &s[..])
     }
+
+    /// The first sentence of the docs as plain text. Headings and code blocks before the first
+    /// paragraph are skipped. Items without docs have an empty summary.
+    pub fn summary(&self) -> String {
+        let docs = self.doc_strings.join("\n");
+        let mut paragraph = Vec::new();
+        let mut classifier = LineClassifier::new();
+
+        for line in docs.lines() {
+            let trimmed = line.trim();
+            if classifier.classify(line).is_code() {
+                if !paragraph.is_empty() {
+                    break;
+                }
+                continue;
+            }
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                if !paragraph.is_empty() {
+                    break;
+                }
+                continue;
+            }
+            paragraph.push(trimmed);
+        }
+
+        first_sentence(&strip_markdown(&paragraph.join(" ")))
+    }
+}
+
+/// The path in a `#[doc(include = "...")]` attribute.
+fn doc_include_path(attr: &ast::Attribute) -> Option<String> {
+    attr.meta_item_list()
+        .and_then(|items| items.iter()
+                  .find(|item| item.check_name("include"))
+                  .and_then(|item| item.value_str()))
+        .map(|path| path.to_string())
+}
+
+/// Reads the contents of a file included with `#[doc(include)]`. Missing files are skipped.
+fn read_doc_include(path: &str, source_dir: Option<&Path>) -> Option<String> {
+    let full_path = match source_dir {
+        Some(dir) => dir.join(path),
+        None => PathBuf::from(path),
+    };
+
+    let mut contents = String::new();
+    match File::open(&full_path).and_then(|mut file| file.read_to_string(&mut contents)) {
+        Ok(_) => Some(contents),
+        Err(e) => {
+            warn!("Could not include doc file {}: {}", full_path.display(), e);
+            None
+        }
+    }
+}
+
+fn indent_width(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+/// Removes the indentation shared by every non-blank line of an item's doc strings, like the
+/// space after `///`, so indented code inside stays indented relative to the text around it.
+/// Blank lines are emptied. As in rustdoc, when comments are mixed with `#[doc = "..."]`
+/// attributes, the attributes are treated as one column further in, since they have no space
+/// after a comment marker to lose.
+fn unindent_doc_fragments(fragments: Vec<(String, bool)>) -> Vec<String> {
+    let mixed = fragments.iter().any(|&(_, sugared)| sugared) &&
+        fragments.iter().any(|&(_, sugared)| !sugared);
+    let extra = |sugared: bool| if mixed && !sugared { 1 } else { 0 };
+
+    let indent = fragments.iter()
+        .flat_map(|&(ref doc, sugared)| doc.lines().map(move |line| (line, sugared)))
+        .filter(|&(line, _)| !line.trim().is_empty())
+        .map(|(line, sugared)| indent_width(line) + extra(sugared))
+        .min()
+        .unwrap_or(0);
+
+    fragments.iter()
+        .map(|&(ref doc, sugared)| {
+            let indent = indent.saturating_sub(extra(sugared));
+            doc.lines()
+                .map(|line| match line.char_indices().nth(indent) {
+                    _ if line.trim().is_empty() => "",
+                    Some((start, _)) => &line[start..],
+                    None => "",
+                })
+                .collect::<Vec<&str>>()
+                .join("\n")
+        })
+        .collect()
+}
+
+/// The contents of a `#[deprecated]` or `#[rustc_deprecated]` attribute. Both fields are absent
+/// for a bare `#[deprecated]`.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Deprecation {
+    pub since: Option<String>,
+    pub note: Option<String>,
+}
+
+impl Deprecation {
+    pub fn from_ast(attr: &ast::Attribute) -> Deprecation {
+        let mut deprecation = Deprecation {
+            since: None,
+            note: None,
+        };
+
+        // #[deprecated = "note"]
+        if let Some(note) = attr.value_str() {
+            deprecation.note = Some(note.to_string());
+        }
+
+        // #[deprecated(since = "1.0.0", note = "note")]
+        // #[rustc_deprecated(since = "1.0.0", reason = "note")]
+        for item in attr.meta_item_list().unwrap_or_default() {
+            if item.check_name("since") {
+                deprecation.since = item.value_str().map(|s| s.to_string());
+            } else if item.check_name("note") || item.check_name("reason") {
+                deprecation.note = item.value_str().map(|s| s.to_string());
+            }
+        }
+
+        deprecation
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum StabilityLevel {
+    Stable,
+    Unstable,
+}
+
+/// The contents of a `#[stable]` or `#[unstable]` attribute, as used by the standard library.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Stability {
+    pub level: StabilityLevel,
+    pub feature: Option<String>,
+    pub since: Option<String>,
+}
+
+impl Stability {
+    /// Parses the attribute if it is a stability attribute.
+    pub fn from_ast(attr: &ast::Attribute) -> Option<Stability> {
+        let level = if attr.check_name("stable") {
+            StabilityLevel::Stable
+        } else if attr.check_name("unstable") {
+            StabilityLevel::Unstable
+        } else {
+            return None;
+        };
+
+        let mut stability = Stability {
+            level: level,
+            feature: None,
+            since: None,
+        };
+
+        for item in attr.meta_item_list().unwrap_or_default() {
+            if item.check_name("feature") {
+                stability.feature = item.value_str().map(|s| s.to_string());
+            } else if item.check_name("since") {
+                stability.since = item.value_str().map(|s| s.to_string());
+            }
+        }
+
+        Some(stability)
+    }
+}
+
+/// A predicate from a `#[cfg(...)]` attribute.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Cfg {
+    /// A plain name, like `unix` or `test`.
+    Name(String),
+    /// A name with a value, like `feature = "net"`.
+    NameValue(String, String),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+}
+
+impl Cfg {
+    /// Parses the predicate inside a `#[cfg]` attribute. Returns `None` if it is malformed.
+    pub fn from_meta(meta: &ast::MetaItem) -> Option<Cfg> {
+        let name = meta.name().to_string();
+
+        if let Some(value) = meta.value_str() {
+            return Some(Cfg::NameValue(name, value.to_string()));
+        }
+        if meta.is_word() {
+            return Some(Cfg::Name(name));
+        }
+
+        let items = match meta.meta_item_list() {
+            Some(items) => items,
+            None => return None,
+        };
+        let mut preds = Vec::new();
+        for item in items {
+            match item.meta_item().and_then(Cfg::from_meta) {
+                Some(pred) => preds.push(pred),
+                None => return None,
+            }
+        }
+
+        match &name[..] {
+            "all" => Some(Cfg::All(preds)),
+            "any" => Some(Cfg::Any(preds)),
+            "not" if preds.len() == 1 => preds.pop().map(|pred| Cfg::Not(Box::new(pred))),
+            _ => None,
+        }
+    }
+
+    fn fmt_nested(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Cfg::All(ref preds) | Cfg::Any(ref preds) if preds.len() > 1 => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl fmt::Display for Cfg {
+    /// Writes the predicate as readable text, like `unix and feature "net"`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (preds, sep) = match *self {
+            Cfg::Name(ref name) => return write!(f, "{}", name),
+            Cfg::NameValue(ref name, ref value) => return write!(f, "{} \"{}\"", name, value),
+            Cfg::Not(ref pred) => {
+                write!(f, "not ")?;
+                return pred.fmt_nested(f);
+            }
+            Cfg::All(ref preds) => (preds, " and "),
+            Cfg::Any(ref preds) => (preds, " or "),
+        };
+
+        for (i, pred) in preds.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", sep)?;
+            }
+            pred.fmt_nested(f)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -117,7 +478,19 @@ pub struct Struct {
     pub ident: ast::Ident,
     pub id: NodeId,
     pub vis: ast::Visibility,
+    pub kind: StructKind,
+    pub fields: Vec<ast::StructField>,
+    pub generics: ast::Generics,
+    pub attrs: Vec<ast::Attribute>,
+    pub path: ModPath,
+}
+
+#[derive(Clone, Debug)]
+pub struct Union {
+    pub ident: ast::Ident,
+    pub vis: ast::Visibility,
     pub fields: Vec<ast::StructField>,
+    pub generics: ast::Generics,
     pub attrs: Vec<ast::Attribute>,
     pub path: ModPath,
 }
@@ -128,7 +501,7 @@ pub struct Function {
     pub unsafety: ast::Unsafety,
     pub constness: ast::Constness,
     pub decl: ast::FnDecl,
-    // TODO: Generics
+    pub generics: ast::Generics,
     pub vis: ast::Visibility,
     pub abi: abi::Abi,
     pub attrs: Vec<ast::Attribute>,
@@ -142,10 +515,15 @@ pub struct Module {
     pub vis: ast::Visibility,
     pub imports: Vec<Import>,
     pub structs: Vec<Struct>,
+    pub unions: Vec<Union>,
     pub fns: Vec<Function>,
     pub mods: Vec<Module>,
     pub consts: Vec<Constant>,
     pub enums: Vec<Enum>,
+    pub typedefs: Vec<Typedef>,
+    pub statics: Vec<Static>,
+    pub foreigns: Vec<ForeignItem>,
+    pub macros: Vec<Macro>,
     pub impls: Vec<Impl>,
     pub traits: Vec<Trait>,
     pub def_traits: Vec<DefaultImpl>,
@@ -166,10 +544,15 @@ impl Module {
             attrs:      Vec::new(),
             imports:    Vec::new(),
             structs:    Vec::new(),
+            unions:     Vec::new(),
             fns:        Vec::new(),
             mods:       Vec::new(),
             consts:     Vec::new(),
             enums:      Vec::new(),
+            typedefs:   Vec::new(),
+            statics:    Vec::new(),
+            foreigns:   Vec::new(),
+            macros:     Vec::new(),
             impls:      Vec::new(),
             traits:     Vec::new(),
             def_traits: Vec::new(),
@@ -188,6 +571,22 @@ impl Module {
         self.namespaces_to_paths.insert(identifier, namespace);
     }
 
+    /// Records a `use` of `original` under the name `ident`, or of everything in `original` if
+    /// `ident` is `None`.
+    pub fn add_import(&mut self,
+                      ident: Option<&ast::Ident>,
+                      original: ModPath) {
+        let path = match ident {
+            Some(ident) => self.path.append_ident(*ident),
+            None        => self.path.clone(),
+        };
+        self.imports.push(Import {
+            path: path,
+            original: original,
+            is_glob: ident.is_none(),
+        });
+    }
+
     pub fn resolve_use(&self, namespaced_path: &ModPath) -> Option<ModPath> {
         let ident = namespaced_path.head()
             .expect("Given path was empty!").identifier;
@@ -203,6 +602,8 @@ pub struct Trait {
     pub items: Vec<TraitItem>,
     pub ident: ast::Ident,
     pub unsafety: ast::Unsafety,
+    pub generics: ast::Generics,
+    pub bounds: ast::TyParamBounds,
     pub vis: ast::Visibility,
     pub attrs: Vec<ast::Attribute>,
     pub path: ModPath,
@@ -219,6 +620,7 @@ pub struct Enum {
     pub ident: ast::Ident,
     pub vis: ast::Visibility,
     pub variants: Vec<ast::Variant>,
+    pub generics: ast::Generics,
     pub attrs: Vec<ast::Attribute>,
     pub path: ModPath,
 }
@@ -241,6 +643,48 @@ pub struct Constant {
     pub path: ModPath,
 }
 
+#[derive(Clone, Debug)]
+pub struct Static {
+    pub type_: Ty,
+    pub mutability: ast::Mutability,
+    pub expr: ast::Expr,
+    pub ident: ast::Ident,
+    pub vis: ast::Visibility,
+    pub attrs: Vec<ast::Attribute>,
+    pub path: ModPath,
+}
+
+/// A function or static declared in an `extern` block.
+#[derive(Clone, Debug)]
+pub struct ForeignItem {
+    pub ident: ast::Ident,
+    pub node: ast::ForeignItemKind,
+    /// The ABI of the containing `extern` block.
+    pub abi: abi::Abi,
+    pub vis: ast::Visibility,
+    pub attrs: Vec<ast::Attribute>,
+    pub path: ModPath,
+}
+
+#[derive(Clone, Debug)]
+pub struct Typedef {
+    pub ident: ast::Ident,
+    pub ty: ast::Ty,
+    pub generics: ast::Generics,
+    pub vis: ast::Visibility,
+    pub attrs: Vec<ast::Attribute>,
+    pub path: ModPath,
+}
+
+#[derive(Clone, Debug)]
+pub struct Macro {
+    pub ident: ast::Ident,
+    pub def: ast::MacroDef,
+    pub vis: ast::Visibility,
+    pub attrs: Vec<ast::Attribute>,
+    pub path: ModPath,
+}
+
 #[derive(Clone, Debug)]
 pub struct Impl {
     pub unsafety: ast::Unsafety,
@@ -249,7 +693,11 @@ pub struct Impl {
     pub for_: ast::Ty,
     pub items: Vec<ast::ImplItem>,
     pub attrs: Vec<ast::Attribute>,
+    /// The module containing the impl, replaced with the path of the implemented type once it is
+    /// resolved, or with the type's path as written if it is from outside the crate.
     pub path: ModPath,
+    /// The path of the implemented trait, if it could be resolved within the crate.
+    pub trait_path: Option<ModPath>,
 }
 
 #[derive(Clone, Debug)]
@@ -259,9 +707,15 @@ pub struct DefaultImpl {
     pub attrs: Vec<ast::Attribute>,
 }
 
+/// A name brought into a module with `use`.
 #[derive(Clone, Debug)]
 pub struct Import {
-    pub path: ast::ViewPath,
+    /// The full path of the name in the importing module, like `crate::C` for `use a::B as C`.
+    /// For glob imports, the path of the importing module itself.
+    pub path: ModPath,
+    /// The imported path as written, like `{{root}}::a::B` or `self::b`.
+    pub original: ModPath,
+    pub is_glob: bool,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
@@ -271,6 +725,27 @@ pub enum FnKind {
     MethodFromTrait,
 }
 
+/// How the fields of a struct are declared.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum StructKind {
+    /// `struct Name { field: T }`
+    Named,
+    /// `struct Name(T);`
+    Tuple,
+    /// `struct Name;`
+    Unit,
+}
+
+impl<'a> From<&'a ast::VariantData> for StructKind {
+    fn from(data: &'a ast::VariantData) -> StructKind {
+        match *data {
+            ast::VariantData::Struct(..) => StructKind::Named,
+            ast::VariantData::Tuple(..)  => StructKind::Tuple,
+            ast::VariantData::Unit(..)   => StructKind::Unit,
+        }
+    }
+}
+
 // These structs have importance in the initial AST visit, because all impls for
 // types have to be resolved by conversion. There could be types whose
 // implementation lives in another module.
@@ -290,12 +765,101 @@ pub struct Ty {
     pub name: String,
 }
 
+impl Ty {
+    /// A type known only by how it is written, with no AST node behind it.
+    pub fn from_name(name: String) -> Ty {
+        Ty {
+            id: NodeId::from(ast::DUMMY_NODE_ID),
+            name: name,
+        }
+    }
+}
+
+/// Prints a type on one line. `pprust` breaks long types like `fn` pointers over several lines
+/// and pads trait objects, printing `Box< Fn()>`, so the whitespace is tidied up after it.
+pub fn ty_to_string(ty: &ast::Ty) -> String {
+    tidy_whitespace(&pprust::ty_to_string(ty))
+}
+
+/// Puts code on one line, with single spaces between words but none just inside brackets or
+/// before commas, like `Box<Fn(u8, u8)>`.
+pub fn tidy_whitespace(printed: &str) -> String {
+    let mut result = String::with_capacity(printed.len());
+    let mut words = printed.split_whitespace().peekable();
+
+    while let Some(word) = words.next() {
+        result.push_str(word);
+        let opens = word.ends_with(|c: char| "<([".contains(c));
+        let closes = words.peek()
+            .map_or(true, |next| next.starts_with(|c: char| ">)],".contains(c)));
+        if !opens && !closes {
+            result.push(' ');
+        }
+    }
+    result
+}
+
 impl From<ast::Ty> for Ty {
     fn from(ty: ast::Ty) -> Self{
         Ty {
             id: NodeId::from(ty.id),
-            name: pprust::ty_to_string(&ty),
+            name: ty_to_string(&ty),
         }
     }
 }
 
+/// Removes inline markdown from a line of text: code span backticks, emphasis and link targets.
+fn strip_markdown(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut plain = String::new();
+    let mut in_code = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if c == '`' {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            plain.push(c);
+            continue;
+        }
+
+        match c {
+            '*' => (),
+            // Underscores inside words, like in `snake_case`, aren't emphasis.
+            '_' if i >= 2 && i < chars.len() && chars[i - 2].is_alphanumeric() &&
+                chars[i].is_alphanumeric() => plain.push(c),
+            '_' => (),
+            '[' => (),
+            // Drop the target of `[text](url)` and `[text][ref]` links.
+            ']' if i < chars.len() && (chars[i] == '(' || chars[i] == '[') => {
+                let close = if chars[i] == '(' { ')' } else { ']' };
+                if let Some(n) = chars[i..].iter().position(|&c| c == close) {
+                    i += n + 1;
+                }
+            }
+            ']' => (),
+            c => plain.push(c),
+        }
+    }
+
+    plain
+}
+
+/// The text up to and including the first period that ends a sentence.
+fn first_sentence(text: &str) -> String {
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_end = match chars.peek() {
+            Some(&(_, next)) => next.is_whitespace(),
+            None => true,
+        };
+        if c == '.' && at_end {
+            return text[..i + 1].to_string();
+        }
+    }
+    text.to_string()
+}
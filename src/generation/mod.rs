@@ -5,17 +5,23 @@ pub mod visitor;
 use std;
 use std::env;
 use std::path::{Path, PathBuf};
-use std::fs::{read_dir, remove_dir_all};
+use std::fs::{read_dir, remove_dir_all, File};
+use std::io::Read;
+use std::rc::Rc;
 
-use store::Store;
+use store::{Store, StoreFormat};
 use syntax::ast;
 use syntax::diagnostics::plugin::DiagnosticBuilder;
+use syntax::errors::{ColorConfig, Handler};
 use syntax::parse::{self, ParseSess};
-use syntax::codemap::FilePathMapping;
+use syntax::codemap::{CodeMap, FilePathMapping};
 
 use document::*;
 use conversion::{Convert, Context, Documentation};
+use intra_links;
+use see_also;
 use paths;
+use rustdoc_json;
 use store::Docset;
 use toml_util;
 use self::visitor::OxidocVisitor;
@@ -108,7 +114,9 @@ fn get_crate_info(crate_path: &PathBuf) -> Result<CrateInfo> {
 
     let info = CrateInfo {
         name: toml_util::get_toml_value(&toml_table, "package", "name")?,
-        version: toml_util::get_toml_value(&toml_table, "package", "version")?,
+        // Workspace members can inherit their version, which isn't read here.
+        version: toml_util::get_toml_value(&toml_table, "package", "version")
+            .unwrap_or_default(),
         lib_path: toml_util::get_toml_value(&toml_table, "lib", "path").ok(),
     };
 
@@ -122,7 +130,12 @@ fn cache_doc_for_crate(crate_path: &PathBuf) -> Result<()> {
 
     println!("Generating documentation for {}", &info);
 
-    let krate = match parse_crate(crate_path, &info) {
+    // The codemap outlives the parse session so `#[doc(include)]` paths can be resolved later.
+    let codemap = Rc::new(CodeMap::new(FilePathMapping::empty()));
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(codemap.clone()));
+    let parse_session = ParseSess::with_span_handler(handler, codemap.clone());
+
+    let krate = match parse_crate(crate_path, &info, &parse_session) {
         Ok(k) => k,
         Err(_) => {
             println!("No crate entry point found \
@@ -131,15 +144,16 @@ fn cache_doc_for_crate(crate_path: &PathBuf) -> Result<()> {
         }
     };
 
-    let mut store = generate_doc_cache(krate, info)
+    let mut store = generate_doc_cache(krate, info, Some(codemap))
         .chain_err(|| "Failed to generate doc cache")?;
 
     store.save()
         .chain_err(|| "Couldn't save oxidoc data for module")
 }
 
-fn parse_crate(crate_path: &PathBuf, crate_info: &CrateInfo) -> Result<ast::Crate> {
-    let parse_session = ParseSess::new(FilePathMapping::empty());
+fn parse_crate(crate_path: &PathBuf,
+               crate_info: &CrateInfo,
+               parse_session: &ParseSess) -> Result<ast::Crate> {
     let lib_path = crate_info.lib_path.clone().unwrap_or("src/lib.rs".to_string());
 
     // TODO: This has to handle multiple [[bin]] targets.
@@ -152,7 +166,7 @@ fn parse_crate(crate_path: &PathBuf, crate_info: &CrateInfo) -> Result<ast::Crat
         }
     }
 
-    let krate = match parse_crate_from_path(main_path.as_path(), &parse_session) {
+    let krate = match parse_crate_from_path(main_path.as_path(), parse_session) {
         Ok(k) => k,
         Err(e) => bail!(ErrorKind::CrateParseError(crate_info.name.clone(), format!("{:?}", e))),
     };
@@ -160,37 +174,98 @@ fn parse_crate(crate_path: &PathBuf, crate_info: &CrateInfo) -> Result<ast::Crat
     Ok(krate)
 }
 
+/// Options for converting a crate's items into documentation.
+#[derive(Clone, Default)]
+pub struct ConvertOptions {
+    /// Keep items marked `#[doc(hidden)]`.
+    pub include_hidden: bool,
+    /// Document private items along with public ones.
+    pub document_private_items: bool,
+    /// The crate's source files, used to record where each item is defined.
+    pub codemap: Option<Rc<CodeMap>>,
+}
+
 pub fn generate_crate_docs(krate: ast::Crate, crate_info: CrateInfo) -> Result<Vec<Documentation>> {
+    generate_crate_docs_with(krate, crate_info, &ConvertOptions::default())
+}
+
+/// Like `generate_crate_docs`, but converts the crate with the given options.
+pub fn generate_crate_docs_with(krate: ast::Crate,
+                                crate_info: CrateInfo,
+                                options: &ConvertOptions) -> Result<Vec<Documentation>> {
     let crate_doc_path = paths::crate_doc_path(&crate_info)
         .chain_err(|| format!("Unable to get crate doc path for crate: {}",
                               &crate_info.name))?;
 
     let mut v = OxidocVisitor::new(crate_info.clone());
     v.visit_crate(krate);
-    let context = Context::new(crate_doc_path.clone(),
-                               crate_info,
-                               v.impls_for_ty.clone());
+    let mut context = Context::new(crate_doc_path.clone(),
+                                   crate_info,
+                                   v.impls_for_ty.clone(),
+                                   v.impls_for_trait.clone(),
+                                   v.hidden_paths.clone());
+    context.include_hidden = options.include_hidden;
+    context.private_paths = v.private_paths.clone();
+    context.document_private_items = options.document_private_items;
+    context.codemap = options.codemap.clone();
+    context.spans = v.spans.clone();
     Ok(v.convert(&context))
 }
 
 pub fn make_docset(documents: Vec<Documentation>) -> Result<Docset> {
-    for doc in &documents {
-        debug!("p: {}", doc.mod_path);
-        doc.save()?;
-    }
-
     let mut docset = Docset::new();
-    docset.add_docs(documents)?;
+    docset.add_docs(documents);
 
     Ok(docset)
 }
 
-/// Generates documentation for the given crate.
-pub fn generate_doc_cache(krate: ast::Crate, crate_info: CrateInfo) -> Result<Store> {
-    let documents = generate_crate_docs(krate, crate_info.clone())?;
+/// Generates documentation for the given crate. `codemap` holds the crate's source files, if they
+/// are still around.
+pub fn generate_doc_cache(krate: ast::Crate,
+                          crate_info: CrateInfo,
+                          codemap: Option<Rc<CodeMap>>) -> Result<Store> {
+    let options = ConvertOptions { codemap: codemap, ..ConvertOptions::default() };
+    let mut documents = generate_crate_docs_with(krate, crate_info.clone(), &options)?;
+
+    let mut store = load_store_for_generation()?;
+    intra_links::resolve_intra_links(&mut documents, &store);
+    see_also::add_see_also(&mut documents);
+
     let docset = make_docset(documents)?;
+    store.add_docset(crate_info, docset);
+    store.save()?;
+
+    Ok(store)
+}
+
+/// Loads the store that generated documentation is added to. A store written by another version
+/// of the format can't be read, so it is replaced by an empty one, which is what regenerating is
+/// for. Any other problem loading it is an error.
+fn load_store_for_generation() -> Result<Store> {
+    match Store::load() {
+        Err(Error(ErrorKind::StoreVersionMismatch(found, expected), _)) => {
+            warn!("Replacing documentation store with version {} by one with version {}",
+                  found, expected);
+            Ok(Store::new(StoreFormat::default()))
+        },
+        result => result,
+    }
+}
+
+/// Like `generate_doc_cache`, but reads the crate's items from a file written by
+/// `rustdoc --output-format json` instead of parsing its source.
+pub fn generate_doc_cache_from_rustdoc_json(json_path: &Path, crate_info: CrateInfo) -> Result<Store> {
+    let mut json = String::new();
+    File::open(json_path)
+        .and_then(|mut file| file.read_to_string(&mut json))
+        .chain_err(|| format!("Failed to read file {}", json_path.display()))?;
+    let mut documents = rustdoc_json::convert_rustdoc_json(&json, crate_info.clone())?;
 
-    let mut store = Store::load();
+    let mut store = load_store_for_generation()?;
+    intra_links::resolve_intra_links(&mut documents, &store);
+    see_also::add_see_also(&mut documents);
+
+    let docset = make_docset(documents)?;
     store.add_docset(crate_info, docset);
     store.save()?;
 
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use syntax::abi;
 use syntax::ast;
+use syntax::codemap::Span;
 use syntax::print::pprust;
 use syntax::symbol::keywords;
 
@@ -21,6 +22,13 @@ pub struct OxidocVisitor {
     pub crate_module: Module,
     pub name_for_ty: HashMap<NodeId, ast::Ident>,
     pub impls_for_ty: HashMap<ModPath, Vec<Impl>>,
+    pub impls_for_trait: HashMap<ModPath, Vec<Impl>>,
+    /// Paths of items marked `#[doc(hidden)]`. Everything under these paths is hidden as well.
+    pub hidden_paths: HashSet<ModPath>,
+    /// Paths of items that aren't public. Everything under these paths is private as well.
+    pub private_paths: HashSet<ModPath>,
+    /// Where each visited item is defined in the source.
+    pub spans: HashMap<ModPath, Span>,
 }
 
 impl OxidocVisitor {
@@ -31,6 +39,10 @@ impl OxidocVisitor {
             crate_info: crate_info,
             name_for_ty: HashMap::new(),
             impls_for_ty: HashMap::new(),
+            impls_for_trait: HashMap::new(),
+            hidden_paths: HashSet::new(),
+            private_paths: HashSet::new(),
+            spans: HashMap::new(),
         }
     }
 
@@ -45,30 +57,35 @@ impl OxidocVisitor {
         }
     }
 
-    fn visit_impl_method(&self, item: &ast::ImplItem, for_path: &ModPath, sig: &ast::MethodSig) -> Function {
+    fn visit_impl_method(&self, item: &ast::ImplItem, for_path: &ModPath, sig: &ast::MethodSig,
+                         kind: FnKind) -> Function {
         // In this case, the final segment of the ModPath is used as the type
         // the item is implemented on.
         Function {
             ident: item.ident,
             decl: (*sig.decl).clone(),
+            generics: sig.generics.clone(),
             unsafety: sig.unsafety.clone(),
             constness: sig.constness.node.clone(),
             vis: item.vis.clone(),
             abi: sig.abi.clone(),
             attrs: item.attrs.clone(),
-            kind: FnKind::MethodFromImpl,
+            kind: kind,
             path: for_path.append_ident(item.ident),
         }
     }
 
-    fn visit_impl_item(&self, module: &mut Module, item: &ast::ImplItem, for_path: &ModPath) {
+    /// Adds an item of an impl block on the type at `for_path`. Methods get `kind`, which tells
+    /// inherent methods apart from trait methods.
+    fn visit_impl_item(&self, module: &mut Module, item: &ast::ImplItem, for_path: &ModPath,
+                       kind: &FnKind) {
         match item.node {
             ast::ImplItemKind::Const(ref ty, ref expr) => {
                 let c = self.visit_impl_const(item, for_path, ty, expr);
                 module.consts.push(c);
             },
             ast::ImplItemKind::Method(ref sig, _) => {
-                let f = self.visit_impl_method(item, for_path, sig);
+                let f = self.visit_impl_method(item, for_path, sig, kind.clone());
                 module.fns.push(f);
             },
             // TODO: Handle types and macros
@@ -77,28 +94,55 @@ impl OxidocVisitor {
         }
     }
 
-    fn add_impl(&mut self, module: &mut Module, imp: Impl) {
+    fn add_impl(&mut self, module: &mut Module, mut imp: Impl) {
+        let mut found_ty = false;
+
         if let ast::TyKind::Path(_, path) = imp.for_.node.clone() {
             let namespaced_path = ModPath::from(path.clone());
             if let Some(full_path) = module.resolve_use(&namespaced_path) {
                 debug!("Full path for {}: {}", namespaced_path, full_path);
+                let kind = if imp.trait_.is_some() {
+                    FnKind::MethodFromTrait
+                } else {
+                    FnKind::MethodFromImpl
+                };
                 for item in &imp.items {
-                    self.visit_impl_item(module, &item, &full_path);
+                    if is_doc_hidden(&imp.attrs) || is_doc_hidden(&item.attrs) {
+                        self.hidden_paths.insert(full_path.append_ident(item.ident));
+                    }
+                    self.spans.insert(full_path.append_ident(item.ident), item.span);
+                    self.visit_impl_item(module, &item, &full_path, &kind);
                 }
-                self.impls_for_ty.entry(full_path.clone()).or_insert(Vec::new()).push(imp);
+                imp.path = full_path;
+                found_ty = true;
             } else {
                 debug!("No type found for impl {}", namespaced_path);
+                imp.path = namespaced_path;
+            }
+        }
+
+        if let Some(trait_ref) = imp.trait_.clone() {
+            let trait_path = ModPath::from(trait_ref.path);
+            if let Some(full_path) = module.resolve_use(&trait_path) {
+                debug!("Impl of trait {} for {}", full_path, pprust::ty_to_string(&imp.for_));
+                imp.trait_path = Some(full_path.clone());
+                self.impls_for_trait.entry(full_path).or_insert(Vec::new()).push(imp.clone());
             }
         }
+
+        if found_ty {
+            self.impls_for_ty.entry(imp.path.clone()).or_insert(Vec::new()).push(imp);
+        }
     }
 
     fn visit_enum_def(&self, item: &ast::Item,
                       enum_def: &ast::EnumDef,
-                      _generics: &ast::Generics) -> Enum {
+                      generics: &ast::Generics) -> Enum {
         Enum {
             ident: item.ident,
             vis: item.vis.clone(),
             variants: enum_def.variants.clone(),
+            generics: generics.clone(),
             attrs: item.attrs.clone(),
             path: self.current_scope.append_ident(item.ident),
         }
@@ -109,10 +153,11 @@ impl OxidocVisitor {
                 ast_unsafety: ast::Unsafety,
                 ast_constness: ast::Constness,
                 ast_abi: abi::Abi,
-                _generics: &ast::Generics) -> Function {
+                generics: &ast::Generics) -> Function {
         Function {
             ident: item.ident,
             decl: fn_decl.clone(),
+            generics: generics.clone(),
             unsafety: ast_unsafety,
             constness: ast_constness,
             vis: item.vis.clone(),
@@ -137,23 +182,91 @@ impl OxidocVisitor {
         }
     }
 
+    fn visit_static(&self, item: &ast::Item,
+                    ast_ty: &ast::Ty,
+                    ast_mutability: ast::Mutability,
+                    ast_expr: &ast::Expr,
+    ) -> Static {
+        Static {
+            ident: item.ident,
+            type_: Ty::from(ast_ty.clone()),
+            mutability: ast_mutability,
+            expr:  ast_expr.clone(),
+            vis: item.vis.clone(),
+            attrs: item.attrs.clone(),
+            path: self.current_scope.append_ident(item.ident),
+        }
+    }
+
+    fn visit_foreign_item(&self, item: &ast::ForeignItem,
+                          ast_abi: abi::Abi) -> ForeignItem {
+        ForeignItem {
+            ident: item.ident,
+            node: item.node.clone(),
+            abi: ast_abi,
+            vis: item.vis.clone(),
+            attrs: item.attrs.clone(),
+            path: self.current_scope.append_ident(item.ident),
+        }
+    }
+
+    fn visit_typedef(&self, item: &ast::Item,
+                     ast_ty: &ast::Ty,
+                     ast_generics: &ast::Generics) -> Typedef {
+        Typedef {
+            ident: item.ident,
+            ty: ast_ty.clone(),
+            generics: ast_generics.clone(),
+            vis: item.vis.clone(),
+            attrs: item.attrs.clone(),
+            path: self.current_scope.append_ident(item.ident),
+        }
+    }
+
+    fn visit_macro_def(&self, item: &ast::Item,
+                       def: &ast::MacroDef) -> Macro {
+        Macro {
+            ident: item.ident,
+            def: def.clone(),
+            vis: item.vis.clone(),
+            attrs: item.attrs.clone(),
+            path: self.current_scope.append_ident(item.ident),
+        }
+    }
+
     fn visit_struct(&self, item: &ast::Item,
                     variant_data: &ast::VariantData,
-                    _ast_generics: &ast::Generics) -> Struct {
+                    ast_generics: &ast::Generics) -> Struct {
         Struct {
             ident: item.ident,
             id: NodeId::from(item.id),
             vis: item.vis.clone(),
+            kind: StructKind::from(variant_data),
             fields: variant_data.fields().iter().cloned().collect(),
+            generics: ast_generics.clone(),
             attrs: item.attrs.clone(),
             path: self.current_scope.append_ident(item.ident),
         }
 
     }
 
+    fn visit_union(&self, item: &ast::Item,
+                   variant_data: &ast::VariantData,
+                   ast_generics: &ast::Generics) -> Union {
+        Union {
+            ident: item.ident,
+            vis: item.vis.clone(),
+            fields: variant_data.fields().iter().cloned().collect(),
+            generics: ast_generics.clone(),
+            attrs: item.attrs.clone(),
+            path: self.current_scope.append_ident(item.ident),
+        }
+    }
+
     fn visit_trait(&self, item: &ast::Item,
                    ast_unsafety: ast::Unsafety,
-                   _ast_generics: &ast::Generics,
+                   ast_generics: &ast::Generics,
+                   ast_bounds: &ast::TyParamBounds,
                    trait_items: &Vec<ast::TraitItem>) -> Trait {
         Trait {
             items: trait_items.iter().cloned().map(|ti| {
@@ -167,6 +280,8 @@ impl OxidocVisitor {
             }).collect(),
             ident: item.ident,
             unsafety: ast_unsafety,
+            generics: ast_generics.clone(),
+            bounds: ast_bounds.clone(),
             vis: item.vis.clone(),
             attrs: item.attrs.clone(),
             path: self.current_scope.append_ident(item.ident),
@@ -186,7 +301,8 @@ impl OxidocVisitor {
             for_: ast_ty.clone(),
             items: items.clone(),
             attrs: item.attrs.clone(),
-            path: self.current_scope.append_ident(item.ident)
+            path: self.current_scope.append_ident(item.ident),
+            trait_path: None,
         }
     }
 
@@ -208,8 +324,11 @@ impl OxidocVisitor {
         match import.node {
             ast::ViewPath_::ViewPathSimple(ident, ref path) => {
                 module.add_use(&ident, ModPath::from(path.clone()));
+                module.add_import(Some(&ident), ModPath::from(path.clone()));
             },
             ast::ViewPath_::ViewPathGlob(ref path) => {
+                module.add_import(None, ModPath::from(path.clone()));
+
                 // FIXME: Get all the keywords for this namespace. One would
                 // have to look into stores of dependencies that are already
                 // saved and get the list of namespaces there.
@@ -243,7 +362,8 @@ impl OxidocVisitor {
                         ModPath::join(&ModPath::from(path.clone()),
                                       &ModPath::from(ident))
                     };
-                    module.add_use(&ident, full_path);
+                    module.add_use(&ident, full_path.clone());
+                    module.add_import(Some(&ident), full_path);
                 }
             }
         }
@@ -266,8 +386,9 @@ impl OxidocVisitor {
                 module.fns.push(f);
             },
             ast::ItemKind::Mod(ref mod_) => {
-                let m = self.visit_module(item.attrs.clone(),
-                                          mod_, Some(item.ident));
+                let mut m = self.visit_module(item.attrs.clone(),
+                                              mod_, Some(item.ident));
+                m.vis = item.vis.clone();
                 module.mods.push(m);
             },
             ast::ItemKind::Enum(ref def, ref generics) => {
@@ -284,13 +405,21 @@ impl OxidocVisitor {
                 module.structs.push(s);
             },
             ast::ItemKind::Union(ref variant_data, ref generics) => {
-                // TODO when unions become stable?
+                let u = self.visit_union(item,
+                                         variant_data,
+                                         generics);
+                module.add_use(&item.ident, u.path.clone());
+                module.unions.push(u);
             },
             ast::ItemKind::Trait(unsafety, ref generics,
                                  ref param_bounds, ref trait_items) => {
                 let t = self.visit_trait(item,
                                          unsafety, generics,
-                                         trait_items);
+                                         param_bounds, trait_items);
+                for (trait_item, ast_item) in t.items.iter().zip(trait_items) {
+                    self.spans.insert(trait_item.path.clone(), ast_item.span);
+                }
+                module.add_use(&item.ident, t.path.clone());
                 module.traits.push(t);
             },
             ast::ItemKind::DefaultImpl(unsafety, ref trait_ref) => {
@@ -306,13 +435,35 @@ impl OxidocVisitor {
                                         ty, items);
                 module.impls.push(i);
             },
-            ast::ItemKind::Ty(..) |
-            ast::ItemKind::Static(..) |
+            ast::ItemKind::Ty(ref ty, ref generics) => {
+                let t = self.visit_typedef(item, ty, generics);
+                module.typedefs.push(t);
+            },
+            ast::ItemKind::Static(ref ty, mutability, ref expr) => {
+                let s = self.visit_static(item, ty, mutability, expr);
+                module.statics.push(s);
+            },
+            ast::ItemKind::ForeignMod(ref foreign_mod) => {
+                for foreign_item in &foreign_mod.items {
+                    if foreign_item.vis != ast::Visibility::Public {
+                        self.private_paths.insert(self.current_scope.append_ident(foreign_item.ident));
+                    }
+                    if is_doc_hidden(&foreign_item.attrs) {
+                        self.hidden_paths.insert(self.current_scope.append_ident(foreign_item.ident));
+                    }
+                    self.spans.insert(self.current_scope.append_ident(foreign_item.ident),
+                                      foreign_item.span);
+                    let f = self.visit_foreign_item(foreign_item, foreign_mod.abi);
+                    module.foreigns.push(f);
+                }
+            },
             ast::ItemKind::Mac(..) |
-            ast::ItemKind::ExternCrate(..) |
-            ast::ItemKind::ForeignMod(..) => (),
+            ast::ItemKind::ExternCrate(..) => (),
             ast::ItemKind::GlobalAsm(..) => (),
-            ast::ItemKind::MacroDef(..) => (),
+            ast::ItemKind::MacroDef(ref def) => {
+                let m = self.visit_macro_def(item, def);
+                module.macros.push(m);
+            },
         }
     }
 
@@ -327,9 +478,17 @@ impl OxidocVisitor {
         module.path = self.current_scope.clone();
 
         for item in &m.items {
-            if should_visit_item(&item) {
-                self.visit_item(item, &mut module);
+            if is_doc_hidden(&item.attrs) {
+                self.hidden_paths.insert(self.current_scope.append_ident(item.ident));
             }
+            if !should_visit_item(&item) {
+                continue;
+            }
+            if !is_public_item(&item) {
+                self.private_paths.insert(self.current_scope.append_ident(item.ident));
+            }
+            self.spans.insert(self.current_scope.append_ident(item.ident), item.span);
+            self.visit_item(item, &mut module);
         }
 
         self.current_scope.pop();
@@ -343,6 +502,7 @@ impl OxidocVisitor {
 
     pub fn visit_crate(&mut self, krate: ast::Crate) {
         debug!("visiting crate");
+        self.spans.insert(ModPath::from(self.crate_info.name.clone()), krate.span);
         self.crate_module = self.visit_module(krate.attrs.clone(),
                                               &krate.module,
                                               None);
@@ -350,23 +510,33 @@ impl OxidocVisitor {
     }
 }
 
+/// Whether an item is needed for the documentation. Private items are kept so they can be
+/// documented on request, and are recorded in `private_paths` by the caller. Private imports
+/// aren't, so they aren't taken for re-exports.
 fn should_visit_item(item: &ast::Item) -> bool {
-    // TODO: Until "pub use" works, public reexports may not be visited, so just visit all modules
-    // to find them.
-    let is_module = match item.node {
-        ast::ItemKind::Mod(..) => true,
-        _ => false,
-    };
-
-    let is_hidden = item.attrs.lists("doc").has_word("hidden");
+    match item.node {
+        ast::ItemKind::Use(..) => item.vis == ast::Visibility::Public,
+        _ => true,
+    }
+}
 
-    // methods in impls inherit the visibility of the parent
-    let is_public = match item.node {
+/// Whether an item can be seen from outside its crate.
+fn is_public_item(item: &ast::Item) -> bool {
+    match item.node {
+        // methods in impls inherit the visibility of the parent
         ast::ItemKind::Impl(..) => true,
+        // items in extern blocks have their own visibility
+        ast::ItemKind::ForeignMod(..) => true,
+        // macro_rules! macros are public only when exported
+        ast::ItemKind::MacroDef(ref def) if def.legacy => {
+            item.attrs.iter().any(|attr| attr.check_name("macro_export"))
+        },
         _ => item.vis == ast::Visibility::Public,
-    };
+    }
+}
 
-    !is_hidden && (is_module || is_public)
+fn is_doc_hidden(attrs: &[ast::Attribute]) -> bool {
+    attrs.lists("doc").has_word("hidden")
 }
 
 fn current_module_scope(visitor: &OxidocVisitor, mod_name: Option<ast::Ident>) -> String {
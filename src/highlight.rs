@@ -0,0 +1,100 @@
+//! A small Rust highlighter for code examples shown in the terminal. It only knows enough of the
+//! language to pick out keywords, literals, comments and type names.
+
+use theme::{ColorSupport, Theme, ThemeStyle};
+
+const KEYWORDS: [&str; 38] = ["as", "break", "const", "continue", "crate", "else", "enum",
+                              "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop",
+                              "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+                              "Self", "static", "struct", "super", "trait", "true", "type",
+                              "unsafe", "use", "where", "while", "dyn", "union", "macro_rules"];
+
+const PRIMITIVES: [&str; 17] = ["bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize",
+                                "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64"];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Token {
+    Keyword,
+    Str,
+    Comment,
+    Type,
+    Plain,
+}
+
+/// Splits `code` into tokens, keeping every character so the pieces join back into `code`.
+fn tokenize(code: &str) -> Vec<(Token, &str)> {
+    let bytes = code.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let token = if code[i..].starts_with("//") {
+            i = code[i..].find('\n').map(|n| i + n).unwrap_or(bytes.len());
+            Token::Comment
+        } else if code[i..].starts_with("/*") {
+            i = code[i + 2..].find("*/").map(|n| i + 2 + n + 2).unwrap_or(bytes.len());
+            Token::Comment
+        } else if bytes[i] == b'"' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(bytes.len());
+            Token::Str
+        } else if is_ident_start(bytes[i]) {
+            while i < bytes.len() && is_ident_continue(bytes[i]) {
+                i += 1;
+            }
+            classify_word(&code[start..i])
+        } else {
+            // Step over a whole character, since the code may not be ASCII.
+            i += code[i..].chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+            Token::Plain
+        };
+
+        tokens.push((token, &code[start..i]));
+    }
+
+    tokens
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b == b'_' || (b as char).is_ascii_alphabetic()
+}
+
+fn is_ident_continue(b: u8) -> bool {
+    b == b'_' || (b as char).is_ascii_alphanumeric()
+}
+
+fn classify_word(word: &str) -> Token {
+    if KEYWORDS.contains(&word) {
+        Token::Keyword
+    } else if PRIMITIVES.contains(&word) || word.starts_with(|c: char| c.is_uppercase()) {
+        Token::Type
+    } else {
+        Token::Plain
+    }
+}
+
+fn token_style(theme: &Theme, token: Token) -> Option<&ThemeStyle> {
+    match token {
+        Token::Keyword => Some(&theme.keyword),
+        Token::Str => Some(&theme.string),
+        Token::Comment => Some(&theme.comment),
+        Token::Type => Some(&theme.ty),
+        Token::Plain => None,
+    }
+}
+
+/// Colors the keywords, strings, comments and type names in a piece of Rust code.
+pub fn highlight_rust(code: &str, theme: &Theme, support: ColorSupport) -> String {
+    tokenize(code).into_iter()
+        .map(|(token, text)| match token_style(theme, token) {
+            Some(style) if *style != ThemeStyle::plain() => {
+                style.paint(text.to_string(), Some(support))
+            }
+            _ => text.to_string(),
+        })
+        .collect()
+}
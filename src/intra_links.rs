@@ -0,0 +1,174 @@
+//! Finding and resolving intra-doc links, like ``[`Vec`]`` or `[push](Vec::push)`, in doc strings.
+
+use code_blocks::LineClassifier;
+use conversion::{DocLink, DocType, Documentation};
+use document::ModPath;
+use store::Store;
+
+/// A link in a doc string whose target is a Rust path rather than a URL.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntraLink {
+    /// Byte range of the whole link in the doc string.
+    pub start: usize,
+    pub end: usize,
+    /// The text shown for the link, like `` `Vec` ``.
+    pub text: String,
+    /// The path as written, like `Vec` or `crate::bar`.
+    pub target: String,
+    /// Whether the link is written as just `[text]`, with the path taken from the text.
+    pub shortcut: bool,
+}
+
+/// Whether `s` reads as a Rust path, optionally followed by `()` or `!`.
+fn is_path(s: &str) -> bool {
+    let s = s.trim_right_matches("()").trim_right_matches('!');
+    !s.is_empty() && s.split("::").all(|segment| {
+        let mut chars = segment.chars();
+        match chars.next() {
+            Some(c) if c == '_' || c.is_alphabetic() => {
+                chars.all(|c| c == '_' || c.is_alphanumeric())
+            }
+            _ => false,
+        }
+    })
+}
+
+fn strip_backticks(s: &str) -> &str {
+    s.trim_matches('`')
+}
+
+/// Finds the intra-doc links in a markdown doc string, skipping code blocks.
+pub fn find_intra_links(md: &str) -> Vec<IntraLink> {
+    let mut links = Vec::new();
+    let mut classifier = LineClassifier::new();
+    let mut offset = 0;
+
+    for line in md.split('\n') {
+        if !classifier.classify(line).is_code() {
+            find_in_line(line, offset, &mut links);
+        }
+        offset += line.len() + 1;
+    }
+
+    links
+}
+
+fn find_in_line(line: &str, offset: usize, links: &mut Vec<IntraLink>) {
+    let mut pos = 0;
+    while let Some(open) = line[pos..].find('[').map(|i| pos + i) {
+        // Brackets inside inline code, like `v[i]`, aren't links.
+        if line[..open].matches('`').count() % 2 == 1 {
+            pos = open + 1;
+            continue;
+        }
+        let close = match line[open + 1..].find(']') {
+            Some(i) => open + 1 + i,
+            None => return,
+        };
+        let text = &line[open + 1..close];
+        let rest = &line[close + 1..];
+
+        let (target, end, shortcut) = if rest.starts_with('(') {
+            match rest.find(')') {
+                Some(i) => (&rest[1..i], close + 1 + i + 1, false),
+                None => return,
+            }
+        } else if rest.starts_with('[') || rest.starts_with(':') {
+            // Reference-style links and their definitions point at URLs.
+            pos = close + 1;
+            continue;
+        } else {
+            (strip_backticks(text), close + 1, true)
+        };
+
+        if is_path(target) {
+            links.push(IntraLink {
+                start: offset + open,
+                end: offset + end,
+                text: text.to_string(),
+                target: target.to_string(),
+                shortcut: shortcut,
+            });
+        }
+        pos = end;
+    }
+}
+
+/// Resolves the path of an intra-doc link written in the documentation of `doc`. Paths are looked
+/// up among `docs` first, then in the store.
+fn resolve(target: &str, doc: &Documentation, docs: &[ModPath], store: &Store) -> Option<ModPath> {
+    let target = target.trim_right_matches("()").trim_right_matches('!');
+    let target = if target.starts_with("crate::") {
+        format!("{}{}", doc.crate_info.name, &target["crate".len()..])
+    } else {
+        target.to_string()
+    };
+    let suffix = format!("::{}", target);
+
+    let local = docs.iter()
+        .filter(|path| {
+            let path = path.to_string();
+            path == target || path.ends_with(&suffix)
+        })
+        .min_by_key(|path| path.0.len());
+    if let Some(path) = local {
+        return Some(path.clone());
+    }
+
+    store.lookup_name(&target).into_iter()
+        .map(|location| &location.mod_path)
+        .find(|path| {
+            let path = path.to_string();
+            path == target || path.ends_with(&suffix)
+        })
+        .cloned()
+}
+
+/// Records the targets of the intra-doc links in each document's doc strings in its links, under
+/// `DocType::IntraDocLink`. Links that can't be resolved are left out.
+pub fn resolve_intra_links(docs: &mut [Documentation], store: &Store) {
+    let paths: Vec<ModPath> = docs.iter().map(|doc| doc.mod_path.clone()).collect();
+
+    for doc in docs.iter_mut() {
+        let mut resolved = Vec::new();
+        for link in find_intra_links(&doc.attrs.doc_strings.join("\n")) {
+            match resolve(&link.target, doc, &paths, store) {
+                Some(path) => {
+                    let link = DocLink { name: link.target, path: path };
+                    if !resolved.contains(&link) {
+                        resolved.push(link);
+                    }
+                }
+                None => debug!("Unresolved link to {} in {}", link.target, doc.mod_path),
+            }
+        }
+
+        if !resolved.is_empty() {
+            doc.links.insert(DocType::IntraDocLink, resolved);
+        }
+    }
+}
+
+/// Rewrites the intra-doc links in `md` to point at the resolved paths in `resolved`. Links to a
+/// path that wasn't resolved become plain text, except for shortcut links like `[note]`, which
+/// are left as written since they're as likely to be prose.
+pub fn rewrite_intra_links(md: &str, resolved: &[DocLink]) -> String {
+    let mut result = String::new();
+    let mut last = 0;
+
+    for link in find_intra_links(md) {
+        result.push_str(&md[last..link.start]);
+        match resolved.iter().find(|resolved| resolved.name == link.target) {
+            Some(resolved) => result.push_str(&format!("[{}]({})", link.text, resolved.path)),
+            None if link.shortcut => {
+                debug!("Leaving unresolved link [{}] as written", link.text);
+                result.push_str(&md[link.start..link.end]);
+            }
+            None => result.push_str(&link.text),
+        }
+        last = link.end;
+    }
+
+    result.push_str(&md[last..]);
+    result
+}
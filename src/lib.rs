@@ -12,8 +12,11 @@ extern crate ansi_term;
 extern crate bincode;
 extern crate cursive;
 extern crate env_logger;
+extern crate flate2;
+extern crate pulldown_cmark;
 extern crate regex;
 extern crate serde;
+extern crate serde_json;
 extern crate strsim;
 extern crate syntex_syntax as syntax;
 extern crate toml;
@@ -23,13 +26,21 @@ extern crate catmark;
 #[cfg(unix)]
 extern crate pager;
 
+pub mod code_blocks;
 pub mod conversion;
 pub mod generation;
+pub mod intra_links;
+pub mod highlight;
+pub mod logging;
 pub mod document;
 pub mod driver;
 pub mod markup;
+pub mod rustdoc_json;
+pub mod see_also;
 pub mod store;
+pub mod table;
+pub mod theme;
 mod toml_util;
 pub mod tui;
 pub mod errors;
-mod paths;
+pub mod paths;
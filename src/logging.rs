@@ -0,0 +1,41 @@
+//! Setting up logging, once at startup, at the verbosity chosen on the command line.
+
+use std::env;
+
+use env_logger::LogBuilder;
+use log::LogLevelFilter;
+
+use errors::*;
+
+/// How much is logged.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verbosity {
+    /// Only errors.
+    Quiet,
+    /// Errors and warnings.
+    Normal,
+    /// Everything down to debug messages.
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn level_filter(&self) -> LogLevelFilter {
+        match *self {
+            Verbosity::Quiet => LogLevelFilter::Error,
+            Verbosity::Normal => LogLevelFilter::Warn,
+            Verbosity::Verbose => LogLevelFilter::Debug,
+        }
+    }
+}
+
+/// Starts logging to stderr at `verbosity`. Filters in `RUST_LOG`, if set, take precedence. Fails
+/// if a logger was already set up.
+pub fn init(verbosity: Verbosity) -> Result<()> {
+    let mut builder = LogBuilder::new();
+    builder.filter(None, verbosity.level_filter());
+    if let Ok(filters) = env::var("RUST_LOG") {
+        builder.parse(&filters);
+    }
+
+    builder.init().chain_err(|| "Could not set up logging")
+}
@@ -8,7 +8,6 @@ extern crate clap;
 extern crate ansi_term;
 extern crate bincode;
 extern crate cursive;
-extern crate env_logger;
 extern crate regex;
 extern crate serde;
 extern crate syntex_syntax as syntax;
@@ -19,13 +18,15 @@ extern crate oxidoc;
 
 use std::path::PathBuf;
 
-use clap::{App, Arg};
+use clap::{App, Arg, ArgMatches};
 use oxidoc::driver::Driver;
 use oxidoc::generation;
+use oxidoc::logging::{self, Verbosity};
 use oxidoc::errors::*;
-use oxidoc::store::StoreLocation;
-use oxidoc::markup::Format;
+use oxidoc::store::{self, StoreLocation};
+use oxidoc::markup::{self, Format, FormatOptions};
 use oxidoc::store::Store;
+use oxidoc::theme::{self, Theme};
 
 #[cfg(unix)]
 extern crate pager;
@@ -55,15 +56,49 @@ fn app<'a, 'b>() -> App<'a, 'b> {
                 .alias("generate"),
         )
         .arg(Arg::with_name("pager").short("p").long("pager").help(
-            "Automatically pages output",
+            "Pages output that doesn't fit on the terminal",
+        ))
+        .arg(Arg::with_name("no-example-first").long("no-example-first").help(
+            "Leaves the first code example inside the documentation body",
+        ))
+        .arg(Arg::with_name("no-color").long("no-color").help(
+            "Prints documentation without colors or styling",
+        ))
+        .arg(Arg::with_name("ascii").long("ascii").help(
+            "Draws table borders and other decorations with ASCII characters only",
+        ))
+        .arg(
+            Arg::with_name("theme")
+                .long("theme")
+                .value_name("THEME")
+                .help("Colors to print documentation with")
+                .possible_values(&theme::THEME_NAMES)
+                .takes_value(true),
+        )
+        .arg(Arg::with_name("quiet").short("q").long("quiet").help(
+            "Only logs errors",
+        ))
+        .arg(Arg::with_name("verbose").short("v").long("verbose").conflicts_with("quiet").help(
+            "Logs debug messages",
         ))
         .arg(Arg::with_name("query").index(1))
 }
 
+fn verbosity(matches: &ArgMatches) -> Verbosity {
+    if matches.is_present("quiet") {
+        Verbosity::Quiet
+    } else if matches.is_present("verbose") {
+        Verbosity::Verbose
+    } else {
+        Verbosity::Normal
+    }
+}
+
 fn main() {
-    env_logger::init().unwrap();
+    let matches = app().get_matches();
+    logging::init(verbosity(&matches)).unwrap();
 
-    if let Err(ref e) = run() {
+    if let Err(ref e) = run(&matches) {
         error!("error: {}", e);
 
         for e in e.iter().skip(1) {
@@ -88,8 +123,7 @@ fn generate(arg: Option<&str>) -> Result<()> {
     }
 }
 
-fn run() -> Result<()> {
-    let matches = app().get_matches();
+fn run(matches: &ArgMatches) -> Result<()> {
     if matches.is_present("version") {
         println!("oxidoc {}", crate_version!());
         return Ok(());
@@ -108,7 +142,17 @@ fn run() -> Result<()> {
         };
 
         let enable_pager = matches.is_present("pager");
-        print_search_query(query, enable_pager)
+        let options = FormatOptions {
+            example_first: !matches.is_present("no-example-first"),
+            ..FormatOptions::default()
+        };
+        let mut theme = matches.value_of("theme")
+            .and_then(Theme::by_name)
+            .unwrap_or_else(theme::active_theme);
+        theme.ascii = matches.is_present("ascii") || markup::ascii_enabled();
+        theme::set_active_theme(theme);
+        let color = !matches.is_present("no-color") && markup::color_enabled();
+        print_search_query(query, enable_pager, &options, color)
     }
 }
 
@@ -117,33 +161,23 @@ fn setup_pager() {}
 
 #[cfg(unix)]
 fn setup_pager() {
-    let executable = get_pager_executable();
+    let pager = std::env::var("PAGER").ok();
+    let executable = markup::pager_command(pager.as_ref().map(|s| s.as_str()));
 
     Pager::with_pager(&executable).setup();
 }
 
-#[cfg(unix)]
-fn get_pager_executable() -> String {
-    if let Ok(pager) = std::env::var("PAGER") {
-        return pager.to_string();
-    }
-
-    // Linux and BSD systems doesn't support "-r" option but macOS supports
-    // For linux `less` shows better results (with control chars)
-    #[cfg(target_os = "macos")]
-    let executable = "more -r";
-
-    #[cfg(not(target_os = "macos"))]
-    let executable = "less";
-
-    return executable.to_string();
-}
-
-fn print_search_query(query: &str, enable_pager: bool) -> Result<()> {
-    let store = Store::load();
+fn print_search_query(query: &str,
+                      enable_pager: bool,
+                      options: &FormatOptions,
+                      color: bool) -> Result<()> {
+    let store = Store::load()?;
     // search::add_search_paths(store.all_locations());
 
-    let results: Vec<&StoreLocation> = store.lookup_name(query).into_iter().take(10).collect();
+    let results: Vec<&StoreLocation> = match store::parse_query(query) {
+        (Some(doc_type), text) => store.search_by_type(&text, doc_type),
+        (None, _) => store.lookup_name(query),
+    }.into_iter().take(10).collect();
 
     if results.is_empty() {
         println!("No results for \"{}\".", query);
@@ -155,17 +189,16 @@ fn print_search_query(query: &str, enable_pager: bool) -> Result<()> {
         .map(|location| {
             let result = Driver::get_doc(&location).unwrap();
 
-            result.format().to_string()
+            result.format_with(options).render(color)
         })
         .collect();
 
-    if enable_pager {
+    let output = formatted.join("\n");
+    if enable_pager && markup::should_page(&output, &markup::Stdout) {
         setup_pager();
     }
 
-    for result in formatted {
-        println!("{}", result);
-    }
+    println!("{}", output);
 
     Ok(())
 }
@@ -1,146 +1,1175 @@
+use std::env;
 use std::fmt;
+use std::u16;
 
-use ansi_term::Style;
 use catmark::{self, OutputKind};
+use code_blocks::{LineClassifier, LineKind};
+use pulldown_cmark::{html, Parser};
 use conversion::*;
 use document::ModPath;
-use generation::ast_ty_wrappers::{FnKind, Attributes};
+use highlight;
+use intra_links;
+use table;
+use generation::ast_ty_wrappers::{FnKind, StructKind, Attributes, Stability, StabilityLevel};
 use term_size;
+use theme::{self, ColorSupport, Theme, ThemeStyle};
 
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Markup {
     Header(String),
     Section(String),
     Block(String),
+    /// An item's signature, set in code style. The visibility, like `pub `, is kept apart to be
+    /// styled on its own, and is empty for items with inherited visibility.
+    Signature(String, String),
     Markdown(String),
+    /// Rows of plain text cells under a header, laid out in aligned columns. Rows with fewer
+    /// cells than the header are padded with empty ones, and extra cells are dropped.
+    Table { headers: Vec<String>, rows: Vec<Vec<String>> },
     Rule(usize),
     LineBreak,
 }
 
 use self::Markup::*;
 
-fn get_term_width() -> u16 {
-    match term_size::dimensions() {
-        Some((w, _)) => w as u16,
-        None => 80,
+/// Environment variable overriding the width documentation is wrapped to.
+pub const WIDTH_VAR: &str = "OXIDOC_WIDTH";
+
+const DEFAULT_WIDTH: u16 = 80;
+const MIN_WIDTH: u16 = 20;
+/// Signatures are indented by two columns.
+const SIGNATURE_INDENT: &str = "  ";
+/// The widest rules are drawn, however wide the terminal.
+const MAX_RULE_WIDTH: u16 = 100;
+
+/// Gets the width to wrap documentation to, from `OXIDOC_WIDTH` if set, then the terminal size.
+pub fn get_term_width() -> u16 {
+    let var = env::var(WIDTH_VAR).ok();
+    let terminal = term_size::dimensions().map(|(w, _)| w);
+    choose_term_width(var.as_ref().map(|s| s.as_str()), terminal)
+}
+
+/// Picks the wrapping width from the value of `OXIDOC_WIDTH` and the terminal width, falling back
+/// to 80 columns. Unparseable overrides are ignored and narrow widths are raised to 20.
+pub fn choose_term_width(var: Option<&str>, terminal: Option<usize>) -> u16 {
+    let from_var = var.and_then(|v| v.trim().parse::<u16>().ok());
+    let from_terminal = terminal.map(|w| if w > u16::MAX as usize { u16::MAX } else { w as u16 });
+
+    let width = from_var.or(from_terminal).unwrap_or(DEFAULT_WIDTH);
+    width.max(MIN_WIDTH)
+}
+
+/// Where rendered documentation is written, as far as deciding whether to page it goes.
+pub trait OutputTarget {
+    fn is_tty(&self) -> bool;
+    /// The height of the terminal in lines, if known.
+    fn height(&self) -> Option<usize>;
+}
+
+/// Standard output, which counts as a terminal when its size can be read.
+pub struct Stdout;
+
+impl OutputTarget for Stdout {
+    fn is_tty(&self) -> bool {
+        term_size::dimensions_stdout().is_some()
+    }
+
+    fn height(&self) -> Option<usize> {
+        term_size::dimensions_stdout().map(|(_, h)| h)
     }
 }
 
-impl fmt::Display for Markup {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let string = match *self {
+/// Whether `text` should be shown through a pager, which is only the case when it's written to a
+/// terminal it doesn't fit on.
+pub fn should_page<T: OutputTarget>(text: &str, target: &T) -> bool {
+    if !target.is_tty() {
+        return false;
+    }
+    match target.height() {
+        Some(height) => text.lines().count() > height,
+        None => false,
+    }
+}
+
+/// Fallback pager, with `-R` so colors come through.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// The pager to use, given the value of `PAGER`.
+pub fn pager_command(env_pager: Option<&str>) -> String {
+    match env_pager {
+        Some(pager) if !pager.trim().is_empty() => pager.to_string(),
+        _ => DEFAULT_PAGER.to_string(),
+    }
+}
+
+/// Environment variable that turns off colors and styling when set to any value.
+pub const NO_COLOR_VAR: &str = "NO_COLOR";
+
+/// Whether terminal output should be styled, which is the case unless `NO_COLOR` is set.
+pub fn color_enabled() -> bool {
+    env::var_os(NO_COLOR_VAR).is_none()
+}
+
+/// Environment variable that makes decorations like table borders ASCII when set to any value.
+pub const ASCII_VAR: &str = "OXIDOC_ASCII";
+
+/// Whether decorations should be drawn with ASCII characters only, for terminals without Unicode.
+pub fn ascii_enabled() -> bool {
+    env::var_os(ASCII_VAR).is_some()
+}
+
+/// Renders a run of ordinary markdown with catmark, after wrapping it to `width`.
+fn render_text(text: &mut Vec<&str>, width: u16, color: bool, rendered: &mut Vec<String>) {
+    if !text.is_empty() {
+        let kind = if color { OutputKind::Color } else { OutputKind::NoColor };
+        let wrapped = wrap_markdown(&text.join("\n"), width as usize);
+        rendered.push(catmark::render_ansi(&wrapped, width, kind));
+        text.clear();
+    }
+}
+
+/// Reads an ATX heading like `## Examples`, returning its level and text.
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let indent = line.len() - line.trim_left_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+
+    let rest = &line[indent..];
+    let level = rest.chars().take_while(|&c| c == '#').count();
+    let text = &rest[level..];
+    if level == 0 || level > 6 || !(text.is_empty() || text.starts_with(' ')) {
+        return None;
+    }
+
+    // A closing run of hashes is only part of the text if it isn't set off by a space.
+    let text = text.trim();
+    let closed = text.trim_right_matches('#');
+    let text = if closed.is_empty() || closed.ends_with(' ') { closed.trim_right() } else { text };
+    Some((level, text))
+}
+
+/// Styles a heading in a doc comment like the `Header` and `Section` markup around it, with
+/// headings below the second level only set in bold.
+fn render_heading(level: usize, text: &str, theme: &Theme, support: Option<ColorSupport>) -> String {
+    match level {
+        1 => Header(text.to_string()).render_with_theme(theme, support),
+        2 => Section(text.to_string()).render_with_theme(theme, support),
+        _ => ThemeStyle::bold().paint(text.to_string(), support),
+    }
+}
+
+/// Escapes control characters other than newlines and tabs, like `\u{1b}`, so escape sequences
+/// written in docs are shown rather than sent to the terminal. Only the styling added while
+/// rendering reaches the terminal as is.
+fn escape_control_chars(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' | '\t' => escaped.push(c),
+            c if c.is_control() => escaped.extend(c.escape_default()),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders markdown for the terminal, `width` columns wide. Headings and tables are laid out
+/// here, and when colors are on so is fenced Rust code, which is highlighted. The text around them
+/// is left to catmark.
+fn render_markdown(md: &str, width: u16, theme: &Theme, support: Option<ColorSupport>) -> String {
+    let color = support.is_some();
+    let md = escape_control_chars(md);
+    let lines: Vec<&str> = md.lines().collect();
+
+    let mut rendered = Vec::new();
+    let mut text: Vec<&str> = Vec::new();
+    let mut classifier = LineClassifier::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let kind = classifier.classify(lines[i]);
+
+        if let (Some(support), LineKind::FenceOpen(info)) = (support, kind) {
+            if is_rust_fence(info) {
+                render_text(&mut text, width, color, &mut rendered);
+                let start = i + 1;
+                i = start;
+                while i < lines.len() && classifier.classify(lines[i]) != LineKind::FenceClose {
+                    i += 1;
+                }
+                let code = lines[start..i].join("\n");
+                let highlighted = highlight::highlight_rust(&code, theme, support);
+                if i < lines.len() {
+                    rendered.push(highlighted.lines()
+                                  .map(|line| format!("    {}", line))
+                                  .collect::<Vec<String>>()
+                                  .join("\n"));
+                    i += 1;
+                } else {
+                    // An unclosed fence runs to the end of the doc string.
+                    rendered.push(highlighted);
+                }
+                continue;
+            }
+        }
+
+        if kind == LineKind::Text {
+            if let Some((level, heading)) = parse_heading(lines[i]) {
+                render_text(&mut text, width, color, &mut rendered);
+                rendered.push(render_heading(level, heading, theme, support));
+                i += 1;
+                continue;
+            }
+            if let Some((table, len)) = table::parse_table(&lines[i..]) {
+                render_text(&mut text, width, color, &mut rendered);
+                rendered.push(table.render(width as usize, theme, support));
+                for line in &lines[i + 1..i + len] {
+                    classifier.classify(line);
+                }
+                i += len;
+                continue;
+            }
+        }
+
+        text.push(lines[i]);
+        i += 1;
+    }
+    render_text(&mut text, width, color, &mut rendered);
+
+    rendered.join("\n")
+}
+
+/// A paragraph or list item being collected for wrapping.
+struct Paragraph {
+    /// Written before the first line, like the indentation and marker of a list item.
+    first_prefix: String,
+    /// Written before every following line, so they stay inside the list item.
+    indent: String,
+    text: String,
+}
+
+impl Paragraph {
+    fn new(first_prefix: &str, text: &str) -> Paragraph {
+        Paragraph {
+            first_prefix: first_prefix.to_string(),
+            indent: " ".repeat(first_prefix.chars().count()),
+            text: text.trim().to_string(),
+        }
+    }
+
+    /// Fills lines with words up to `width` columns. Code spans are never split, and words wider
+    /// than `width` get a line to themselves.
+    fn wrap(&self, width: usize, lines: &mut Vec<String>) {
+        if self.text.is_empty() {
+            return;
+        }
+
+        let mut line = self.first_prefix.clone();
+        let mut empty = true;
+        for word in split_words(&self.text) {
+            let fits = line.chars().count() + 1 + word.chars().count() <= width;
+            if !empty && !fits {
+                lines.push(line);
+                line = self.indent.clone();
+                empty = true;
+            }
+            if !empty {
+                line.push(' ');
+            }
+            line.push_str(word);
+            empty = false;
+        }
+        lines.push(line);
+    }
+}
+
+/// Splits text at spaces, except for spaces inside code spans like `` `a b` ``.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    // The number of backticks that opened the code span we're in, if any.
+    let mut fence = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c == '`' {
+            let mut run = 1;
+            while let Some(&(_, '`')) = chars.peek() {
+                chars.next();
+                run += 1;
+            }
+            if fence == 0 {
+                fence = run;
+            } else if fence == run {
+                fence = 0;
+            }
+        }
+        if c == ' ' && fence == 0 {
+            if let Some(s) = start.take() {
+                words.push(&text[s..i]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        words.push(&text[s..]);
+    }
+    words
+}
+
+/// The length of the indentation and marker starting a list item, like `- ` or `  2. `.
+fn list_marker_len(line: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_left().len();
+    let rest = &line[indent..];
+    let marker = if rest.starts_with("- ") || rest.starts_with("* ") || rest.starts_with("+ ") {
+        1
+    } else {
+        let digits = rest.chars().take_while(|c| c.is_digit(10)).count();
+        match rest[digits..].chars().next() {
+            Some('.') | Some(')') if digits > 0 && rest[digits + 1..].starts_with(' ') => digits + 1,
+            _ => return None,
+        }
+    };
+    Some(indent + marker + 1)
+}
+
+/// Whether a line ends in a Markdown hard line break.
+fn ends_with_hard_break(line: &str) -> bool {
+    line.ends_with("  ") || line.ends_with('\\')
+}
+
+/// Rewraps the paragraphs and list items of `md` to fit in `width` columns. Continuation lines
+/// of a list item are indented to line up with its text. Code blocks, headings, tables and block
+/// quotes are left as written.
+pub fn wrap_markdown(md: &str, width: usize) -> String {
+    let mut lines = Vec::new();
+    let mut paragraph: Option<Paragraph> = None;
+    let mut classifier = LineClassifier::new();
+
+    for line in md.lines() {
+        let trimmed = line.trim();
+
+        if classifier.classify(line).is_code() || trimmed.is_empty() || trimmed.starts_with('#') ||
+            trimmed.starts_with('|') || trimmed.starts_with('>') {
+            if let Some(paragraph) = paragraph.take() {
+                paragraph.wrap(width, &mut lines);
+            }
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if let Some(marker_len) = list_marker_len(line) {
+            if let Some(paragraph) = paragraph.take() {
+                paragraph.wrap(width, &mut lines);
+            }
+            paragraph = Some(Paragraph::new(&line[..marker_len], &line[marker_len..]));
+        } else {
+            match paragraph {
+                Some(ref mut paragraph) => {
+                    paragraph.text.push(' ');
+                    paragraph.text.push_str(trimmed);
+                }
+                None => {
+                    let indent = &line[..line.len() - line.trim_left().len()];
+                    paragraph = Some(Paragraph::new(indent, trimmed));
+                }
+            }
+        }
+
+        // Keep the break, and carry on with the rest of the paragraph on a new line.
+        if ends_with_hard_break(line) {
+            if let Some(finished) = paragraph.take() {
+                finished.wrap(width, &mut lines);
+                if line.ends_with("  ") {
+                    if let Some(last) = lines.last_mut() {
+                        last.push_str("  ");
+                    }
+                }
+                let indent = finished.indent;
+                paragraph = Some(Paragraph { first_prefix: indent.clone(), indent: indent,
+                                             text: String::new() });
+            }
+        }
+    }
+
+    if let Some(paragraph) = paragraph.take() {
+        paragraph.wrap(width, &mut lines);
+    }
+
+    lines.join("\n")
+}
+
+/// The color support to render with, or `None` to render without styling.
+fn color_support(color: bool) -> Option<ColorSupport> {
+    if color {
+        Some(ColorSupport::detect())
+    } else {
+        None
+    }
+}
+
+impl Markup {
+    /// Renders this piece for the terminal in the active theme, with ANSI styling only if `color`
+    /// is set.
+    pub fn render(&self, color: bool) -> String {
+        self.render_with_theme(&theme::active_theme(), color_support(color))
+    }
+
+    /// Renders this piece in `theme`, writing its colors for `support`, or without any styling if
+    /// `support` is `None`.
+    pub fn render_with_theme(&self, theme: &Theme, support: Option<ColorSupport>) -> String {
+        self.render_with_width(get_term_width(), theme, support)
+    }
+
+    /// Like `render_with_theme`, but fits markdown text and tables to `width` columns instead of
+    /// the terminal.
+    pub fn render_with_width(&self,
+                             width: u16,
+                             theme: &Theme,
+                             support: Option<ColorSupport>) -> String {
+        match *self {
             Header(ref text) => {
-                Style::new()
-                    .bold()
-                    .paint(format!("==== {}", text))
-                    .to_string()
+                theme.header.paint(format!("==== {}", escape_control_chars(text)), support)
             }
             Section(ref text) => {
-                Style::new()
-                    .bold()
-                    .paint(format!("== {}", text))
-                    .to_string()
+                theme.section.paint(format!("== {}", escape_control_chars(text)), support)
+            }
+            Block(ref text) => theme.code.paint(escape_control_chars(text), support),
+            Signature(ref vis, ref decl) if !vis.is_empty() => {
+                format!("{}{}{}",
+                        theme.code.paint(SIGNATURE_INDENT.to_string(), support),
+                        theme.visibility.paint(escape_control_chars(vis), support),
+                        theme.code.paint(escape_control_chars(decl), support))
+            }
+            Signature(..) => {
+                theme.code.paint(escape_control_chars(&self.signature_text()), support)
             }
-            Block(ref text) => text.clone(),
+            Markdown(ref md) => render_markdown(md, width, theme, support),
+            Table { ref headers, ref rows } => {
+                let cells = |row: &[String]| -> Vec<String> {
+                    row.iter().map(|cell| escape_control_chars(cell)).collect()
+                };
+                let table = table::Table {
+                    alignments: vec![table::Alignment::Left; headers.len()],
+                    header: cells(headers),
+                    rows: rows.iter().map(|row| cells(&table_row(row, headers.len()))).collect(),
+                };
+                table.render(width as usize, theme, support)
+            }
+            Rule(ref count) => theme.rule.paint("-".repeat(*count), support),
+            LineBreak => "".to_string(),
+        }
+    }
+}
+
+impl Markup {
+    /// The text of a signature as it's shown, indented and with its visibility.
+    fn signature_text(&self) -> String {
+        match *self {
+            Signature(ref vis, ref decl) => format!("{}{}{}", SIGNATURE_INDENT, vis, decl),
+            _ => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for Markup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(color_enabled()))
+    }
+}
+
+impl Markup {
+    /// Renders this piece as plain Markdown, without any terminal styling.
+    pub fn to_markdown(&self) -> String {
+        match *self {
+            Header(ref text) => format!("## {}", text),
+            Section(ref text) => format!("### {}", text),
+            Block(ref text) => {
+                if is_code_block(text) {
+                    fence_code(text)
+                } else {
+                    text.clone()
+                }
+            }
+            Signature(..) => fence_code(&self.signature_text()),
+            Markdown(ref md) => md.clone(),
+            Table { ref headers, ref rows } => {
+                let row_text = |row: &[String]| -> String {
+                    let cells: Vec<String> = row.iter()
+                        .map(|cell| cell.replace('|', "\\|"))
+                        .collect();
+                    format!("| {} |", cells.join(" | "))
+                };
+                let mut lines = vec![row_text(headers),
+                                     format!("|{}", "---|".repeat(headers.len()))];
+                for row in rows {
+                    lines.push(row_text(&table_row(row, headers.len())));
+                }
+                lines.join("\n")
+            }
+            Rule(..) => "---".to_string(),
+            LineBreak => "".to_string(),
+        }
+    }
+}
+
+impl Markup {
+    /// Renders this piece as an HTML fragment. Text outside of Markdown is escaped.
+    pub fn to_html(&self) -> String {
+        match *self {
+            Header(ref text) => format!("<h2>{}</h2>", escape_html(text)),
+            Section(ref text) => format!("<h3>{}</h3>", escape_html(text)),
+            Block(ref text) => format!("<pre>{}</pre>", escape_html(text)),
+            Signature(..) => format!("<pre>{}</pre>", escape_html(&self.signature_text())),
             Markdown(ref md) => {
-                let width = get_term_width();
-                catmark::render_ansi(md, width, OutputKind::Color)
+                let mut rendered = String::new();
+                html::push_html(&mut rendered, Parser::new(md));
+                rendered
             }
-            Rule(ref count) => "-".repeat(*count),
+            Table { ref headers, ref rows } => {
+                let row_html = |row: &[String], tag: &str| -> String {
+                    let cells: Vec<String> = row.iter()
+                        .map(|cell| format!("<{}>{}</{}>", tag, escape_html(cell), tag))
+                        .collect();
+                    format!("<tr>{}</tr>", cells.concat())
+                };
+                let body: Vec<String> = rows.iter()
+                    .map(|row| row_html(&table_row(row, headers.len()), "td"))
+                    .collect();
+                format!("<table><thead>{}</thead><tbody>{}</tbody></table>",
+                        row_html(headers, "th"), body.concat())
+            }
+            Rule(..) => "<hr>".to_string(),
             LineBreak => "".to_string(),
-        };
-        write!(f, "{}", string)
+        }
+    }
+}
+
+/// A row of a `Table` cut or padded to `len` cells.
+fn table_row(row: &[String], len: usize) -> Vec<String> {
+    let mut row = row.to_vec();
+    row.resize(len, String::new());
+    row
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Blocks holding code, like item signatures, are indented.
+fn is_code_block(text: &str) -> bool {
+    text.lines().any(|line| line.starts_with(char::is_whitespace))
+}
+
+/// Wraps code in a Rust fence long enough not to be closed by any backticks inside it.
+fn fence_code(code: &str) -> String {
+    let mut longest_run: usize = 0;
+    let mut run = 0;
+    for c in code.chars() {
+        if c == '`' {
+            run += 1;
+            longest_run = longest_run.max(run);
+        } else {
+            run = 0;
+        }
     }
+
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{}rust\n{}\n{}", fence, code, fence)
 }
 
 /// A formatted piece of documentation made up of individual markup pieces.
 pub struct MarkupDoc {
     pub parts: Vec<Markup>,
+    /// The width to fit markdown text and tables to in the terminal, from the `FormatOptions`
+    /// the documentation was formatted with. `None` fits them to the terminal.
+    pub width: Option<u16>,
 }
 
 impl MarkupDoc {
     pub fn new(parts: Vec<Markup>) -> Self {
-        MarkupDoc { parts: parts }
+        MarkupDoc {
+            parts: parts,
+            width: None,
+        }
+    }
+
+    /// Renders the documentation for the terminal, with ANSI styling only if `color` is set.
+    pub fn render(&self, color: bool) -> String {
+        self.render_with_theme(&theme::active_theme(), color_support(color))
+    }
+
+    pub fn render_with_theme(&self, theme: &Theme, support: Option<ColorSupport>) -> String {
+        let width = self.width.unwrap_or_else(get_term_width);
+        let mut result = String::new();
+        for part in self.parts.iter() {
+            result.push_str(&part.render_with_width(width, theme, support));
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Renders the documentation as Markdown, for saving to a file instead of showing in a
+    /// terminal.
+    pub fn to_markdown(&self) -> String {
+        let mut result = String::new();
+        for part in self.parts.iter() {
+            // A rule right under a line of text would turn it into a heading instead.
+            if let Rule(..) = *part {
+                if !result.is_empty() && !result.ends_with("\n\n") {
+                    result.push('\n');
+                }
+            }
+            result.push_str(&part.to_markdown());
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Renders the documentation as an HTML fragment, for viewing in a browser.
+    pub fn to_html(&self) -> String {
+        self.parts.iter()
+            .map(|part| part.to_html())
+            .filter(|html| !html.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n")
     }
 }
 
 impl fmt::Display for MarkupDoc {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for part in self.parts.iter() {
-            part.fmt(f)?;
-            write!(f, "\n")?;
+        write!(f, "{}", self.render(color_enabled()))
+    }
+}
+
+/// Options controlling how documentation is laid out into markup.
+#[derive(Clone, Debug)]
+pub struct FormatOptions {
+    /// Show the first Rust code example of the body right after the signature.
+    pub example_first: bool,
+    /// The width to fit signatures to. Longer function signatures get one argument per line.
+    pub width: u16,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            example_first: true,
+            width: get_term_width(),
         }
-        Ok(())
     }
 }
 
 /// Describes an item that can be inserted into documentation markup.
 pub trait Format {
-    fn format(&self) -> MarkupDoc;
+    fn format(&self) -> MarkupDoc {
+        self.format_with(&FormatOptions::default())
+    }
+
+    fn format_with(&self, options: &FormatOptions) -> MarkupDoc;
 }
 
 impl Format for Documentation {
-    fn format(&self) -> MarkupDoc {
+    fn format_with(&self, options: &FormatOptions) -> MarkupDoc {
         let header = doc_header(self);
         let info = doc_inner_info(self);
-        let signature = doc_signature(self);
-        let body = doc_body(self);
+        let signature = doc_signature(self, options.width);
+        let stability = doc_stability(self);
+        let deprecation = doc_deprecation(self);
+        let cfg = doc_cfg(self);
+        let must_use = doc_must_use(self);
+        let (example, body) = doc_example_and_body(self, options.example_first);
         let related_items = doc_related_items(self);
+        let see_also = doc_see_also(self);
 
         let mut result = Vec::new();
         result.extend(header.parts);
         result.extend(info.parts);
         result.extend(signature.parts);
+        result.extend(stability.parts);
+        result.extend(deprecation.parts);
+        result.extend(cfg.parts);
+        result.extend(must_use.parts);
+        result.extend(example.parts);
         result.extend(body.parts);
         result.extend(related_items.parts);
+        result.extend(see_also.parts);
 
-        MarkupDoc::new(result)
+        MarkupDoc {
+            parts: result,
+            width: Some(options.width),
+        }
     }
 }
 
 impl Format for ModPath {
-    fn format(&self) -> MarkupDoc {
+    fn format_with(&self, _options: &FormatOptions) -> MarkupDoc {
         MarkupDoc::new(vec![Header(self.to_string())])
     }
 }
 
 impl Format for Attributes {
-    fn format(&self) -> MarkupDoc {
+    fn format_with(&self, _options: &FormatOptions) -> MarkupDoc {
         let body = self.doc_strings.join("\n");
 
         MarkupDoc::new(vec![Markdown(body)])
     }
 }
 
+/// Info string tokens rustdoc still treats as Rust code.
+const RUST_FENCE_TOKENS: [&str; 7] = ["rust", "ignore", "should_panic", "no_run",
+                                      "compile_fail", "allow_fail", "test_harness"];
+
+fn is_rust_fence(info: &str) -> bool {
+    info.split(',')
+        .map(|token| token.trim())
+        .all(|token| token.is_empty() || RUST_FENCE_TOKENS.contains(&token))
+}
+
+/// Splits the first fenced Rust code block out of a markdown doc string. Returns the code inside
+/// the fence and the doc string with the block removed, or `None` if there is no Rust example.
+pub fn extract_first_example(doc: &str) -> Option<(String, String)> {
+    let lines: Vec<&str> = doc.lines().collect();
+    let mut classifier = LineClassifier::new();
+    let mut open_fence: Option<(usize, bool)> = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        match (classifier.classify(line), open_fence) {
+            (LineKind::FenceOpen(info), _) => open_fence = Some((i, is_rust_fence(info))),
+            (LineKind::FenceClose, Some((start, true))) => {
+                let code = lines[start + 1..i].join("\n");
+                let rest = lines[..start].iter()
+                    .chain(lines[i + 1..].iter())
+                    .cloned()
+                    .collect::<Vec<&str>>()
+                    .join("\n");
+                return Some((code, rest));
+            }
+            (LineKind::FenceClose, _) => open_fence = None,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// The `# Examples` section of a doc string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExamplesSection {
+    /// The markdown under the heading, up to the next heading of the same or a higher level.
+    pub text: String,
+    /// The code inside each fenced Rust block of the section.
+    pub code_blocks: Vec<String>,
+    /// The doc string without the section.
+    pub rest: String,
+}
+
+/// The code inside each fenced Rust block of a markdown doc string.
+fn rust_code_blocks(doc: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut classifier = LineClassifier::new();
+    let mut open_fence: Option<(bool, Vec<&str>)> = None;
+
+    for line in doc.lines() {
+        match (classifier.classify(line), open_fence.take()) {
+            (LineKind::FenceOpen(info), _) => open_fence = Some((is_rust_fence(info), Vec::new())),
+            (LineKind::FenceClose, Some((true, code))) => blocks.push(code.join("\n")),
+            (LineKind::Code, Some((is_rust, mut code))) => {
+                code.push(line);
+                open_fence = Some((is_rust, code));
+            }
+            _ => (),
+        }
+    }
+
+    blocks
+}
+
+/// Splits the `# Examples` (or `# Example`) section out of an item's doc strings, or returns
+/// `None` if there is no such section or it is empty.
+pub fn extract_examples(doc_strings: &[String]) -> Option<ExamplesSection> {
+    split_examples_section(&doc_strings.join("\n"))
+}
+
+fn split_examples_section(doc: &str) -> Option<ExamplesSection> {
+    let lines: Vec<&str> = doc.lines().collect();
+    let mut classifier = LineClassifier::new();
+    let mut start: Option<(usize, usize)> = None;
+    let mut end = lines.len();
+
+    for (i, line) in lines.iter().enumerate() {
+        if classifier.classify(line).is_code() {
+            continue;
+        }
+
+        match (start, parse_heading(line)) {
+            (None, Some((level, "Examples"))) | (None, Some((level, "Example"))) => {
+                start = Some((i, level));
+            }
+            (Some((_, section_level)), Some((level, _))) if level <= section_level => {
+                end = i;
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let heading = match start {
+        Some((heading, _)) => heading,
+        None => return None,
+    };
+    let text = lines[heading + 1..end].join("\n").trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+
+    let code_blocks = rust_code_blocks(&text);
+    let rest = lines[..heading].iter()
+        .chain(lines[end..].iter())
+        .cloned()
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    Some(ExamplesSection { text: text, code_blocks: code_blocks, rest: rest })
+}
+
 fn doc_header(data: &Documentation) -> MarkupDoc {
     let name = match data.inner_data {
+        DocInnerData::FnDoc(ref func) if func.is_foreign => "Foreign Function",
         DocInnerData::FnDoc(..) => "Function",
         DocInnerData::StructDoc(..) => "Struct",
+        DocInnerData::UnionDoc(..) => "Union",
         DocInnerData::ConstDoc(..) => "Constant",
+        DocInnerData::StaticDoc(ref static_) if static_.abi.is_some() => "Foreign Static",
+        DocInnerData::StaticDoc(..) => "Static",
+        DocInnerData::TypedefDoc(..) => "Type Definition",
+        DocInnerData::MacroDoc(..) => "Macro",
         DocInnerData::EnumDoc(..) => "Enum",
         DocInnerData::TraitDoc(..) => "Trait",
+        DocInnerData::TraitItemDoc(TraitItem { node: TraitItemKind::Method(_, true), .. }) => {
+            "Provided Method"
+        }
+        DocInnerData::TraitItemDoc(TraitItem { node: TraitItemKind::Method(_, false), .. }) => {
+            "Required Method"
+        }
         DocInnerData::TraitItemDoc(..) => "Trait Item",
         DocInnerData::ModuleDoc(ref module) => if module.is_crate { "Crate" } else { "Module" },
     };
 
-    MarkupDoc::new(vec![
+    let mut parts = vec![
         Block(format!("({})", data.crate_info)),
         Header(format!("{} {}", name, data.mod_path)),
-    ])
+    ];
+    if let Some(ref source) = data.source {
+        parts.push(Block(format!("Defined in {}", source)));
+    }
+    MarkupDoc::new(parts)
 }
 
 fn doc_body(data: &Documentation) -> MarkupDoc {
-    data.attrs.format()
+    MarkupDoc::new(vec![Markdown(doc_text(data))])
+}
+
+/// The doc string of an item, with its intra-doc links pointed at the paths they resolved to.
+fn doc_text(data: &Documentation) -> String {
+    let resolved = data.links.get(&DocType::IntraDocLink).map(|links| links.as_slice());
+    intra_links::rewrite_intra_links(&data.attrs.doc_strings.join("\n"), resolved.unwrap_or(&[]))
+}
+
+/// Splits an item's examples out of its doc string when examples go first. An `# Examples`
+/// section is shown under its own heading, wherever it's placed. Without one, the first Rust
+/// code block is pulled out as the example. Otherwise the doc string is left as written.
+fn doc_example_and_body(data: &Documentation, example_first: bool) -> (MarkupDoc, MarkupDoc) {
+    if !example_first {
+        return (MarkupDoc::new(vec![]), doc_body(data));
+    }
+
+    let body = doc_text(data);
+
+    if let Some(section) = split_examples_section(&body) {
+        let examples = MarkupDoc::new(vec![
+            Section("Examples".to_string()),
+            Markdown(section.text),
+            LineBreak,
+        ]);
+        return (examples, MarkupDoc::new(vec![Markdown(section.rest)]));
+    }
+
+    match extract_first_example(&body) {
+        Some((code, rest)) => {
+            let example = MarkupDoc::new(vec![
+                Section("Example".to_string()),
+                Markdown(format!("```rust\n{}\n```", code)),
+                LineBreak,
+            ]);
+            (example, MarkupDoc::new(vec![Markdown(rest)]))
+        }
+        _ => (MarkupDoc::new(vec![]), doc_body(data)),
+    }
+}
+
+/// Shows a notice for items marked `#[unstable]`. Stable items need no notice.
+fn doc_stability(data: &Documentation) -> MarkupDoc {
+    match data.attrs.stability {
+        Some(Stability { level: StabilityLevel::Unstable, ref feature, .. }) => {
+            let banner = match *feature {
+                Some(ref feature) => format!("Unstable (feature = \"{}\")", feature),
+                None => "Unstable".to_string(),
+            };
+            MarkupDoc::new(vec![Section(banner), LineBreak])
+        }
+        _ => MarkupDoc::new(vec![]),
+    }
+}
+
+/// Shows a notice for items marked `#[deprecated]`, with the version and note if given.
+fn doc_deprecation(data: &Documentation) -> MarkupDoc {
+    let deprecation = match data.attrs.deprecation {
+        Some(ref deprecation) => deprecation,
+        None => return MarkupDoc::new(vec![]),
+    };
+
+    let mut parts = vec![Section("Deprecated".to_string())];
+
+    let mut notice = Vec::new();
+    if let Some(ref since) = deprecation.since {
+        notice.push(format!("Since {}.", since));
+    }
+    if let Some(ref note) = deprecation.note {
+        notice.push(note.clone());
+    }
+    if !notice.is_empty() {
+        parts.push(Markdown(notice.join(" ")));
+    }
+
+    parts.push(LineBreak);
+    MarkupDoc::new(parts)
+}
+
+/// Shows the conditions from an item's `#[cfg]` attributes, like `Available on unix`.
+fn doc_cfg(data: &Documentation) -> MarkupDoc {
+    match data.attrs.cfg {
+        Some(ref cfg) => MarkupDoc::new(vec![Block(format!("Available on {}", cfg)), LineBreak]),
+        None => MarkupDoc::new(vec![]),
+    }
+}
+
+/// Shows the item's `#[must_use]` attribute, with its message if it has one.
+fn doc_must_use(data: &Documentation) -> MarkupDoc {
+    let attr = match data.attrs.must_use {
+        Some(ref message) if message.is_empty() => "#[must_use]".to_string(),
+        Some(ref message) => format!("#[must_use = \"{}\"]", message),
+        None => return MarkupDoc::new(vec![]),
+    };
+
+    MarkupDoc::new(vec![Block(attr), LineBreak])
 }
 
 fn doc_related_items(data: &Documentation) -> MarkupDoc {
-    MarkupDoc::new(vec![])
+    match data.inner_data {
+        DocInnerData::ModuleDoc(..) => doc_module_contents(data),
+        DocInnerData::TraitDoc(..) => doc_trait_items(data),
+        DocInnerData::StructDoc(ref struct_) => {
+            let mut parts = doc_member_descriptions("Fields", &field_descriptions(&struct_.fields))
+                .parts;
+            parts.extend(doc_methods(data).parts);
+            parts.extend(doc_implemented_traits(data).parts);
+            MarkupDoc::new(parts)
+        }
+        DocInnerData::UnionDoc(ref union_) => {
+            doc_member_descriptions("Fields", &field_descriptions(&union_.fields))
+        }
+        DocInnerData::EnumDoc(ref enum_) => {
+            let variants: Vec<(String, &Attributes)> = enum_.variants.iter()
+                .map(|variant| (variant.name.clone(), &variant.attrs))
+                .collect();
+            let mut parts = doc_member_descriptions("Variants", &variants).parts;
+            parts.extend(doc_methods(data).parts);
+            parts.extend(doc_implemented_traits(data).parts);
+            MarkupDoc::new(parts)
+        }
+        _ => MarkupDoc::new(vec![]),
+    }
+}
+
+/// The names of struct fields with their docs. Tuple fields are named by position.
+fn field_descriptions(fields: &[StructField]) -> Vec<(String, &Attributes)> {
+    fields.iter()
+        .enumerate()
+        .map(|(i, field)| (field.ident.clone().unwrap_or_else(|| i.to_string()), &field.attrs))
+        .collect()
+}
+
+/// Lists fields or variants with the summaries of their docs. Undocumented members are listed
+/// by name alone, and the section is left out if none of them are documented.
+fn doc_member_descriptions(title: &str, members: &[(String, &Attributes)]) -> MarkupDoc {
+    let summaries: Vec<(&str, Option<String>)> = members.iter()
+        .map(|&(ref name, attrs)| {
+            let summary = attrs.summary();
+            (name.as_str(), if summary.is_empty() { None } else { Some(summary) })
+        })
+        .collect();
+    if summaries.iter().all(|&(_, ref summary)| summary.is_none()) {
+        return MarkupDoc::new(vec![]);
+    }
+
+    let entries = summaries.iter()
+        .map(|&(name, ref summary)| match *summary {
+            Some(ref summary) => format!("- `{}`: {}", name, summary),
+            None => format!("- `{}`", name),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    MarkupDoc::new(vec![LineBreak, Section(title.to_string()), Markdown(entries)])
+}
+
+/// Lists the names of the functions implemented on a type, sorted: associated functions like
+/// `new` under "Functions", and those taking `self` under "Methods". Empty sections are left
+/// out.
+fn doc_methods(data: &Documentation) -> MarkupDoc {
+    let mut parts = Vec::new();
+    for &(ref doc_type, title) in &[(DocType::AssocFn, "Functions"), (DocType::Method, "Methods")] {
+        let mut names: Vec<&str> = match data.links.get(doc_type) {
+            Some(links) => links.iter().map(|link| link.name.as_str()).collect(),
+            None => vec![],
+        };
+        if names.is_empty() {
+            continue;
+        }
+        names.sort();
+        names.dedup();
+
+        let entries = names.iter()
+            .map(|name| format!("- {}", name))
+            .collect::<Vec<String>>()
+            .join("\n");
+        parts.extend(vec![LineBreak, Section(title.to_string()), Markdown(entries)]);
+    }
+    MarkupDoc::new(parts)
+}
+
+/// Lists the traits implemented for a type, sorted and linked to their paths.
+fn doc_implemented_traits(data: &Documentation) -> MarkupDoc {
+    let mut links: Vec<&DocLink> = match data.links.get(&DocType::ImplementedTrait) {
+        Some(links) => links.iter().collect(),
+        None => return MarkupDoc::new(vec![]),
+    };
+    if links.is_empty() {
+        return MarkupDoc::new(vec![]);
+    }
+    links.sort_by(|a, b| a.name.cmp(&b.name));
+    links.dedup();
+
+    let entries = links.iter()
+        .map(|link| format!("- [{}]({})", link.name, link.path))
+        .collect::<Vec<String>>()
+        .join("\n");
+    MarkupDoc::new(vec![LineBreak, Section("Implements".to_string()), Markdown(entries)])
+}
+
+/// Lists the items related to this one, linked to their paths.
+fn doc_see_also(data: &Documentation) -> MarkupDoc {
+    let links = data.see_also();
+    if links.is_empty() {
+        return MarkupDoc::new(vec![]);
+    }
+
+    let entries = links.iter()
+        .map(|link| format!("- [{}]({})", link.name, link.path))
+        .collect::<Vec<String>>()
+        .join("\n");
+    MarkupDoc::new(vec![LineBreak, Section("See Also".to_string()), Markdown(entries)])
+}
+
+/// The sections listing a trait's items, in the order rustdoc shows them.
+const TRAIT_ITEM_SECTIONS: [(DocType, &str); 6] = [
+    (DocType::AssocType, "Associated Types"),
+    (DocType::AssocConst, "Associated Constants"),
+    (DocType::TraitItemMethod, "Required Methods"),
+    (DocType::ProvidedMethod, "Provided Methods"),
+    (DocType::Macro, "Macros"),
+    (DocType::Implementor, "Implementors"),
+];
+
+/// Lists the names of a trait's items in declaration order, in a section for each kind of item.
+fn doc_trait_items(data: &Documentation) -> MarkupDoc {
+    let mut parts = Vec::new();
+    for &(ref doc_type, title) in TRAIT_ITEM_SECTIONS.iter() {
+        let links = match data.links.get(doc_type) {
+            Some(links) if !links.is_empty() => links,
+            _ => continue,
+        };
+        let entries = links.iter()
+            .map(|link| format!("- {}", link.name))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        parts.push(LineBreak);
+        parts.push(Section(title.to_string()));
+        parts.push(Markdown(entries));
+    }
+    MarkupDoc::new(parts)
+}
+
+/// Whether items of `doc_type` can be defined in a module, as opposed to links to related items.
+fn is_module_item(doc_type: &DocType) -> bool {
+    match *doc_type {
+        DocType::Module |
+        DocType::Function |
+        DocType::Struct |
+        DocType::Union |
+        DocType::Enum |
+        DocType::Const |
+        DocType::Static |
+        DocType::Typedef |
+        DocType::Trait |
+        DocType::Macro => true,
+        _ => false,
+    }
+}
+
+/// Lists the names of the items in a module, grouped by their kind and sorted within each group.
+fn doc_module_contents(data: &Documentation) -> MarkupDoc {
+    let groups: Vec<String> = data.links.iter()
+        .filter(|&(doc_type, links)| is_module_item(doc_type) && !links.is_empty())
+        .map(|(doc_type, links)| {
+            let mut names: Vec<&str> = links.iter().map(|link| link.name.as_str()).collect();
+            names.sort();
+            let entries = names.iter()
+                .map(|name| format!("- {}", name))
+                .collect::<Vec<String>>()
+                .join("\n");
+            format!("**{}**\n\n{}", doc_type, entries)
+        })
+        .collect();
+
+    if groups.is_empty() {
+        return MarkupDoc::new(vec![]);
+    }
+
+    let mut parts = vec![LineBreak, Section("Contents".to_string())];
+    parts.extend(groups.into_iter().map(Markdown));
+    MarkupDoc::new(parts)
 }
 
 fn doc_inner_info(data: &Documentation) -> MarkupDoc {
     let markup = match data.inner_data {
         DocInnerData::FnDoc(ref func) => {
             match func.kind {
-                FnKind::MethodFromImpl => Header(format!(
+                FnKind::MethodFromImpl | FnKind::MethodFromTrait => Header(format!(
                     "Impl on type {}",
                     data.mod_path.parent().unwrap()
                 )),
@@ -151,7 +1180,11 @@ fn doc_inner_info(data: &Documentation) -> MarkupDoc {
             Header(format!("From trait {}", data.mod_path.parent().unwrap()))
         }
         DocInnerData::StructDoc(..) |
+        DocInnerData::UnionDoc(..) |
         DocInnerData::ConstDoc(..) |
+        DocInnerData::StaticDoc(..) |
+        DocInnerData::TypedefDoc(..) |
+        DocInnerData::MacroDoc(..) |
         DocInnerData::EnumDoc(..) |
         DocInnerData::TraitDoc(..) |
         DocInnerData::ModuleDoc(..) => LineBreak,
@@ -159,78 +1192,315 @@ fn doc_inner_info(data: &Documentation) -> MarkupDoc {
     MarkupDoc::new(vec![markup])
 }
 
-fn doc_signature(data: &Documentation) -> MarkupDoc {
+/// The length of the rules around signatures on a terminal `width` columns wide.
+fn rule_width(width: u16) -> usize {
+    width.min(MAX_RULE_WIDTH) as usize
+}
+
+fn doc_signature(data: &Documentation, width: u16) -> MarkupDoc {
     let vis_string = match data.visibility {
-        Some(ref v) => v.to_string(),
+        Some(ref v) => vis_prefix(v),
         None => "".to_string(),
     };
 
     let header = match data.inner_data {
         DocInnerData::ModuleDoc(ref module) => {
             if module.is_crate {
-                return MarkupDoc::new(vec![Rule(10), LineBreak]);
+                return MarkupDoc::new(vec![Rule(rule_width(width)), LineBreak]);
             } else {
                 doc_module(data)
             }
         }
-        DocInnerData::FnDoc(ref func) => doc_fn(data, func),
-        DocInnerData::EnumDoc(..) => doc_enum(data),
-        DocInnerData::StructDoc(..) => doc_struct(data),
-        DocInnerData::ConstDoc(ref konst) => doc_const(data, konst),
-        DocInnerData::TraitDoc(..) => doc_trait(data),
+        DocInnerData::FnDoc(ref func) => {
+            // The signature is indented by two columns.
+            let width = (width as usize).saturating_sub(2 + vis_string.len());
+            doc_fn(data, func, width)
+        }
+        DocInnerData::EnumDoc(ref enum_) => doc_enum(data, enum_),
+        DocInnerData::StructDoc(ref struct_) => doc_struct(data, struct_),
+        DocInnerData::UnionDoc(ref union_) => {
+            doc_named_fields("union", data, &union_.fields, &union_.generics)
+        }
+        DocInnerData::ConstDoc(ref konst) => {
+            doc_const(data, konst, (width as usize).saturating_sub(2 + vis_string.len()))
+        }
+        DocInnerData::StaticDoc(ref static_) => {
+            doc_static(data, static_, (width as usize).saturating_sub(2 + vis_string.len()))
+        }
+        DocInnerData::TypedefDoc(ref typedef) => doc_typedef(data, typedef),
+        DocInnerData::MacroDoc(ref mac) => doc_macro(data, mac),
+        DocInnerData::TraitDoc(ref trait_) => doc_trait(data, trait_),
         DocInnerData::TraitItemDoc(ref item) => doc_trait_item(data, item),
     };
 
-    MarkupDoc::new(vec![
-        Rule(10),
-        LineBreak,
-        Block(format!("  {} {}", vis_string, header)),
+    let mut parts = vec![Rule(rule_width(width)), LineBreak];
+    // Attributes like `#[repr(C)]` go on their own lines above the signature, as in the source.
+    if !data.attrs.other_attrs.is_empty() {
+        let attrs: Vec<String> = data.attrs.other_attrs.iter()
+            .map(|attr| format!("{}{}", SIGNATURE_INDENT, attr))
+            .collect();
+        parts.push(Block(attrs.join("\n")));
+    }
+    parts.extend(vec![
+        Signature(vis_string, header),
         LineBreak,
-        Rule(10),
+        Rule(rule_width(width)),
         LineBreak,
-    ])
+    ]);
+    MarkupDoc::new(parts)
 }
 
 fn doc_module(data: &Documentation) -> String {
     format!("mod {}", data.mod_path)
 }
 
-fn doc_fn(data: &Documentation, func: &Function) -> String {
-    format!("fn {} {}", data.name, func.header)
+/// Renders a function signature, placing each argument on its own line if the signature would
+/// be wider than `width` on one line.
+fn doc_fn(data: &Documentation, func: &Function, width: usize) -> String {
+    let qualifiers = doc_fn_qualifiers(&func.constness, func.is_async, &func.unsafety,
+                                       func.is_foreign, &func.abi);
+    let name = format!("{}fn {}{}", qualifiers, data.name, doc_generics(&func.generics));
+    let where_clause = doc_where_clause(&func.generics);
+
+    let one_line = format!("{}{}", name, func.header);
+    if one_line.chars().count() <= width || func.header.inputs.is_empty() {
+        return format!("{}{}", one_line, where_clause);
+    }
+
+    let mut args: Vec<String> = func.header.args().iter()
+        .map(|arg| format!("      {},", arg))
+        .collect();
+    if func.header.variadic {
+        args.push("      ...".to_string());
+    }
+    let output = match func.header.output {
+        Some(ref output) => format!(" -> {}", output),
+        None => String::new(),
+    };
+    format!("{}(\n{}\n  ){}{}", name, args.join("\n"), output, where_clause)
 }
 
-fn doc_enum(data: &Documentation) -> String {
-    format!("enum {}", data.name)
+/// Renders the qualifiers before `fn` in a signature, like `const unsafe extern "C" `.
+fn doc_fn_qualifiers(constness: &Constness,
+                     is_async: bool,
+                     unsafety: &Unsafety,
+                     is_foreign: bool,
+                     abi: &Abi) -> String {
+    let const_ = match *constness {
+        Constness::Const => "const ",
+        Constness::NotConst => "",
+    };
+    let async_ = if is_async { "async " } else { "" };
+    // Foreign functions are always unsafe to call, but are declared without the keyword.
+    let unsafe_ = match *unsafety {
+        Unsafety::Unsafe if !is_foreign => "unsafe ",
+        _ => "",
+    };
+    let extern_ = if is_foreign || *abi != Abi::Rust {
+        format!("extern \"{}\" ", abi)
+    } else {
+        String::new()
+    };
+    format!("{}{}{}{}", const_, async_, unsafe_, extern_)
 }
 
-fn doc_struct(data: &Documentation) -> String {
-    format!("struct {} {{ /* fields omitted */ }}", data.name)
+/// Renders generic parameters like `<'a, T: Clone = u32>`, or nothing if there are none.
+fn doc_generics(generics: &Generics) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+
+    let ty_params = generics.ty_params.iter().map(|param| {
+        let mut rendered = param.name.clone();
+        if !param.bounds.is_empty() {
+            rendered = format!("{}: {}", rendered, param.bounds.join(" + "));
+        }
+        if let Some(ref default) = param.default {
+            rendered = format!("{} = {}", rendered, default.name);
+        }
+        rendered
+    });
+
+    let params = generics.lifetimes.iter().cloned()
+        .chain(ty_params)
+        .collect::<Vec<String>>();
+
+    format!("<{}>", params.join(", "))
 }
 
-fn doc_const(data: &Documentation, konst: &Constant) -> String {
-    format!("const {}: {} = {}", data.name, konst.ty.name, konst.expr)
+/// Renders the `where` clause on its own line, or nothing if there are no predicates.
+fn doc_where_clause(generics: &Generics) -> String {
+    if generics.where_predicates.is_empty() {
+        return String::new();
+    }
+
+    format!("\n  where {}", generics.where_predicates.join(", "))
 }
 
-fn doc_trait(data: &Documentation) -> String {
-    format!("trait {} {{ /* fields omitted */ }}", data.name)
+fn doc_enum(data: &Documentation, enum_: &Enum) -> String {
+    let name = format!("{}{}{}",
+                       data.name, doc_generics(&enum_.generics), doc_where_clause(&enum_.generics));
+    if enum_.variants.is_empty() {
+        return format!("enum {} {{}}", name);
+    }
+
+    let variants = enum_.variants.iter()
+        .map(|variant| format!("      {},", doc_variant(variant)))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("enum {} {{\n{}\n  }}", name, variants)
+}
+
+fn doc_variant(variant: &Variant) -> String {
+    let shape = match variant.kind {
+        VariantKind::Unit => variant.name.clone(),
+        VariantKind::Tuple(ref fields) => {
+            let types = fields.iter()
+                .map(|field| field.ty.name.clone())
+                .collect::<Vec<String>>();
+            format!("{}({})", variant.name, types.join(", "))
+        }
+        VariantKind::Struct(ref fields) => {
+            let fields = fields.iter()
+                .map(|field| format!("{}: {}",
+                                     field.ident.clone().unwrap_or_default(),
+                                     field.ty.name))
+                .collect::<Vec<String>>();
+            format!("{} {{ {} }}", variant.name, fields.join(", "))
+        }
+    };
+
+    match variant.discriminant {
+        Some(ref discriminant) => format!("{} = {}", shape, discriminant),
+        None => shape,
+    }
+}
+
+fn vis_prefix(vis: &Visibility) -> String {
+    match vis.to_string() {
+        ref v if v.is_empty() => "".to_string(),
+        v => format!("{} ", v),
+    }
+}
+
+fn doc_struct(data: &Documentation, struct_: &Struct) -> String {
+    let generics = doc_generics(&struct_.generics);
+    let where_clause = doc_where_clause(&struct_.generics);
+    match struct_.kind {
+        StructKind::Unit => format!("struct {}{}{};", data.name, generics, where_clause),
+        StructKind::Tuple => {
+            let fields = struct_.fields.iter()
+                .map(|field| format!("{}{}", vis_prefix(&field.vis), field.ty.name))
+                .collect::<Vec<String>>();
+            format!("struct {}{}({}){};", data.name, generics, fields.join(", "), where_clause)
+        }
+        StructKind::Named => doc_named_fields("struct", data, &struct_.fields, &struct_.generics),
+    }
+}
+
+/// Renders a struct-like declaration with one named field per line.
+fn doc_named_fields(keyword: &str, data: &Documentation, fields: &[StructField],
+                    generics: &Generics) -> String {
+    let name = format!("{}{}{}", data.name, doc_generics(generics), doc_where_clause(generics));
+    if fields.is_empty() {
+        return format!("{} {} {{}}", keyword, name);
+    }
+
+    let fields = fields.iter()
+        .map(|field| format!("      {}{}: {},",
+                             vis_prefix(&field.vis),
+                             field.ident.clone().unwrap_or_default(),
+                             field.ty.name))
+        .collect::<Vec<String>>();
+    format!("{} {} {{\n{}\n  }}", keyword, name, fields.join("\n"))
+}
+
+/// Renders the declaration of a constant or static, moving the type and what follows it to a
+/// continuation line if the declaration would be wider than `width` on one line.
+fn doc_typed_item(decl: &str, ty: &str, rest: &str, width: usize) -> String {
+    let one_line = format!("{}: {}{}", decl, ty, rest);
+    if one_line.chars().count() <= width {
+        one_line
+    } else {
+        format!("{}:\n      {}{}", decl, ty, rest)
+    }
+}
+
+fn doc_const(data: &Documentation, konst: &Constant, width: usize) -> String {
+    doc_typed_item(&format!("const {}", data.name), &konst.ty.name,
+                   &format!(" = {}", konst.expr), width)
+}
+
+fn doc_static(data: &Documentation, static_: &Static, width: usize) -> String {
+    let mutability = match static_.mutability {
+        Mutability::Mutable => "mut ",
+        Mutability::Immutable => "",
+    };
+    let extern_ = match static_.abi {
+        Some(ref abi) => format!("extern \"{}\" ", abi),
+        None => String::new(),
+    };
+    let rest = match static_.expr {
+        Some(ref expr) => format!(" = {};", expr),
+        None => ";".to_string(),
+    };
+    doc_typed_item(&format!("{}static {}{}", extern_, mutability, data.name), &static_.ty.name,
+                   &rest, width)
+}
+
+fn doc_typedef(data: &Documentation, typedef: &Typedef) -> String {
+    format!("type {}{} = {};", data.name, doc_generics(&typedef.generics), typedef.ty.name)
+}
+
+fn doc_macro(data: &Documentation, mac: &Macro) -> String {
+    if mac.matchers.is_empty() {
+        return format!("macro_rules! {} {{ ... }}", data.name);
+    }
+
+    let arms = mac.matchers.iter()
+        .map(|matcher| format!("      {} => {{ ... }};", matcher))
+        .collect::<Vec<String>>();
+    format!("macro_rules! {} {{\n{}\n  }}", data.name, arms.join("\n"))
+}
+
+fn doc_trait(data: &Documentation, trait_: &Trait) -> String {
+    let bounds = if trait_.bounds.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", trait_.bounds.join(" + "))
+    };
+
+    format!("trait {}{}{}{} {{ /* fields omitted */ }}",
+            data.name,
+            doc_generics(&trait_.generics),
+            bounds,
+            doc_where_clause(&trait_.generics))
 }
 
 fn doc_trait_item(data: &Documentation, item: &TraitItem) -> String {
     let item_string = match item.node {
         TraitItemKind::Const(ref ty, ref expr) => {
-            let expr_string = match *expr {
-                Some(ref e) => e.clone(),
-                None => "".to_string(),
-            };
-            format!("const {}: {} = {}", data.name, ty.name, expr_string)
+            match *expr {
+                Some(ref e) => format!("const {}: {} = {};", data.name, ty.name, e),
+                None => format!("const {}: {};", data.name, ty.name),
+            }
+        }
+        TraitItemKind::Method(ref sig, _) => {
+            let qualifiers = doc_fn_qualifiers(&sig.constness, false, &sig.unsafety, false,
+                                               &sig.abi);
+            format!("{}fn {}{}", qualifiers, data.name, sig.header)
         }
-        TraitItemKind::Method(ref sig) => format!("fn {} {}", data.name, sig.header),
-        TraitItemKind::Type(ref ty) => {
-            let ty_string = match *ty {
-                Some(ref t) => t.name.clone(),
-                None => "".to_string(),
+        TraitItemKind::Type(ref bounds, ref ty) => {
+            let bounds_string = if bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", bounds.join(" + "))
             };
-            format!("type {}", ty_string)
+            match *ty {
+                Some(ref t) => format!("type {}{} = {};", data.name, bounds_string, t.name),
+                None => format!("type {}{};", data.name, bounds_string),
+            }
         }
         TraitItemKind::Macro(ref mac) => format!("macro {} {}", data.name, mac),
     };
@@ -1,9 +1,11 @@
 use std::{env, fs, io, result};
 use std::path::{PathBuf};
+use std::sync::Mutex;
 use document::CrateInfo;
 use ::errors::*;
 
-const STORE_FILENAME: &str = "store";
+/// The name of the store index file, in the doc registry or in a store directory.
+pub const STORE_FILENAME: &str = "store";
 
 pub fn home_dir() -> Result<PathBuf> {
     if let Some(home_dir) = env::home_dir() {
@@ -19,7 +21,20 @@ fn make_registry_path(dir: &str) -> Result<PathBuf> {
     Ok(home_dir.as_path().join(".cargo").join("registry").join(dir))
 }
 
+lazy_static! {
+    static ref DOC_REGISTRY_OVERRIDE: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+/// Stores documentation under `path` instead of `~/.cargo/registry/doc`, so tests can keep away
+/// from the user's store.
+pub fn set_doc_registry_path(path: PathBuf) {
+    *DOC_REGISTRY_OVERRIDE.lock().unwrap() = Some(path);
+}
+
 pub fn doc_registry_path() -> Result<PathBuf> {
+    if let Some(ref path) = *DOC_REGISTRY_OVERRIDE.lock().unwrap() {
+        return Ok(path.clone());
+    }
     make_registry_path("doc")
 }
 
@@ -37,10 +52,7 @@ pub fn store_file_path() -> Result<PathBuf> {
 pub fn crate_doc_path(crate_info: &CrateInfo) -> Result<PathBuf> {
     let registry_path = doc_registry_path()?;
 
-    let path = registry_path.join(format!("{}-{}",
-                                          crate_info.name,
-                                          crate_info.version));
-    Ok(path)
+    Ok(registry_path.join(crate_info.to_path_prefix()))
 }
 
 pub fn iter_crate_source_paths() -> Result<Vec<PathBuf>> {
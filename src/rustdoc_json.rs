@@ -0,0 +1,535 @@
+//! Reading the output of `rustdoc --output-format json` into documentation, as an alternative to
+//! parsing the crate's source. Expects the format of recent nightlies, where each item's `inner`
+//! holds a single key naming its kind. Only functions, structs and traits are read so far.
+
+use std::collections::BTreeMap;
+
+use serde_json::{self, Value};
+
+use conversion::*;
+use document::{CrateInfo, ModPath, SourceLocation};
+use generation::ast_ty_wrappers::{Attributes, Deprecation, FnKind, StructKind, Ty};
+use ::errors::*;
+
+static NULL: Value = Value::Null;
+
+/// The parts of a rustdoc JSON file needed to look up items.
+struct RustdocCrate<'a> {
+    index: &'a Value,
+    paths: &'a Value,
+    crate_info: &'a CrateInfo,
+}
+
+/// Ids are numbers in newer formats and strings in older ones, but are always strings as keys.
+fn id_key(id: &Value) -> Option<String> {
+    match *id {
+        Value::String(ref s) => Some(s.clone()),
+        Value::Number(ref n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// The single key and value of an externally tagged enum, like `{"function": {...}}`, or the
+/// string of a unit variant, like `"unit"`.
+fn variant(value: &Value) -> Option<(&str, &Value)> {
+    match *value {
+        Value::Object(ref map) if map.len() == 1 => {
+            map.iter().next().map(|(key, value)| (key.as_str(), value))
+        }
+        Value::String(ref s) => Some((s.as_str(), &NULL)),
+        _ => None,
+    }
+}
+
+/// The first of `keys` present in `value`, for fields renamed between format versions.
+fn either<'a>(value: &'a Value, keys: &[&str]) -> &'a Value {
+    keys.iter()
+        .filter_map(|key| value.get(*key))
+        .next()
+        .unwrap_or(&NULL)
+}
+
+fn flag(value: &Value, keys: &[&str]) -> bool {
+    either(value, keys).as_bool().unwrap_or(false)
+}
+
+fn render_path(path: &Value) -> String {
+    let name = either(path, &["path", "name"]).as_str().unwrap_or("_").to_string();
+    match path.get("args") {
+        Some(args) if !args.is_null() => format!("{}{}", name, render_generic_args(args)),
+        _ => name,
+    }
+}
+
+fn render_generic_args(args: &Value) -> String {
+    match variant(args) {
+        Some(("angle_bracketed", args)) => {
+            let mut rendered: Vec<String> = args["args"].as_array()
+                .map(|args| args.iter().map(|arg| match variant(arg) {
+                    Some(("type", ty)) => render_type(ty),
+                    Some(("lifetime", lifetime)) => lifetime.as_str().unwrap_or("'_").to_string(),
+                    Some(("const", constant)) => {
+                        constant["expr"].as_str().unwrap_or("_").to_string()
+                    }
+                    _ => "_".to_string(),
+                }).collect())
+                .unwrap_or_default();
+            let constraints = either(args, &["constraints", "bindings"]);
+            if let Some(constraints) = constraints.as_array() {
+                for constraint in constraints {
+                    let name = constraint["name"].as_str().unwrap_or("_");
+                    match variant(&constraint["binding"]) {
+                        Some(("equality", term)) => {
+                            let ty = term.get("type").unwrap_or(term);
+                            rendered.push(format!("{} = {}", name, render_type(ty)));
+                        }
+                        Some(("constraint", bounds)) => {
+                            rendered.push(format!("{}: {}", name, render_bounds(bounds).join(" + ")));
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            if rendered.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", rendered.join(", "))
+            }
+        }
+        Some(("parenthesized", args)) => {
+            let inputs: Vec<String> = args["inputs"].as_array()
+                .map(|inputs| inputs.iter().map(render_type).collect())
+                .unwrap_or_default();
+            match render_output(&args["output"]) {
+                Some(output) => format!("({}) -> {}", inputs.join(", "), output),
+                None => format!("({})", inputs.join(", ")),
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+fn render_bounds(bounds: &Value) -> Vec<String> {
+    bounds.as_array()
+        .map(|bounds| bounds.iter().filter_map(|bound| match variant(bound) {
+            Some(("trait_bound", bound)) => {
+                let modifier = if bound["modifier"].as_str() == Some("maybe") { "?" } else { "" };
+                Some(format!("{}{}", modifier, render_path(&bound["trait"])))
+            }
+            Some(("outlives", lifetime)) => lifetime.as_str().map(|s| s.to_string()),
+            _ => None,
+        }).collect())
+        .unwrap_or_default()
+}
+
+/// A return type, or `None` for `()`.
+fn render_output(output: &Value) -> Option<String> {
+    match *output {
+        Value::Null => None,
+        ref ty if ty["tuple"].as_array().map_or(false, |types| types.is_empty()) => None,
+        ref ty => Some(render_type(ty)),
+    }
+}
+
+/// Writes out a type the way it would appear in source.
+fn render_type(ty: &Value) -> String {
+    let mutability = |value: &Value| {
+        if flag(value, &["is_mutable", "mutable"]) { "mut " } else { "" }
+    };
+
+    match variant(ty) {
+        Some(("primitive", name)) | Some(("generic", name)) => {
+            name.as_str().unwrap_or("_").to_string()
+        }
+        Some(("resolved_path", path)) => render_path(path),
+        Some(("borrowed_ref", reference)) => {
+            let lifetime = reference["lifetime"].as_str()
+                .map(|lifetime| format!("{} ", lifetime))
+                .unwrap_or_default();
+            format!("&{}{}{}", lifetime, mutability(reference), render_type(&reference["type"]))
+        }
+        Some(("raw_pointer", pointer)) => {
+            let kind = if mutability(pointer).is_empty() { "const" } else { "mut" };
+            format!("*{} {}", kind, render_type(&pointer["type"]))
+        }
+        Some(("tuple", types)) => {
+            let types: Vec<String> = types.as_array()
+                .map(|types| types.iter().map(render_type).collect())
+                .unwrap_or_default();
+            if types.len() == 1 {
+                format!("({},)", types[0])
+            } else {
+                format!("({})", types.join(", "))
+            }
+        }
+        Some(("slice", inner)) => format!("[{}]", render_type(inner)),
+        Some(("array", array)) => {
+            format!("[{}; {}]", render_type(&array["type"]), array["len"].as_str().unwrap_or("_"))
+        }
+        Some(("impl_trait", bounds)) => format!("impl {}", render_bounds(bounds).join(" + ")),
+        Some(("dyn_trait", dyn_trait)) => {
+            let mut bounds: Vec<String> = dyn_trait["traits"].as_array()
+                .map(|traits| traits.iter().map(|bound| render_path(&bound["trait"])).collect())
+                .unwrap_or_default();
+            if let Some(lifetime) = dyn_trait["lifetime"].as_str() {
+                bounds.push(lifetime.to_string());
+            }
+            format!("dyn {}", bounds.join(" + "))
+        }
+        Some(("qualified_path", path)) => {
+            let self_type = render_type(&path["self_type"]);
+            let name = path["name"].as_str().unwrap_or("_");
+            match path.get("trait") {
+                Some(trait_) if !trait_.is_null() => {
+                    format!("<{} as {}>::{}", self_type, render_path(trait_), name)
+                }
+                _ => format!("{}::{}", self_type, name),
+            }
+        }
+        Some(("function_pointer", pointer)) => {
+            let header = render_fn_header(either(pointer, &["sig", "decl"]));
+            match header.output {
+                Some(ref output) => format!("fn({}) -> {}", header.args().join(", "), output),
+                None => format!("fn({})", header.args().join(", ")),
+            }
+        }
+        _ => "_".to_string(),
+    }
+}
+
+fn render_fn_header(sig: &Value) -> FnHeader {
+    let inputs = sig["inputs"].as_array()
+        .map(|inputs| inputs.iter().map(|input| {
+            let pat = input[0].as_str().unwrap_or("").to_string();
+            let pat = if pat == "_" { String::new() } else { pat };
+            (pat, render_type(&input[1]))
+        }).collect())
+        .unwrap_or_default();
+
+    FnHeader::new(inputs,
+                  render_output(&sig["output"]),
+                  flag(sig, &["is_c_variadic", "c_variadic"]))
+}
+
+fn convert_generics(generics: &Value) -> Generics {
+    let mut result = Generics::default();
+
+    for param in generics["params"].as_array().into_iter().flat_map(|params| params.iter()) {
+        let name = param["name"].as_str().unwrap_or("_").to_string();
+        match variant(&param["kind"]) {
+            Some(("lifetime", lifetime)) => {
+                let outlives: Vec<&str> = lifetime["outlives"].as_array()
+                    .map(|outlives| outlives.iter().filter_map(|l| l.as_str()).collect())
+                    .unwrap_or_default();
+                if outlives.is_empty() {
+                    result.lifetimes.push(name);
+                } else {
+                    result.lifetimes.push(format!("{}: {}", name, outlives.join(" + ")));
+                }
+            }
+            // `impl Trait` arguments show up as synthetic parameters.
+            Some(("type", ty)) if !flag(ty, &["is_synthetic", "synthetic"]) => {
+                result.ty_params.push(TyParam {
+                    name: name,
+                    bounds: render_bounds(&ty["bounds"]),
+                    default: ty["default"].as_object().map(|_| {
+                        Ty::from_name(render_type(&ty["default"]))
+                    }),
+                });
+            }
+            _ => (),
+        }
+    }
+
+    let predicates = generics["where_predicates"].as_array();
+    for predicate in predicates.into_iter().flat_map(|predicates| predicates.iter()) {
+        if let Some(("bound_predicate", predicate)) = variant(predicate) {
+            result.where_predicates.push(format!("{}: {}",
+                                                 render_type(&predicate["type"]),
+                                                 render_bounds(&predicate["bounds"]).join(" + ")));
+        }
+    }
+
+    result
+}
+
+fn convert_abi(abi: &Value) -> Abi {
+    let name = match variant(abi) {
+        Some((name, _)) => name,
+        None => return Abi::Rust,
+    };
+    match name {
+        "C" => Abi::C,
+        "System" => Abi::System,
+        "Cdecl" => Abi::Cdecl,
+        "Stdcall" => Abi::Stdcall,
+        "Fastcall" => Abi::Fastcall,
+        "Vectorcall" => Abi::Vectorcall,
+        "Thiscall" => Abi::Thiscall,
+        "Aapcs" => Abi::Aapcs,
+        "Win64" => Abi::Win64,
+        "SysV64" => Abi::SysV64,
+        _ => Abi::Rust,
+    }
+}
+
+fn convert_visibility(vis: &Value) -> Visibility {
+    match variant(vis) {
+        Some(("public", _)) => Visibility::Public,
+        Some(("crate", _)) => Visibility::Crate,
+        Some(("restricted", restricted)) => {
+            let path = restricted["path"].as_str().unwrap_or("").trim_left_matches("::");
+            Visibility::Restricted(ModPath::from(path.to_string()))
+        }
+        _ => Visibility::Inherited,
+    }
+}
+
+fn convert_attrs(item: &Value) -> Attributes {
+    let mut attrs = Attributes::new();
+    if let Some(docs) = item["docs"].as_str() {
+        attrs.doc_strings = docs.lines().map(|line| line.to_string()).collect();
+    }
+    if let Some(deprecation) = item["deprecation"].as_object() {
+        let field = |key: &str| deprecation.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+        attrs.deprecation = Some(Deprecation {
+            since: field("since"),
+            note: field("note"),
+        });
+    }
+    attrs
+}
+
+fn convert_span(span: &Value) -> Option<SourceLocation> {
+    let file = match span["filename"].as_str() {
+        Some(file) => file.to_string(),
+        None => return None,
+    };
+    let begin = &span["begin"];
+    Some(SourceLocation {
+        file: file,
+        line: begin[0].as_u64().unwrap_or(0) as usize,
+        col: begin[1].as_u64().unwrap_or(0) as usize,
+    })
+}
+
+impl<'a> RustdocCrate<'a> {
+    fn item(&self, id: &Value) -> Option<&'a Value> {
+        id_key(id).and_then(|key| self.index.get(&key))
+    }
+
+    /// The full path of an item listed in `paths`, starting with the crate name.
+    fn item_path(&self, key: &str) -> Option<ModPath> {
+        let segments = match self.paths[key]["path"].as_array() {
+            Some(segments) => segments,
+            None => return None,
+        };
+        let mut path = ModPath::new();
+        path.push_string(self.crate_info.name.clone());
+        for segment in segments.iter().skip(1).filter_map(|segment| segment.as_str()) {
+            path.push_string(segment.to_string());
+        }
+        Some(path)
+    }
+
+    fn doc(&self, item: &Value, mod_path: ModPath, inner_data: DocInnerData,
+           links: DocRelatedItems) -> Documentation {
+        Documentation {
+            name: item["name"].as_str().unwrap_or("").to_string(),
+            attrs: convert_attrs(item),
+            crate_info: self.crate_info.clone(),
+            mod_path: mod_path,
+            inner_data: inner_data,
+            visibility: Some(convert_visibility(&item["visibility"])),
+            source: convert_span(&item["span"]),
+            links: links,
+        }
+    }
+
+    fn convert_function(&self, item: &Value, function: &Value, path: ModPath) -> Documentation {
+        let header = &function["header"];
+        let doc = FnDoc(Function {
+            header: render_fn_header(either(function, &["sig", "decl"])),
+            generics: convert_generics(&function["generics"]),
+            unsafety: if flag(header, &["is_unsafe", "unsafe"]) {
+                Unsafety::Unsafe
+            } else {
+                Unsafety::Normal
+            },
+            constness: if flag(header, &["is_const", "const"]) {
+                Constness::Const
+            } else {
+                Constness::NotConst
+            },
+            abi: convert_abi(&header["abi"]),
+            kind: FnKind::ItemFn,
+            is_foreign: false,
+            is_async: flag(header, &["is_async", "async"]),
+        });
+        self.doc(item, path, doc, BTreeMap::new())
+    }
+
+    fn convert_struct(&self, item: &Value, struct_: &Value, path: ModPath) -> Documentation {
+        let (kind, field_ids) = match variant(&struct_["kind"]) {
+            Some(("tuple", ids)) => (StructKind::Tuple, ids),
+            Some(("plain", plain)) => (StructKind::Named, &plain["fields"]),
+            _ => (StructKind::Unit, &NULL),
+        };
+
+        // Tuple fields that aren't public are listed as `null`.
+        let fields: Vec<StructField> = field_ids.as_array().into_iter()
+            .flat_map(|ids| ids.iter())
+            .filter_map(|id| self.item(id))
+            .map(|field| {
+                let name = field["name"].as_str().unwrap_or("").to_string();
+                StructField {
+                    ident: if kind == StructKind::Tuple { None } else { Some(name.clone()) },
+                    vis: convert_visibility(&field["visibility"]),
+                    ty: Ty::from_name(render_type(&field["inner"]["struct_field"])),
+                    attrs: convert_attrs(field),
+                    path: ModPath::join(&path, &ModPath::from(name)),
+                }
+            })
+            .collect();
+
+        let field_links = fields.iter()
+            .filter_map(|field| field.ident.clone().map(|ident| DocLink {
+                name: ident,
+                path: field.path.clone(),
+            }))
+            .collect();
+        let mut links = BTreeMap::new();
+        links.insert(DocType::StructField, field_links);
+
+        let generics = convert_generics(&struct_["generics"]);
+        self.doc(item, path, StructDoc(Struct { kind: kind, fields: fields, generics: generics }),
+                 links)
+    }
+
+    /// Converts a trait, followed by its items.
+    fn convert_trait(&self, item: &Value, trait_: &Value, path: ModPath) -> Vec<Documentation> {
+        let mut docs = Vec::new();
+        let mut links: DocRelatedItems = BTreeMap::new();
+        for ty in &[DocType::AssocConst, DocType::TraitItemMethod, DocType::ProvidedMethod,
+                    DocType::AssocType, DocType::Macro] {
+            links.insert(ty.clone(), Vec::new());
+        }
+
+        let items = trait_["items"].as_array().into_iter().flat_map(|ids| ids.iter());
+        for trait_item in items.filter_map(|id| self.item(id)) {
+            let name = trait_item["name"].as_str().unwrap_or("").to_string();
+            let item_path = ModPath::join(&path, &ModPath::from(name.clone()));
+
+            let (node, link_type) = match variant(&trait_item["inner"]) {
+                Some(("function", function)) => {
+                    let header = &function["header"];
+                    let sig = MethodSig {
+                        unsafety: if flag(header, &["is_unsafe", "unsafe"]) {
+                            Unsafety::Unsafe
+                        } else {
+                            Unsafety::Normal
+                        },
+                        constness: if flag(header, &["is_const", "const"]) {
+                            Constness::Const
+                        } else {
+                            Constness::NotConst
+                        },
+                        abi: convert_abi(&header["abi"]),
+                        header: render_fn_header(either(function, &["sig", "decl"])),
+                    };
+                    let provided = flag(function, &["has_body"]);
+                    let link_type = if provided {
+                        DocType::ProvidedMethod
+                    } else {
+                        DocType::TraitItemMethod
+                    };
+                    (TraitItemKind::Method(sig, provided), link_type)
+                }
+                Some(("assoc_const", constant)) => {
+                    let ty = Ty::from_name(render_type(either(constant, &["type", "type_"])));
+                    let default = either(constant, &["value", "default"]).as_str()
+                        .map(|s| s.to_string());
+                    (TraitItemKind::Const(ty, default), DocType::AssocConst)
+                }
+                Some(("assoc_type", assoc)) => {
+                    let default = either(assoc, &["type", "default"]);
+                    let default = if default.is_null() {
+                        None
+                    } else {
+                        Some(Ty::from_name(render_type(default)))
+                    };
+                    (TraitItemKind::Type(render_bounds(&assoc["bounds"]), default),
+                     DocType::AssocType)
+                }
+                _ => continue,
+            };
+
+            links.entry(link_type).or_insert_with(Vec::new).push(DocLink {
+                name: name,
+                path: item_path.clone(),
+            });
+            let mut doc = self.doc(trait_item, item_path, TraitItemDoc(TraitItem { node: node }),
+                                   BTreeMap::new());
+            doc.visibility = Some(Visibility::Inherited);
+            docs.push(doc);
+        }
+
+        let trait_doc = TraitDoc(Trait {
+            unsafety: if flag(trait_, &["is_unsafe", "unsafe"]) {
+                Unsafety::Unsafe
+            } else {
+                Unsafety::Normal
+            },
+            generics: convert_generics(&trait_["generics"]),
+            bounds: render_bounds(&trait_["bounds"]),
+        });
+        docs.push(self.doc(item, path, trait_doc, links));
+        docs
+    }
+}
+
+/// Converts the items of a rustdoc JSON file into documentation for `crate_info`. If
+/// `crate_info` has no version, the `crate_version` of the file is used. Items from other
+/// crates, and kinds of items that aren't supported yet, are skipped.
+pub fn convert_rustdoc_json(json: &str, mut crate_info: CrateInfo) -> Result<Vec<Documentation>> {
+    let root: Value = serde_json::from_str(json)
+        .chain_err(|| format!("Failed to parse rustdoc JSON for {}", crate_info.name))?;
+    if crate_info.version.is_empty() {
+        if let Some(version) = root["crate_version"].as_str() {
+            crate_info.version = version.to_string();
+        }
+    }
+    let index = match root["index"].as_object() {
+        Some(index) => index,
+        None => bail!("Rustdoc JSON for {} has no item index", crate_info.name),
+    };
+
+    let krate = RustdocCrate {
+        index: &root["index"],
+        paths: &root["paths"],
+        crate_info: &crate_info,
+    };
+
+    let mut docs = Vec::new();
+    for (key, item) in index {
+        if item["crate_id"].as_u64() != Some(0) {
+            continue;
+        }
+        // Only items with a path of their own are converted here. Fields and trait items are
+        // converted along with their parents.
+        let path = match krate.item_path(key) {
+            Some(path) => path,
+            None => continue,
+        };
+
+        match variant(&item["inner"]) {
+            Some(("function", function)) => docs.push(krate.convert_function(item, function, path)),
+            Some(("struct", struct_)) => docs.push(krate.convert_struct(item, struct_, path)),
+            Some(("trait", trait_)) => docs.extend(krate.convert_trait(item, trait_, path)),
+            _ => (),
+        }
+    }
+
+    docs.sort_by(|a, b| (&a.mod_path, &a.name).cmp(&(&b.mod_path, &b.name)));
+    Ok(docs)
+}
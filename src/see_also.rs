@@ -0,0 +1,66 @@
+//! Filling in the "See Also" sections of documents with items related to them by name.
+
+use std::collections::HashMap;
+
+use conversion::{DocLink, DocType, Documentation};
+use document::ModPath;
+
+/// The part of a snake_case name before the first underscore, like `read` in `read_line`.
+fn name_prefix(name: &str) -> Option<&str> {
+    match name.find('_') {
+        Some(i) if i > 0 => Some(&name[..i]),
+        _ => None,
+    }
+}
+
+/// Where siblings sharing a prefix are grouped: the parent module or type, the kind of item and
+/// the prefix.
+type SiblingKey = (Option<ModPath>, DocType, String);
+
+fn sibling_key(doc: &Documentation) -> Option<SiblingKey> {
+    name_prefix(&doc.name).map(|prefix| (doc.mod_path.parent(), doc.get_type(), prefix.to_string()))
+}
+
+/// Lists related items under `DocType::SeeAlso` in each document: the items named by its
+/// `#[doc(alias = "...")]` attributes, and its siblings of the same kind whose names share a
+/// prefix, like `read_line` and `read_to_end`. Documents that already list related items are
+/// left alone.
+pub fn add_see_also(docs: &mut [Documentation]) {
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut siblings: HashMap<SiblingKey, Vec<usize>> = HashMap::new();
+    for (i, doc) in docs.iter().enumerate() {
+        by_name.entry(&doc.name).or_insert_with(Vec::new).push(i);
+        if let Some(key) = sibling_key(doc) {
+            siblings.entry(key).or_insert_with(Vec::new).push(i);
+        }
+    }
+
+    let related: Vec<Vec<DocLink>> = docs.iter().enumerate().map(|(i, doc)| {
+        if !doc.see_also().is_empty() {
+            return Vec::new();
+        }
+
+        let mut indices: Vec<usize> = doc.attrs.aliases.iter()
+            .flat_map(|alias| by_name.get(alias.as_str()).into_iter().flat_map(|found| found.iter()))
+            .cloned()
+            .collect();
+        if let Some(found) = sibling_key(doc).and_then(|key| siblings.get(&key)) {
+            indices.extend(found.iter().cloned());
+        }
+        indices.sort();
+        indices.dedup();
+
+        let mut links: Vec<DocLink> = indices.into_iter()
+            .filter(|&j| j != i)
+            .map(|j| DocLink { name: docs[j].name.clone(), path: docs[j].mod_path.clone() })
+            .collect();
+        links.sort_by(|a, b| a.name.cmp(&b.name));
+        links
+    }).collect();
+
+    for (doc, links) in docs.iter_mut().zip(related) {
+        if !links.is_empty() {
+            doc.links.insert(DocType::SeeAlso, links);
+        }
+    }
+}
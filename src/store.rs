@@ -1,18 +1,30 @@
 use conversion::Documentation;
-use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::btree_map::Entry;
 use std::fmt;
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Write};
+use std::mem;
+use std::ops::Deref;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
 
 use bincode::{self, Infinite};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use log::LogLevel;
 use serde::de::DeserializeOwned;
 use serde::ser::Serialize;
+use serde_json;
 use strsim::levenshtein;
 
-use conversion::DocType;
+use conversion::{DocType, ModuleDoc, Visibility};
 use document::CrateInfo;
-use document::ModPath;
+use generation::ast_ty_wrappers;
+use document::{ModPath, escape_file_segment};
 use paths;
 use ::errors::*;
 
@@ -29,56 +41,178 @@ fn create_or_open_file<T: AsRef<Path>>(path: T) -> Result<File> {
         OpenOptions::new()
             .read(true)
             .write(true)
+            .truncate(true)
             .open(path_as)
             .chain_err(|| format!("Could not open file {}", path_as.display()))
     }
 }
 
+/// Returns true if the file at `path` already holds exactly `data`.
+fn file_has_contents(path: &Path, data: &[u8]) -> bool {
+    let mut existing = Vec::new();
+    match File::open(path) {
+        Ok(mut file) => file.read_to_end(&mut existing).is_ok() && existing.as_slice() == data,
+        Err(_) => false,
+    }
+}
+
+/// The first bytes of a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Gzips data before it is written to disk.
+fn compress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::Default);
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Undoes `compress`. Data without the gzip magic bytes was written before files were compressed,
+/// and is returned as is.
+fn decompress(data: Vec<u8>) -> io::Result<Vec<u8>> {
+    if !data.starts_with(&GZIP_MAGIC) {
+        return Ok(data);
+    }
+    let mut decompressed = Vec::new();
+    GzDecoder::new(data.as_slice())?.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Reads the file at `path`, decompressing it if needed.
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut data))
+        .chain_err(|| format!("Failed to read file {}", path.display()))?;
+    decompress(data).chain_err(|| format!("Could not decompress file {}", path.display()))
+}
+
+/// Compresses `data` and writes it to `path`.
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    let data = compress(data)
+        .chain_err(|| format!("Could not compress data for {}", path.display()))?;
+
+    // Leave unchanged files alone so regenerating documentation doesn't touch their mtimes.
+    if file_has_contents(path, data.as_slice()) {
+        return Ok(());
+    }
+
+    let mut file = create_or_open_file(path)?;
+    file.write_all(data.as_slice())
+        .chain_err(|| format!("Failed to write file {}", path.display()))
+}
+
+/// How documents are serialized on disk.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum StoreFormat {
+    /// Compact and fast to load.
+    Bincode,
+    /// Readable with other tools, but larger and slower. The default.
+    Json,
+}
+
+impl Default for StoreFormat {
+    fn default() -> Self {
+        StoreFormat::Json
+    }
+}
+
+/// Written before JSON data, so it can be told apart from bincode, which has no header.
+const JSON_HEADER: &[u8] = b"odoc-json\n";
+
+/// Reads an object written by `serialize_object` or `serialize_object_as` in either format.
 pub fn deserialize_object<S, T>(path: T) -> Result<S>
     where S: DeserializeOwned,
           T: AsRef<Path>
 {
     let path_as = path.as_ref();
-    let mut data: Vec<u8> = Vec::new();
-    let mut bincoded_file = File::open(&path_as)
-        .chain_err(|| format!("Could not open file {}", path_as.display()))?;
-
-    bincoded_file.read_to_end(&mut data)
-        .chain_err(|| format!("Failed to read file {}", path_as.display()))?;
-    let result = bincode::deserialize(data.as_slice())
-        .chain_err(|| format!("Could not deserialize file at {}", path_as.display()))?;
+    let data = read_file(path_as)?;
+    let result = if data.starts_with(JSON_HEADER) {
+        serde_json::from_slice(&data[JSON_HEADER.len()..])
+            .chain_err(|| format!("Could not deserialize file at {}", path_as.display()))?
+    } else {
+        bincode::deserialize(data.as_slice())
+            .chain_err(|| format!("Could not deserialize file at {}", path_as.display()))?
+    };
 
     Ok(result)
 }
 
+/// Writes `data` to `path` in bincode, gzipped. Used for the store index, whose version header
+/// `Store::load_from_file` reads as bincode whatever format its documents are in.
 pub fn serialize_object<S, T>(data: &S, path: T) -> Result<()>
     where S: Serialize,
           T: AsRef<Path>
 {
-    let path_as = path.as_ref();
+    serialize_object_as(data, path, StoreFormat::Bincode)
+}
 
-    let data = bincode::serialize(data, Infinite)
-        .chain_err(|| format!("Could not serialize data for {}", path_as.display()))?;
+/// Writes `data` to `path` in the given format, gzipped.
+pub fn serialize_object_as<S, T>(data: &S, path: T, format: StoreFormat) -> Result<()>
+    where S: Serialize,
+          T: AsRef<Path>
+{
+    let path_as = path.as_ref();
 
-    let mut bincoded_file = create_or_open_file(path_as)?;
-    bincoded_file.write(data.as_slice())
-        .chain_err(|| format!("Failed to write file {}", path_as.display()))?;
+    let data = match format {
+        StoreFormat::Bincode => bincode::serialize(data, Infinite)
+            .chain_err(|| format!("Could not serialize data for {}", path_as.display()))?,
+        StoreFormat::Json => {
+            let mut json = JSON_HEADER.to_vec();
+            serde_json::to_writer(&mut json, data)
+                .chain_err(|| format!("Could not serialize data for {}", path_as.display()))?;
+            json
+        }
+    };
 
-    Ok(())
+    write_file(path_as, data.as_slice())
 }
 
+/// Version of the store index format, written at the start of the store file. Bump this whenever
+/// the layout of `Store` changes so old indices are rejected instead of misread.
+pub const STORE_VERSION: u32 = 23;
+
+/// How many documents a store opened with `Store::open_lazy` keeps in memory.
+pub const LAZY_CACHE_SIZE: usize = 64;
+
+/// How many re-exports in a row a lookup follows before giving up, in case they form a cycle.
+const MAX_REEXPORT_DEPTH: usize = 8;
+
 type CrateVersion = String;
 type CrateName = String;
 
 /// Mapping of version strings for a crate to the documentation for that crate version.
-type CrateVersions = HashMap<CrateVersion, Docset>;
+type CrateVersions = BTreeMap<CrateVersion, Docset>;
 
 /// Top-level storage of all crates and their documents, organized by version.
-type DocumentCorpus = HashMap<CrateName, CrateVersions>;
+type DocumentCorpus = BTreeMap<CrateName, CrateVersions>;
 
 /// Mapping from module path keywords to the full module paths that use those keywords. Used for
 /// quick lookup of documentation based on keywords.
-type ModuleExpansions = HashMap<String, HashSet<String>>;
+type ModuleExpansions = BTreeMap<String, BTreeSet<String>>;
+
+/// A map from the full path of a type or trait to the names of the methods documented on it.
+type MethodIndex = BTreeMap<ModPath, BTreeSet<String>>;
+
+/// The on-disk form of the store index.
+#[derive(Serialize)]
+struct StoreFile<'a> {
+    version: u32,
+    store: &'a Store,
+}
+
+/// The exported form of a whole store, as written by `Store::to_json`.
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    version: u32,
+    documents: Vec<&'a Documentation>,
+}
+
+/// The exported form of a whole store, as read by `Store::from_json`.
+#[derive(Deserialize)]
+struct JsonImport {
+    version: u32,
+    documents: Vec<Documentation>,
+}
 
 /// The central point for retrieving documentation. Stores a map of crate names to their versions,
 /// which map to their individual documentation stores. Also contains a keyword prefix map for
@@ -91,31 +225,221 @@ pub struct Store {
     /// A map from individual module path segments to fully resolved module paths that use them.
     /// "vec" => ["std::vec::Vec", ...]
     module_expansions: ModuleExpansions,
+
+    /// "crate::vec::Vec" => ["new", "push", ...]
+    methods: MethodIndex,
+
+    /// The format documents are saved in. The store index itself is always bincode.
+    format: StoreFormat,
+
+    /// Documents already read from disk, if the store was opened with `open_lazy`.
+    #[serde(skip_serializing, skip_deserializing)]
+    cache: Option<RefCell<DocCache>>,
+
+    /// The module paths of every document in the latest version of each crate, sorted. Rebuilt
+    /// from `items` whenever they change, so it isn't saved.
+    #[serde(skip_serializing, skip_deserializing)]
+    modpaths: Vec<ModPath>,
 }
 
 impl Store {
-    pub fn new() -> Self {
+    /// Creates an empty store that saves documents in `format`.
+    pub fn new(format: StoreFormat) -> Self {
         Store {
-            items: HashMap::new(),
-            module_expansions: HashMap::new(),
+            items: BTreeMap::new(),
+            module_expansions: BTreeMap::new(),
+            methods: BTreeMap::new(),
+            format: format,
+            cache: None,
+            modpaths: Vec::new(),
         }
     }
 
-    pub fn load() -> Self {
-        match Store::load_from_disk() {
-            Ok(store) => store,
-            Err(_)    => Store::new(),
+    /// Loads the store index from the doc registry, or an empty store if none was saved yet.
+    pub fn load() -> Result<Self> {
+        let store_file = paths::store_file_path()?;
+        Store::load_or_new(store_file)
+    }
+
+    /// Loads the store index at `path` like `load_from_file`, or an empty store if there is no
+    /// file there. A store written by another version of the format is still an error.
+    pub fn load_or_new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Store::new(StoreFormat::default()));
         }
+        Store::load_from_file(path)
     }
 
+    /// Writes any newly added documentation to disk, followed by the store index itself.
     pub fn save(&mut self) -> Result<()> {
+        self.save_documents()?;
+
         let store_file = paths::store_file_path()?;
-        serialize_object(self, store_file)
+        self.save_to_file(store_file)
+    }
+
+    /// The files `save` would write, without touching the disk: the documents added since the
+    /// last save, sorted, followed by the store index.
+    pub fn save_plan(&self) -> Result<Vec<PathBuf>> {
+        let mut plan = Vec::new();
+        for document in self.items.values().flat_map(|krate| krate.values())
+            .flat_map(|docset| docset.unsaved.iter())
+        {
+            plan.push(self.path_for_version(&document.mod_path, &document.crate_info.version)?);
+        }
+        plan.sort();
+        plan.dedup();
+
+        plan.push(paths::store_file_path()?);
+        Ok(plan)
+    }
+
+    /// Writes the store index to `path`, preceded by the format version.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        serialize_object(&StoreFile { version: STORE_VERSION, store: self }, path)
+    }
+
+    /// Writes the documentation added to every docset since it was last saved, each document to
+    /// the file `path_for` gives for it.
+    pub fn save_documents(&mut self) -> Result<()> {
+        let mut unsaved = Vec::new();
+        for krate in self.items.values_mut() {
+            for docset in krate.values_mut() {
+                unsaved.append(&mut docset.unsaved);
+            }
+        }
+
+        for document in unsaved {
+            let path = self.path_for_version(&document.mod_path, &document.crate_info.version)?;
+            document.save_to(&path, self.format)
+                .chain_err(|| format!("Could not save doc {} in docset", document.mod_path))?;
+        }
+        Ok(())
     }
 
     pub fn load_from_disk() -> Result<Self> {
         let store_file = paths::store_file_path()?;
-        deserialize_object(store_file)
+        Store::load_from_file(store_file)
+    }
+
+    /// Reads a store index written by `save_to_file`, failing if it was written by a different
+    /// version of the format.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = read_file(path)?;
+
+        let mut reader = data.as_slice();
+        let version: u32 = bincode::deserialize_from(&mut reader, Infinite)
+            .chain_err(|| format!("Could not read store version from {}", path.display()))?;
+        if version != STORE_VERSION {
+            bail!(ErrorKind::StoreVersionMismatch(version, STORE_VERSION));
+        }
+
+        let mut store: Store = bincode::deserialize_from(&mut reader, Infinite)
+            .chain_err(|| format!("Could not deserialize store at {}", path.display()))?;
+        store.update_modpaths();
+        Ok(store)
+    }
+
+    /// Reads a store index like `load_from_file`, but keeps the most recently used documents
+    /// fetched with `get_doc` in memory, up to `LAZY_CACHE_SIZE` of them. Nothing besides the
+    /// index is read until a document is asked for.
+    pub fn open_lazy<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut store = Store::load_from_file(path)?;
+        store.cache = Some(RefCell::new(DocCache::new(LAZY_CACHE_SIZE)));
+        Ok(store)
+    }
+
+    /// Serializes every document in the store, including ones not yet saved, into a single JSON
+    /// document. Documents are ordered by crate, version and path so the output is stable.
+    pub fn to_json(&self) -> Result<String> {
+        let mut loaded = Vec::new();
+        let mut documents = Vec::new();
+        for krate in self.items.values() {
+            for docset in krate.values() {
+                for location in docset.documents.values() {
+                    match docset.unsaved_doc(&location.mod_path) {
+                        Some(doc) => documents.push(doc),
+                        None => {
+                            let doc: Documentation = deserialize_object(location.to_filepath()?)
+                                .chain_err(|| format!("Could not load doc {} for export", location))?;
+                            loaded.push(doc);
+                        }
+                    }
+                }
+            }
+        }
+        documents.extend(loaded.iter());
+        documents.sort_by(|a, b| {
+            (&a.crate_info.name, &a.crate_info.version, &a.mod_path)
+                .cmp(&(&b.crate_info.name, &b.crate_info.version, &b.mod_path))
+        });
+
+        let export = JsonExport {
+            version: STORE_VERSION,
+            documents: documents,
+        };
+        serde_json::to_string_pretty(&export)
+            .chain_err(|| "Could not serialize store to JSON")
+    }
+
+    /// Writes every document in the store, including ones not yet saved, to `w` as JSON Lines:
+    /// one compact JSON document per line, in the same order as `to_json`. Documents are read
+    /// and written one at a time, so large stores are never held in memory all at once.
+    pub fn write_jsonl<W: Write>(&self, mut w: W) -> Result<()> {
+        let mut locations = Vec::new();
+        for krate in self.items.values() {
+            for docset in krate.values() {
+                locations.extend(docset.documents.values().map(|loc| (docset, loc)));
+            }
+        }
+        locations.sort_by(|&(_, a), &(_, b)| {
+            (&a.crate_info.name, &a.crate_info.version, &a.mod_path)
+                .cmp(&(&b.crate_info.name, &b.crate_info.version, &b.mod_path))
+        });
+
+        for (docset, location) in locations {
+            let doc = match docset.unsaved_doc(&location.mod_path) {
+                Some(doc) => DocRef::Unsaved(doc),
+                None => {
+                    let doc: Documentation = deserialize_object(location.to_filepath()?)
+                        .chain_err(|| format!("Could not load doc {} for export", location))?;
+                    DocRef::Loaded(Arc::new(doc))
+                }
+            };
+            serde_json::to_writer(&mut w, &*doc)
+                .chain_err(|| format!("Could not serialize {} to JSON", location))?;
+            w.write_all(b"\n").chain_err(|| "Could not write JSON Lines export")?;
+        }
+
+        w.flush().chain_err(|| "Could not write JSON Lines export")
+    }
+
+    /// Reads a store from the output of `to_json`. The documents are kept in memory until the
+    /// store is saved.
+    pub fn from_json(json: &str) -> Result<Store> {
+        let import: JsonImport = serde_json::from_str(json)
+            .chain_err(|| "Could not deserialize store from JSON")?;
+        if import.version != STORE_VERSION {
+            bail!(ErrorKind::StoreVersionMismatch(import.version, STORE_VERSION));
+        }
+
+        let mut crates: BTreeMap<(CrateName, CrateVersion), (CrateInfo, Vec<Documentation>)> =
+            BTreeMap::new();
+        for doc in import.documents {
+            let key = (doc.crate_info.name.clone(), doc.crate_info.version.clone());
+            let crate_info = doc.crate_info.clone();
+            crates.entry(key).or_insert((crate_info, Vec::new())).1.push(doc);
+        }
+
+        let mut store = Store::new(StoreFormat::default());
+        for (_, (crate_info, documents)) in crates {
+            let mut docset = Docset::new();
+            docset.add_docs(documents);
+            store.add_docset(crate_info, docset);
+        }
+        Ok(store)
     }
 
     /// Add documentation for a specific version of a crate.
@@ -123,26 +447,206 @@ impl Store {
         // TODO: Any way to remove old module expansions if docset is regenerated?
         for doc in docset.documents.values() {
             self.add_module_expansions(doc);
+            self.add_method(doc);
+        }
+        for alias in docset.reexports.keys() {
+            let alias = ModPath::from(format!("{}::{}", crate_info.name, alias));
+            self.add_path_expansions(&alias);
         }
 
-        let mut entry = self.items.entry(crate_info.name).or_insert(HashMap::new());
+        let mut entry = self.items.entry(crate_info.name).or_insert(BTreeMap::new());
         entry.insert(crate_info.version, docset);
+        self.update_modpaths();
+    }
+
+    /// Folds the documentation of another store into this one. Where both stores have a document
+    /// at the same path in the same crate version, the one from `other` is kept.
+    pub fn merge(&mut self, other: Store) {
+        for (krate_name, versions) in other.items {
+            let krate = self.items.entry(krate_name.clone()).or_insert(BTreeMap::new());
+            for (version, docset) in versions {
+                match krate.entry(version.clone()) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(docset);
+                    }
+                    Entry::Occupied(mut entry) => {
+                        for path in entry.get_mut().merge(docset) {
+                            warn!("Replacing documentation for {} in {}-{}",
+                                  path, krate_name, version);
+                        }
+                    }
+                }
+            }
+        }
+
+        for (segment, paths) in other.module_expansions {
+            self.module_expansions.entry(segment).or_insert(BTreeSet::new()).extend(paths);
+        }
+
+        for (scope, methods) in other.methods {
+            self.methods.entry(scope).or_insert(BTreeSet::new()).extend(methods);
+        }
+        self.update_modpaths();
+    }
+
+    /// Deletes the documentation of every version of the crate `name`, both from the store and
+    /// from disk, then saves the store. Documents that are already gone from disk are skipped.
+    pub fn remove_crate(&mut self, name: &str) -> Result<()> {
+        let versions = match self.items.remove(name) {
+            Some(versions) => versions,
+            None => bail!("No documentation stored for crate {}", name),
+        };
+
+        for docset in versions.values() {
+            if let Some(location) = docset.documents.values().next() {
+                let crate_dir = paths::crate_doc_path(&location.crate_info)?;
+                match fs::remove_dir_all(&crate_dir) {
+                    Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
+                        bail!("Could not remove {}: {}", crate_dir.display(), e)
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let name = name.to_lowercase();
+        let in_crate = |path: &str| path == name || path.starts_with(&format!("{}::", name));
+        let expansions = mem::replace(&mut self.module_expansions, BTreeMap::new());
+        self.module_expansions = expansions.into_iter()
+            .map(|(segment, paths)| {
+                (segment, paths.into_iter().filter(|path| !in_crate(path)).collect::<BTreeSet<_>>())
+            })
+            .filter(|&(_, ref paths)| !paths.is_empty())
+            .collect();
+        let methods = mem::replace(&mut self.methods, BTreeMap::new());
+        self.methods = methods.into_iter()
+            .filter(|&(ref scope, _)| !in_crate(&scope.to_string().to_lowercase()))
+            .collect();
+        if let Some(ref cache) = self.cache {
+            cache.borrow_mut().entries.retain(|&(ref path, _)| {
+                !in_crate(&path.to_string().to_lowercase())
+            });
+        }
+        self.update_modpaths();
+
+        self.save()
+    }
+
+    /// What in the latest version of each crate is out of date with `src_files`: the modules with
+    /// items defined in a source file that changed since it was parsed, and the files in
+    /// `src_files` that no documentation was generated from. Recorded files that are missing from
+    /// `src_files` are only stale if they no longer exist, in which case `remove_deleted_sources`
+    /// drops their documentation.
+    pub fn needs_rebuild(&self, src_files: &[PathBuf]) -> StaleSources {
+        let mut stale = BTreeSet::new();
+        let mut recorded = HashSet::new();
+        for krate_versions in self.items.values() {
+            let docset = match latest_version(krate_versions) {
+                Some(version) => &krate_versions[version],
+                None => continue,
+            };
+
+            for (file, record) in docset.sources.iter() {
+                let path = Path::new(file);
+                recorded.insert(path);
+                let changed = match file_mtime(path) {
+                    Some(mtime) => mtime != record.modified && src_files.iter().any(|f| f == path),
+                    None => true,
+                };
+                if changed {
+                    stale.extend(record.items.iter().filter_map(|item| docset.module_of(item)));
+                }
+            }
+        }
+
+        StaleSources {
+            modules: stale.into_iter().collect(),
+            untracked: src_files.iter()
+                .filter(|file| !recorded.contains(file.as_path()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Deletes the documentation of items whose source files no longer exist, both from the store
+    /// and from disk. Returns the paths of the removed items.
+    pub fn remove_deleted_sources(&mut self) -> Result<Vec<ModPath>> {
+        let mut removed = Vec::new();
+        for docset in self.items.values_mut().flat_map(|versions| versions.values_mut()) {
+            removed.extend(docset.remove_deleted_sources()?);
+        }
+
+        let removed_strings: BTreeSet<String> = removed.iter()
+            .map(|path| path.to_string().to_lowercase())
+            .collect();
+        let expansions = mem::replace(&mut self.module_expansions, BTreeMap::new());
+        self.module_expansions = expansions.into_iter()
+            .map(|(segment, paths)| {
+                let paths = paths.into_iter()
+                    .filter(|path| !removed_strings.contains(path))
+                    .collect::<BTreeSet<_>>();
+                (segment, paths)
+            })
+            .filter(|&(_, ref paths)| !paths.is_empty())
+            .collect();
+        for path in removed.iter() {
+            self.methods.remove(path);
+            if let (Some(scope), Some(name)) = (path.parent(), path.name()) {
+                if let Some(methods) = self.methods.get_mut(&scope) {
+                    methods.remove(&name.identifier);
+                }
+            }
+        }
+        if let Some(ref cache) = self.cache {
+            cache.borrow_mut().entries.retain(|&(ref path, _)| !removed.contains(path));
+        }
+        self.update_modpaths();
+
+        Ok(removed)
     }
 
     /// Adds the keywords for module paths in the provided document to the prefix map used for
     /// document loookup.
     fn add_module_expansions(&mut self, doc: &StoreLocation) {
-        for segment in doc.mod_path.0.iter() {
-            let mod_path = doc.mod_path.to_string().to_lowercase();
+        self.add_path_expansions(&doc.mod_path);
+    }
+
+    fn add_path_expansions(&mut self, path: &ModPath) {
+        for segment in path.0.iter() {
+            let mod_path = path.to_string().to_lowercase();
 
             let entry = self.module_expansions
                 .entry(segment.identifier.to_lowercase())
-                .or_insert(HashSet::new());
+                .or_insert(BTreeSet::new());
 
             entry.insert(mod_path);
         }
     }
 
+    /// Indexes the document under its owning type or trait if it is a method.
+    fn add_method(&mut self, doc: &StoreLocation) {
+        match doc.doc_type {
+            DocType::Method | DocType::TraitItemMethod => (),
+            _ => return,
+        }
+
+        if let Some(scope) = doc.mod_path.parent() {
+            self.methods.entry(scope).or_insert(BTreeSet::new()).insert(doc.name.clone());
+        }
+    }
+
+    /// The names of the methods documented on the type or trait at the fully resolved `scope`.
+    pub fn get_methods(&self, scope: &ModPath) -> Option<&BTreeSet<String>> {
+        self.methods.get(scope)
+    }
+
+    /// The paths of the items documented directly below `parent` in the latest version of each
+    /// crate.
+    pub fn child_paths(&self, parent: &ModPath) -> Vec<ModPath> {
+        let locations = self.latest_locations();
+        parent.children(locations.iter().map(|loc| &loc.mod_path))
+    }
+
     pub fn all_locations(&self) -> Vec<StoreLocation> {
         let mut results = Vec::new();
         for krate in self.items.values() {
@@ -171,6 +675,361 @@ impl Store {
         results
     }
 
+    /// Searches the latest documentation of each crate for items whose names or
+    /// `#[doc(alias)]`es contain `query`, ignoring case. Exact matches come first, then prefix
+    /// matches, then the rest.
+    pub fn search(&self, query: &str) -> Vec<&StoreLocation> {
+        rank_matches(self.name_matches(&query.to_lowercase()))
+    }
+
+    /// The locations in the latest docsets whose names or aliases match the lowercased `query`,
+    /// unsorted. Items matched by several aliases are listed once for each.
+    fn name_matches(&self, query: &str) -> Vec<(NameMatch, &StoreLocation)> {
+        let mut results = Vec::new();
+
+        for krate_versions in self.items.values() {
+            let docset = match latest_version(krate_versions) {
+                Some(version) => &krate_versions[version],
+                None => continue,
+            };
+            for loc in docset.documents.values() {
+                if let Some(quality) = name_match(query, &loc.name) {
+                    results.push((quality, loc));
+                }
+            }
+            for (alias, paths) in &docset.aliases {
+                if let Some(quality) = name_match(query, alias) {
+                    results.extend(paths.iter()
+                                   .filter_map(|path| docset.documents.get(path))
+                                   .map(|loc| (quality, loc)));
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Finds the items in the latest version of each crate whose paths end with `suffix`, so a
+    /// path can be given without its crate, like `collections::HashMap`. Every match is returned,
+    /// ordered by path, for the caller to choose between. An empty suffix matches nothing.
+    pub fn resolve_partial(&self, suffix: &ModPath) -> Vec<&StoreLocation> {
+        if suffix.0.is_empty() {
+            return Vec::new();
+        }
+
+        let mut results: Vec<&StoreLocation> = self.latest_locations()
+            .into_iter()
+            .filter(|loc| loc.mod_path.ends_with(suffix))
+            .collect();
+        results.sort_by(|a, b| a.mod_path.cmp(&b.mod_path));
+        results
+    }
+
+    /// Finds the functions and methods in the latest version of each crate whose argument and
+    /// return types are exactly those of `query`, like `(usize) -> bool`. Argument names aren't
+    /// compared, and a method's receiver counts as an argument of type `&Self` and so on.
+    /// Results are ordered by path. Every function document is read to compare its signature.
+    pub fn search_by_signature(&self, query: &str) -> Vec<&StoreLocation> {
+        let query = normalize_signature(query);
+        let mut results = Vec::new();
+        for krate_versions in self.items.values() {
+            let docset = match latest_version(krate_versions) {
+                Some(version) => &krate_versions[version],
+                None => continue,
+            };
+            for location in docset.documents.values() {
+                match location.doc_type {
+                    DocType::Function | DocType::Method | DocType::TraitItemMethod => (),
+                    _ => continue,
+                }
+
+                let signature = match docset.unsaved_doc(&location.mod_path) {
+                    Some(doc) => doc.fn_header().map(|header| header.type_signature()),
+                    None => match self.get_doc(&location.mod_path) {
+                        Ok(doc) => doc.fn_header().map(|header| header.type_signature()),
+                        Err(e) => {
+                            warn!("Skipping {}: {}", location, e);
+                            None
+                        }
+                    },
+                };
+                if signature.map_or(false, |signature| signature == query) {
+                    results.push(location);
+                }
+            }
+        }
+        results.sort_by(|a, b| a.mod_path.cmp(&b.mod_path));
+        results
+    }
+
+    /// Like `search`, but only returns items of the given type.
+    pub fn search_by_type(&self, query: &str, ty: DocType) -> Vec<&StoreLocation> {
+        self.search(query).into_iter().filter(|loc| loc.doc_type == ty).collect()
+    }
+
+    /// Searches the latest documentation of each crate for items whose names contain the
+    /// characters of `query` in order, so abbreviations like `HshMp` find `HashMap`. Results are
+    /// ranked by descending score, with shorter names first among equal scores, and at most
+    /// `limit` are returned.
+    pub fn fuzzy_search(&self, query: &str, limit: usize) -> Vec<&StoreLocation> {
+        let mut results: Vec<(i64, &StoreLocation)> = self.latest_locations()
+            .into_iter()
+            .filter_map(|loc| fuzzy_score(query, &loc.name).map(|score| (score, loc)))
+            .collect();
+
+        results.sort_by(|&(sa, a), &(sb, b)| {
+            sb.cmp(&sa)
+                .then_with(|| a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.mod_path.to_string().cmp(&b.mod_path.to_string()))
+        });
+        results.truncate(limit);
+
+        results.into_iter().map(|(_, loc)| loc).collect()
+    }
+
+    /// The module paths of every document in the latest version of each crate, sorted.
+    pub fn get_modpaths(&self) -> &[ModPath] {
+        &self.modpaths
+    }
+
+    fn update_modpaths(&mut self) {
+        let mut paths: Vec<ModPath> = self.latest_locations().into_iter()
+            .map(|loc| loc.mod_path.clone())
+            .collect();
+        paths.sort();
+        self.modpaths = paths;
+    }
+
+    /// Logs the paths from `get_modpaths` at debug level, one per line.
+    pub fn debug_dump_modpaths(&self) {
+        if !log_enabled!(LogLevel::Debug) {
+            return;
+        }
+        for path in self.get_modpaths() {
+            debug!("{}", path);
+        }
+    }
+
+    /// Counts the documents in every version of every crate, from the index alone.
+    pub fn stats(&self) -> StoreStats {
+        let mut stats = StoreStats {
+            crates: self.items.len(),
+            documents: 0,
+            modpaths: 0,
+            by_type: BTreeMap::new(),
+        };
+        let mut paths = HashSet::new();
+
+        for krate in self.items.values() {
+            for docset in krate.values() {
+                for loc in docset.documents.values() {
+                    stats.documents += 1;
+                    *stats.by_type.entry(loc.doc_type.clone()).or_insert(0) += 1;
+                    paths.insert(&loc.mod_path);
+                }
+            }
+        }
+
+        stats.modpaths = paths.len();
+        stats
+    }
+
+    /// The locations of all documents in the latest version of each crate.
+    fn latest_locations(&self) -> Vec<&StoreLocation> {
+        let mut results = Vec::new();
+        for krate_versions in self.items.values() {
+            if let Some(version) = latest_version(krate_versions) {
+                results.extend(krate_versions[version].documents.values());
+            }
+        }
+        results
+    }
+
+    /// Iterates over the documents in the latest version of each crate, ordered by crate and
+    /// path. Documents that haven't been saved yet are served from memory, and the rest are read
+    /// from disk as they're reached. Documents that can't be read are skipped.
+    pub fn iter(&self) -> Documents {
+        self.documents_where(|_| true)
+    }
+
+    /// The names of the crates documented in the store, sorted.
+    pub fn crates(&self) -> Vec<&str> {
+        self.items.keys().map(|name| name.as_str()).collect()
+    }
+
+    /// Like `iter`, but only yields the documents of the crate called `name`.
+    pub fn documents_in_crate(&self, name: &str) -> Documents {
+        self.documents_where(|krate| krate == name)
+    }
+
+    /// Iterates over the latest documents of the crates whose names `include` accepts.
+    fn documents_where<F>(&self, include: F) -> Documents
+        where F: Fn(&str) -> bool
+    {
+        let mut locations = Vec::new();
+        for (krate, krate_versions) in &self.items {
+            if !include(krate) {
+                continue;
+            }
+            if let Some(version) = latest_version(krate_versions) {
+                let docset = &krate_versions[version];
+                locations.extend(docset.documents.values().map(|loc| (docset, loc)));
+            }
+        }
+        locations.sort_by(|&(_, a), &(_, b)| {
+            (&a.crate_info.name, &a.mod_path).cmp(&(&b.crate_info.name, &b.mod_path))
+        });
+
+        Documents {
+            store: self,
+            locations: locations.into_iter(),
+            visibility: None,
+            module: None,
+        }
+    }
+
+    /// Checks the store for signs of conversion bugs: links to items of stored crates that
+    /// aren't documented, locations and method index entries without a readable document, and
+    /// paths documented twice in the same docset. Every problem found is reported in one error.
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        let known: HashSet<&ModPath> = self.items.values()
+            .flat_map(|versions| versions.values())
+            .flat_map(|docset| docset.documents.values())
+            .map(|loc| &loc.mod_path)
+            .collect();
+        // Paths can also resolve through re-exports.
+        let resolves = |path: &ModPath| {
+            known.contains(path) || path.head().map_or(false, |krate| {
+                self.latest_doc_with_match(&krate.identifier, path.clone()).is_some()
+            })
+        };
+
+        for (krate_name, versions) in &self.items {
+            for (version, docset) in versions {
+                for &(ref key, ref replaced, ref kept) in &docset.clashes {
+                    problems.push(format!("{} is documented more than once in {}-{}, as {} and {}",
+                                          key, krate_name, version, replaced, kept));
+                }
+
+                for location in docset.documents.values() {
+                    let doc = match docset.unsaved_doc(&location.mod_path) {
+                        Some(doc) => DocRef::Unsaved(doc),
+                        None => match location.to_filepath().and_then(deserialize_object) {
+                            Ok(doc) => DocRef::Loaded(Arc::new(doc)),
+                            Err(e) => {
+                                problems.push(format!("{} has no readable document: {}",
+                                                      location.mod_path, e));
+                                continue;
+                            }
+                        },
+                    };
+
+                    for link in doc.links.values().flat_map(|links| links.iter()) {
+                        // Links out to crates that aren't stored can't be checked.
+                        let stored = link.path.head()
+                            .map_or(false, |krate| self.items.contains_key(&krate.identifier));
+                        if stored && !resolves(&link.path) {
+                            problems.push(format!("{} links to {}, which isn't documented",
+                                                  location.mod_path, link.path));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (scope, methods) in &self.methods {
+            for method in methods {
+                let path = ModPath::from(format!("{}::{}", scope, method));
+                if !resolves(&path) {
+                    problems.push(format!("The method index lists {}, which isn't documented",
+                                          path));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ErrorKind::InconsistentStore(problems).into())
+        }
+    }
+
+    /// Loads the latest stored documentation for the given fully resolved module path.
+    pub fn load_doc(&self, mod_path: &ModPath) -> Result<Documentation> {
+        let krate_name = match mod_path.head() {
+            Some(segment) => segment.identifier,
+            None => bail!("Cannot load documentation for an empty module path"),
+        };
+
+        if self.latest_doc_with_match(&krate_name, mod_path.clone()).is_none() {
+            bail!("No documentation stored for {}", mod_path);
+        }
+
+        let path = self.path_for(mod_path)?;
+        deserialize_object(&path)
+            .chain_err(|| format!("Failed to load documentation for {} from {}",
+                                  mod_path, path.display()))
+    }
+
+    /// The file the latest documentation at `mod_path` is kept in, which is where `save` writes
+    /// it and `load_doc` reads it from. The path only depends on where the document is stored, so
+    /// it stays the same across runs. Paths not in the store get a file named after the path
+    /// alone, in the directory of the latest version of their crate.
+    pub fn path_for(&self, mod_path: &ModPath) -> Result<PathBuf> {
+        let krate_name = mod_path.head().map(|segment| segment.identifier).unwrap_or_default();
+        let version = self.items.get(&krate_name)
+            .and_then(latest_version)
+            .cloned()
+            .unwrap_or_default();
+        self.path_for_version(mod_path, &version)
+    }
+
+    /// Like `path_for`, but for the documentation in `version` of the crate instead of the
+    /// latest one. Only the latest version follows reexports, like `load_doc`.
+    fn path_for_version(&self, mod_path: &ModPath, version: &str) -> Result<PathBuf> {
+        let krate_name = mod_path.head().map(|segment| segment.identifier).unwrap_or_default();
+        let versions = self.items.get(&krate_name);
+        let is_latest = versions.and_then(latest_version).map_or(true, |latest| latest == version);
+        let location = if is_latest {
+            self.latest_doc_with_match(&krate_name, mod_path.clone())
+        } else {
+            let key = mod_path.tail().to_string().to_lowercase();
+            versions.and_then(|versions| versions.get(version))
+                .and_then(|docset| docset.documents.get(&key))
+        };
+
+        match location {
+            Some(location) => location.to_filepath(),
+            None => unstored_location(mod_path, krate_name, version).to_filepath(),
+        }
+    }
+
+    /// Like `load_doc`, but serves the document from memory if a lazily opened store has already
+    /// read it.
+    pub fn get_doc(&self, mod_path: &ModPath) -> Result<Arc<Documentation>> {
+        let cache = match self.cache {
+            Some(ref cache) => cache,
+            None => return self.load_doc(mod_path).map(Arc::new),
+        };
+
+        if let Some(doc) = cache.borrow_mut().get(mod_path) {
+            return Ok(doc);
+        }
+
+        let doc = Arc::new(self.load_doc(mod_path)?);
+        cache.borrow_mut().insert(mod_path.clone(), doc.clone());
+        Ok(doc)
+    }
+
+    /// Whether the document at `mod_path` is held in memory by a lazily opened store.
+    pub fn is_cached(&self, mod_path: &ModPath) -> bool {
+        match self.cache {
+            Some(ref cache) => cache.borrow().contains(mod_path),
+            None => false,
+        }
+    }
+
     /// Searches the documentation store for the given fully resolved module path string.
     fn retrieve_match(&self, mat: String) -> Option<&StoreLocation> {
         let krate_name = mat.split("::").next().unwrap().to_string();
@@ -179,14 +1038,29 @@ impl Store {
         self.latest_doc_with_match(&krate_name, path_in_krate)
     }
 
-    /// Retrieves the latest documentation for a crate matching the given module path
+    /// Retrieves the latest documentation for a crate matching the given module path. If the path
+    /// is a re-export, the documentation of the re-exported item is returned instead.
     fn latest_doc_with_match(&self, krate_name: &str, path_in_krate: ModPath) -> Option<&StoreLocation> {
+        self.latest_doc_following_reexports(krate_name, path_in_krate, MAX_REEXPORT_DEPTH)
+    }
+
+    fn latest_doc_following_reexports(&self,
+                                      krate_name: &str,
+                                      path_in_krate: ModPath,
+                                      depth: usize) -> Option<&StoreLocation> {
         // FIXME: Doesn't handle items that exist in old versions and removed in the latest version
         if let Some(krate_versions) = self.items.get(krate_name) {
             if let Some(version) = latest_version(krate_versions) {
                 krate_versions.get(version).and_then(|docset| {
-                    let path = path_in_krate.tail().to_string();
-                    docset.documents.get(&path)
+                    let path = path_in_krate.tail().to_string().to_lowercase();
+                    docset.documents.get(&path).or_else(|| {
+                        match docset.reexports.get(&path) {
+                            Some(original) if depth > 0 => {
+                                self.latest_reexported_doc(krate_name, original, depth - 1)
+                            }
+                            _ => None,
+                        }
+                    })
                 })
             } else {
                 None
@@ -195,6 +1069,50 @@ impl Store {
             None
         }
     }
+
+    /// Finds the documentation for the item re-exported from `original` by a crate. Imports from
+    /// other crates resolve through the crate root, like `krate::std::fmt`, so those are also
+    /// looked up without the leading crate name.
+    fn latest_reexported_doc(&self,
+                             krate_name: &str,
+                             original: &ModPath,
+                             depth: usize) -> Option<&StoreLocation> {
+        let in_crate = self.latest_doc_following_reexports(krate_name, original.clone(), depth);
+        if in_crate.is_some() {
+            return in_crate;
+        }
+
+        let external = original.tail();
+        match external.head() {
+            Some(segment) => {
+                self.latest_doc_following_reexports(&segment.identifier, external, depth)
+            }
+            None => None,
+        }
+    }
+}
+
+/// Spaces a signature query the way `FnHeader::type_signature` does, dropping an explicit
+/// `-> ()`.
+fn normalize_signature(query: &str) -> String {
+    let query = ast_ty_wrappers::tidy_whitespace(&query.replace("->", " -> "));
+    if query.ends_with(" -> ()") {
+        query[..query.len() - " -> ()".len()].to_string()
+    } else {
+        query
+    }
+}
+
+/// Where documentation at `mod_path` that isn't in the store would be kept. Its type isn't known,
+/// so it gets the file of a function, whose name has no doc type prefix.
+fn unstored_location(mod_path: &ModPath, krate_name: String, version: &str) -> StoreLocation {
+    let name = mod_path.name().map(|segment| segment.identifier).unwrap_or_default();
+    let crate_info = CrateInfo {
+        name: krate_name,
+        version: version.to_string(),
+        lib_path: None,
+    };
+    StoreLocation::new(name, crate_info, mod_path.clone(), DocType::Function)
 }
 
 fn latest_version(versions: &CrateVersions) -> Option<&CrateVersion> {
@@ -210,6 +1128,103 @@ fn latest_version(versions: &CrateVersions) -> Option<&CrateVersion> {
     res
 }
 
+/// How closely an item name matches a search query, best first.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum NameMatch {
+    Exact,
+    Prefix,
+    Substring,
+}
+
+/// Matches a lowercased query against an item name, ignoring case.
+fn name_match(query: &str, name: &str) -> Option<NameMatch> {
+    let name = name.to_lowercase();
+    if name == query {
+        Some(NameMatch::Exact)
+    } else if name.starts_with(query) {
+        Some(NameMatch::Prefix)
+    } else if name.contains(query) {
+        Some(NameMatch::Substring)
+    } else {
+        None
+    }
+}
+
+/// The kinds of items a search query can be limited to with a prefix, like `fn:map`.
+const QUERY_PREFIX_TYPES: [DocType; 13] = [
+    DocType::Function, DocType::Method, DocType::Module, DocType::Enum, DocType::Variant,
+    DocType::Struct, DocType::Union, DocType::StructField, DocType::Const, DocType::Static,
+    DocType::Typedef, DocType::Trait, DocType::Macro,
+];
+
+/// Splits a search query like `struct:Vec` into the kind of item it's limited to and the text to
+/// search for. Prefixes are the keywords from `DocType::as_str`. Queries without a known prefix,
+/// including paths like `vec::Vec`, are searched for as written.
+pub fn parse_query(query: &str) -> (Option<DocType>, String) {
+    if let Some(colon) = query.find(':') {
+        let (prefix, rest) = (&query[..colon], &query[colon + 1..]);
+        if !rest.starts_with(':') {
+            let doc_type = QUERY_PREFIX_TYPES.iter().find(|ty| ty.as_str() == prefix);
+            if let Some(doc_type) = doc_type {
+                return (Some(doc_type.clone()), rest.to_string());
+            }
+        }
+    }
+
+    (None, query.to_string())
+}
+
+/// Orders search matches best first, then by path, keeping only the best match of each item.
+fn rank_matches(mut results: Vec<(NameMatch, &StoreLocation)>) -> Vec<&StoreLocation> {
+    results.sort_by(|&(ref qa, a), &(ref qb, b)| {
+        qa.cmp(qb).then_with(|| a.mod_path.to_string().cmp(&b.mod_path.to_string()))
+    });
+
+    // An item matched by both its name and an alias is only listed at its best match.
+    let mut seen = HashSet::new();
+    results.into_iter()
+        .map(|(_, loc)| loc)
+        .filter(|loc| seen.insert((&loc.crate_info.name, &loc.mod_path)))
+        .collect()
+}
+
+/// Scores how well an abbreviated query matches an item name, ignoring case, or returns `None` if
+/// the name does not contain every character of the query in order. Matched characters that start
+/// a word or continue the previous match raise the score; unmatched characters in the name lower
+/// it.
+fn fuzzy_score(query: &str, name: &str) -> Option<i64> {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let name_chars: Vec<char> = name.chars().collect();
+    let mut score = 0;
+    let mut matched = 0;
+    let mut last_match: Option<usize> = None;
+    let mut pos = 0;
+
+    for q in query.chars().map(&lower) {
+        let found = (pos..name_chars.len()).find(|&i| lower(name_chars[i]) == q);
+        let i = match found {
+            Some(i) => i,
+            None => return None,
+        };
+
+        let starts_word = i == 0 ||
+            !name_chars[i - 1].is_alphanumeric() ||
+            (name_chars[i].is_uppercase() && name_chars[i - 1].is_lowercase());
+        if starts_word {
+            score += 2;
+        }
+        if last_match.map_or(false, |last| last + 1 == i) {
+            score += 1;
+        }
+
+        matched += 1;
+        last_match = Some(i);
+        pos = i + 1;
+    }
+
+    Some(score - (name_chars.len() as i64 - matched))
+}
+
 /// Returns the module paths which contain all the provided path segments.
 fn get_all_matching_paths(query: String,
                           module_expansions: &ModuleExpansions)
@@ -235,7 +1250,7 @@ fn get_all_matching_paths(query: String,
 }
 
 /// Returns the strings that exist in both `target` and `other`.
-fn intersect(target: Vec<String>, other: &HashSet<String>) -> Vec<String> {
+fn intersect(target: Vec<String>, other: &BTreeSet<String>) -> Vec<String> {
     let mut in_common = Vec::new();
     let mut other_vec: Vec<_> = other.iter().collect();
 
@@ -249,16 +1264,172 @@ fn intersect(target: Vec<String>, other: &HashSet<String>) -> Vec<String> {
     in_common
 }
 
-/// Returns an integer that can be used to compare Semantic Versioning strings.
+/// Returns an integer that can be used to compare Semantic Versioning strings. Pre-release and
+/// build metadata are ignored, and versions that can't be parsed compare lowest.
 fn version_number_hash(version: &str) -> u64 {
-    let slice: Vec<String> = version.split(".").map(|s| s.to_string()).collect();
+    let release = version.split(|c| c == '-' || c == '+').next().unwrap_or("");
+    let slice: Vec<u64> = match release.split(".").map(|s| s.parse::<u64>()).collect() {
+        Ok(numbers) => numbers,
+        Err(_) => return 0,
+    };
     if slice.len() != 3 {
         return 0;
     }
-    let a = slice[0].parse::<u64>().unwrap();
-    let b = slice[1].parse::<u64>().unwrap();
-    let c = slice[2].parse::<u64>().unwrap();
-    (a << 16) + (b << 8) + c
+    (slice[0] << 16) + (slice[1] << 8) + slice[2]
+}
+
+/// What `Store::needs_rebuild` found out of date.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StaleSources {
+    /// Modules with items defined in a source file that changed or was deleted since it was
+    /// parsed, sorted.
+    pub modules: Vec<ModPath>,
+    /// Source files no documentation was generated from, like ones added to the crate since. The
+    /// modules they define aren't known until they are parsed.
+    pub untracked: Vec<PathBuf>,
+}
+
+impl StaleSources {
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.untracked.is_empty()
+    }
+}
+
+/// Summary counts of what a store holds, from `Store::stats`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StoreStats {
+    /// The number of crates. Several versions of one crate count as one.
+    pub crates: usize,
+    /// The number of documents across every crate version.
+    pub documents: usize,
+    /// The number of distinct module paths. A path documented in several versions of a crate is
+    /// counted once.
+    pub modpaths: usize,
+    /// The number of documents of each type.
+    pub by_type: BTreeMap<DocType, usize>,
+}
+
+impl fmt::Display for StoreStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} crates, {} documents at {} paths",
+               self.crates, self.documents, self.modpaths)?;
+        for (doc_type, count) in &self.by_type {
+            write!(f, "\n  {}: {}", doc_type, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// A document yielded by `Store::iter`, either held in memory by the store or read from disk.
+pub enum DocRef<'a> {
+    Unsaved(&'a Documentation),
+    Loaded(Arc<Documentation>),
+}
+
+impl<'a> Deref for DocRef<'a> {
+    type Target = Documentation;
+
+    fn deref(&self) -> &Documentation {
+        match *self {
+            DocRef::Unsaved(doc) => doc,
+            DocRef::Loaded(ref doc) => doc,
+        }
+    }
+}
+
+/// An iterator over the documents of a store, returned by `Store::iter`.
+pub struct Documents<'a> {
+    store: &'a Store,
+    locations: ::std::vec::IntoIter<(&'a Docset, &'a StoreLocation)>,
+    visibility: Option<Visibility>,
+    module: Option<ModPath>,
+}
+
+impl<'a> Documents<'a> {
+    /// Only yields items with the given visibility, as given by
+    /// `Documentation::effective_visibility`.
+    pub fn by_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// Only yields items somewhere below the module at `module`.
+    pub fn in_module(mut self, module: &ModPath) -> Self {
+        self.module = Some(module.clone());
+        self
+    }
+}
+
+impl<'a> Iterator for Documents<'a> {
+    type Item = DocRef<'a>;
+
+    fn next(&mut self) -> Option<DocRef<'a>> {
+        while let Some((docset, location)) = self.locations.next() {
+            // Checked before loading, so documents outside the module are never read.
+            if let Some(ref module) = self.module {
+                if !module.is_ancestor_of(&location.mod_path) {
+                    continue;
+                }
+            }
+
+            let doc = match docset.unsaved_doc(&location.mod_path) {
+                Some(doc) => DocRef::Unsaved(doc),
+                None => match self.store.get_doc(&location.mod_path) {
+                    Ok(doc) => DocRef::Loaded(doc),
+                    Err(e) => {
+                        warn!("Skipping {}: {}", location, e);
+                        continue;
+                    }
+                },
+            };
+
+            if let Some(ref visibility) = self.visibility {
+                if doc.effective_visibility() != *visibility {
+                    continue;
+                }
+            }
+            return Some(doc);
+        }
+        None
+    }
+}
+
+/// The documents most recently read by a lazily opened store, least recently used first.
+struct DocCache {
+    capacity: usize,
+    entries: Vec<(ModPath, Arc<Documentation>)>,
+}
+
+impl DocCache {
+    fn new(capacity: usize) -> Self {
+        DocCache {
+            capacity: capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    fn get(&mut self, mod_path: &ModPath) -> Option<Arc<Documentation>> {
+        let pos = match self.entries.iter().position(|&(ref path, _)| path == mod_path) {
+            Some(pos) => pos,
+            None => return None,
+        };
+        let entry = self.entries.remove(pos);
+        let doc = entry.1.clone();
+        self.entries.push(entry);
+        Some(doc)
+    }
+
+    fn insert(&mut self, mod_path: ModPath, doc: Arc<Documentation>) {
+        self.entries.retain(|&(ref path, _)| *path != mod_path);
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((mod_path, doc));
+    }
+
+    fn contains(&self, mod_path: &ModPath) -> bool {
+        self.entries.iter().any(|&(ref path, _)| path == mod_path)
+    }
 }
 
 /// A set of documentation for a specific crate version.
@@ -266,29 +1437,255 @@ fn version_number_hash(version: &str) -> u64 {
 pub struct Docset {
     /// Mapping from a crate-local module path string to the corresponding location
     /// "vec::Vec" => StoreLocation { name: Vec, /* ... */ }
-    pub documents: HashMap<String, StoreLocation>,
+    pub documents: BTreeMap<String, StoreLocation>,
+
+    /// Documentation added since the docset was last saved. Only the locations above are kept in
+    /// the store index; the documents themselves are written to their own files.
+    #[serde(skip_serializing, skip_deserializing)]
+    unsaved: Vec<Documentation>,
+
+    /// Mapping from the crate-local path of a `pub use` to the full path of the item it
+    /// re-exports. Glob re-exports aren't included.
+    /// "c" => "crate::a::B"
+    pub reexports: BTreeMap<String, ModPath>,
+
+    /// The source files documents were generated from, by path.
+    pub sources: BTreeMap<String, SourceRecord>,
+
+    /// Mapping from a lowercased `#[doc(alias)]` to the documents it names, by their keys in
+    /// `documents`.
+    /// "grow" => ["vec::vec::reserve"]
+    pub aliases: BTreeMap<String, BTreeSet<String>>,
+
+    /// Documents whose keys in `documents` differed only in case from one added before, so one
+    /// replaced the other. Holds the key and the paths of the replaced and the kept document.
+    /// "foo" => (crate::Foo, crate::foo)
+    pub clashes: Vec<(String, ModPath, ModPath)>,
+}
+
+/// A source file that documentation was generated from.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct SourceRecord {
+    /// The modification time of the file when its documents were generated, in nanoseconds since
+    /// the Unix epoch.
+    pub modified: u64,
+    /// The items defined in the file.
+    pub items: BTreeSet<ModPath>,
+}
+
+/// The modification time of the file at `path` in nanoseconds since the Unix epoch, or `None` if
+/// it can't be read.
+fn file_mtime(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_secs() * 1_000_000_000 + since.subsec_nanos() as u64)
 }
 
 impl Docset {
     pub fn new() -> Self {
         Docset {
-            documents: HashMap::new(),
+            documents: BTreeMap::new(),
+            unsaved: Vec::new(),
+            reexports: BTreeMap::new(),
+            sources: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+            clashes: Vec::new(),
         }
     }
 
-    fn add_doc(&mut self, document: Documentation) -> Result<()> {
-        let relative_path = document.mod_path.tail().to_string();
+    fn add_doc(&mut self, document: Documentation) {
+        let relative_path = document.mod_path.tail().to_string().to_lowercase();
         let store_location = document.to_store_location();
-        self.documents.insert(relative_path.to_lowercase(), store_location);
-        document.save()
-            .chain_err(|| format!("Could not add doc {} to docset", document.mod_path))
+        for alias in &document.attrs.aliases {
+            self.aliases.entry(alias.to_lowercase())
+                .or_insert(BTreeSet::new())
+                .insert(relative_path.clone());
+        }
+        let mod_path = store_location.mod_path.clone();
+        if let Some(replaced) = self.documents.insert(relative_path.clone(), store_location) {
+            if replaced.mod_path != mod_path {
+                self.clashes.push((relative_path, replaced.mod_path, mod_path));
+            }
+        }
+        if let ModuleDoc(ref module) = document.inner_data {
+            for reexport in module.reexports.iter().filter(|reexport| !reexport.is_glob) {
+                let alias = reexport.alias.tail().to_string().to_lowercase();
+                self.reexports.insert(alias, reexport.original.clone());
+            }
+        }
+        self.record_source(&document);
+        self.unsaved.push(document);
     }
 
-    pub fn add_docs(&mut self, documents: Vec<Documentation>) -> Result<()> {
+    pub fn add_docs(&mut self, documents: Vec<Documentation>) {
         for doc in documents.into_iter() {
-            self.add_doc(doc)?;
+            self.add_doc(doc);
+        }
+    }
+
+    /// Adds the documents of another docset for the same crate version, replacing any at the same
+    /// path. Returns the paths that were replaced.
+    fn merge(&mut self, other: Docset) -> Vec<String> {
+        let mut replaced = Vec::new();
+        let incoming: HashSet<String> = other.documents.keys().cloned().collect();
+        for (path, location) in other.documents {
+            if self.documents.insert(path.clone(), location).is_some() {
+                replaced.push(path);
+            }
+        }
+
+        self.reexports.extend(other.reexports);
+        self.clashes.extend(other.clashes);
+        for (alias, paths) in other.aliases {
+            self.aliases.entry(alias).or_insert(BTreeSet::new()).extend(paths);
+        }
+        for (file, record) in other.sources {
+            match self.sources.entry(file) {
+                Entry::Vacant(entry) => {
+                    entry.insert(record);
+                }
+                Entry::Occupied(mut entry) => {
+                    let existing = entry.get_mut();
+                    existing.modified = record.modified;
+                    existing.items.extend(record.items);
+                }
+            }
+        }
+
+        // Saving an unsaved document at a replaced path would write over the incoming one.
+        self.unsaved.retain(|doc| {
+            let path = doc.mod_path.tail().to_string().to_lowercase();
+            !incoming.contains(&path)
+        });
+        self.unsaved.extend(other.unsaved);
+
+        replaced
+    }
+
+    /// Finds the most recently added unsaved document at `mod_path`, if any.
+    fn unsaved_doc(&self, mod_path: &ModPath) -> Option<&Documentation> {
+        self.unsaved.iter().rev().find(|doc| doc.mod_path == *mod_path)
+    }
+
+    /// Records that `document` was generated from its source file as it is now, so that
+    /// `needs_rebuild` can tell when the file changes. Documents are added as soon as their
+    /// crate is parsed, so a file changed before they are saved still counts as changed.
+    fn record_source(&mut self, document: &Documentation) {
+        let file = match document.source {
+            Some(ref source) => &source.file,
+            None => return,
+        };
+        if let Some(mtime) = file_mtime(Path::new(file)) {
+            let record = self.sources.entry(file.clone()).or_insert(SourceRecord {
+                modified: mtime,
+                items: BTreeSet::new(),
+            });
+            record.modified = mtime;
+            record.items.insert(document.mod_path.clone());
+        }
+    }
+
+    /// The module an item belongs to, which is the item itself for modules.
+    fn module_of(&self, item: &ModPath) -> Option<ModPath> {
+        let key = item.tail().to_string().to_lowercase();
+        match self.documents.get(&key) {
+            Some(location) if location.doc_type == DocType::Module => Some(item.clone()),
+            Some(_) => item.parent(),
+            None => None,
+        }
+    }
+
+    /// Forgets the documents defined in source files that no longer exist and deletes their
+    /// files. Returns the paths of the forgotten documents.
+    fn remove_deleted_sources(&mut self) -> Result<Vec<ModPath>> {
+        let deleted: Vec<String> = self.sources.keys()
+            .filter(|file| file_mtime(Path::new(file)).is_none())
+            .cloned()
+            .collect();
+
+        let mut removed = Vec::new();
+        for file in deleted {
+            let record = self.sources.remove(&file).unwrap();
+            for item in record.items {
+                let key = item.tail().to_string().to_lowercase();
+                if let Some(location) = self.documents.remove(&key) {
+                    let path = location.to_filepath()?;
+                    match fs::remove_file(&path) {
+                        Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
+                            bail!("Could not remove {}: {}", path.display(), e)
+                        }
+                        _ => (),
+                    }
+                }
+                self.unsaved.retain(|doc| doc.mod_path != item);
+                removed.push(item);
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// A collection of stores, each kept in its own directory under a common root, that can be
+/// searched together.
+pub struct StoreRegistry {
+    /// Each store's directory and the store, ordered by directory.
+    stores: Vec<(PathBuf, Store)>,
+}
+
+impl StoreRegistry {
+    /// Opens the stores in the subdirectories of `root` that contain a store file. Documents are
+    /// only read when they're first needed. Stores that can't be opened are skipped.
+    pub fn scan<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref();
+        let entries = fs::read_dir(root)
+            .chain_err(|| format!("Could not read store directory {}", root.display()))?;
+
+        let mut dirs: Vec<PathBuf> = entries.filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.join(paths::STORE_FILENAME).is_file())
+            .collect();
+        dirs.sort();
+
+        let mut stores = Vec::new();
+        for dir in dirs {
+            match Store::open_lazy(dir.join(paths::STORE_FILENAME)) {
+                Ok(store) => stores.push((dir, store)),
+                Err(e) => warn!("Skipping store in {}: {}", dir.display(), e),
+            }
+        }
+
+        Ok(StoreRegistry { stores: stores })
+    }
+
+    /// The directories of the opened stores.
+    pub fn store_dirs(&self) -> Vec<&Path> {
+        self.stores.iter().map(|&(ref dir, _)| dir.as_path()).collect()
+    }
+
+    /// Like `Store::search`, but across every store. An item of a crate found in several stores
+    /// is listed once, from the first store.
+    pub fn search(&self, query: &str) -> Vec<&StoreLocation> {
+        let query = query.to_lowercase();
+        let matches = self.stores.iter()
+            .flat_map(|&(_, ref store)| store.name_matches(&query))
+            .collect();
+        rank_matches(matches)
+    }
+
+    /// Loads the documentation at `mod_path`, whose first segment names the crate, from the first
+    /// store that documents that crate.
+    pub fn resolve(&self, mod_path: &ModPath) -> Result<Arc<Documentation>> {
+        let krate_name = match mod_path.head() {
+            Some(segment) => segment.identifier,
+            None => bail!("Cannot load documentation for an empty module path"),
+        };
+
+        match self.stores.iter().find(|&&(_, ref store)| store.items.contains_key(&krate_name)) {
+            Some(&(_, ref store)) => store.get_doc(mod_path),
+            None => bail!("No store documents the crate {}", krate_name),
         }
-        Ok(())
     }
 }
 
@@ -316,18 +1713,19 @@ impl StoreLocation {
         }
     }
 
-    pub fn to_filepath(&self) -> PathBuf {
-        let mut path = paths::crate_doc_path(&self.crate_info).unwrap();
+    pub fn to_filepath(&self) -> Result<PathBuf> {
+        let mut path = paths::crate_doc_path(&self.crate_info)?;
         let doc_path = self.mod_path.to_filepath();
         path.push(doc_path);
-        let filename = format!("{}{}.odoc", self.doc_type.get_file_prefix(), self.name);
+        let filename = format!("{}{}.odoc", self.doc_type.get_file_prefix(),
+                               escape_file_segment(&self.name));
         path.push(filename);
-        path
+        Ok(path)
     }
 }
 
 impl fmt::Display for StoreLocation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} ({} {})", self.mod_path, self.crate_info.name, self.crate_info.version)
+        write!(f, "{} ({})", self.mod_path, self.crate_info)
     }
 }
@@ -0,0 +1,212 @@
+//! Pipe tables in Markdown doc comments, laid out as aligned columns for the terminal.
+
+use theme::{ColorSupport, Theme};
+
+/// The characters a table is drawn with.
+struct Decorations {
+    /// Separates the cells of a row.
+    column_separator: &'static str,
+    /// Separates the header row from the body.
+    header_rule: &'static str,
+    header_rule_crossing: &'static str,
+    ellipsis: &'static str,
+}
+
+const UNICODE_DECORATIONS: Decorations = Decorations {
+    column_separator: " │ ",
+    header_rule: "─",
+    header_rule_crossing: "─┼─",
+    ellipsis: "…",
+};
+
+/// For terminals without Unicode.
+const ASCII_DECORATIONS: Decorations = Decorations {
+    column_separator: " | ",
+    header_rule: "-",
+    header_rule_crossing: "-+-",
+    ellipsis: "...",
+};
+
+/// How the text of a column is aligned, from the colons in its delimiter cell.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+/// A table with a header row, as written in GitHub flavored Markdown.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Table {
+    pub alignments: Vec<Alignment>,
+    pub header: Vec<String>,
+    /// The body rows, each with as many cells as the header.
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Splits a row into its cells, dropping the pipes at either end. Pipes escaped as `\|` are kept
+/// in the cell.
+fn split_row(line: &str) -> Vec<String> {
+    let mut row = line.trim();
+    if row.starts_with('|') {
+        row = &row[1..];
+    }
+    if row.ends_with('|') && !row.ends_with("\\|") {
+        row = &row[..row.len() - 1];
+    }
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => {
+                cells.push(cell.trim().to_string());
+                cell.clear();
+            }
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// Reads the alignments out of a delimiter row like `| :--- | ---: |`, or `None` if the line
+/// isn't one.
+fn parse_delimiter_row(line: &str) -> Option<Vec<Alignment>> {
+    if !line.contains('-') {
+        return None;
+    }
+
+    let mut alignments = Vec::new();
+    for cell in split_row(line) {
+        let left = cell.starts_with(':');
+        let right = cell.ends_with(':');
+        let dashes = cell.trim_matches(':');
+        if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+            return None;
+        }
+        alignments.push(match (left, right) {
+            (true, true) => Alignment::Center,
+            (false, true) => Alignment::Right,
+            _ => Alignment::Left,
+        });
+    }
+    Some(alignments)
+}
+
+/// Reads a table starting at the first of `lines`, returning it with the number of lines it
+/// takes up. A table needs a header row followed by a delimiter row with the same number of
+/// cells, so pipes in ordinary prose aren't taken for one.
+pub fn parse_table(lines: &[&str]) -> Option<(Table, usize)> {
+    if lines.len() < 2 || !lines[0].contains('|') || !lines[1].contains('|') {
+        return None;
+    }
+
+    let header = split_row(lines[0]);
+    let alignments = match parse_delimiter_row(lines[1]) {
+        Some(ref alignments) if alignments.len() == header.len() => alignments.clone(),
+        _ => return None,
+    };
+
+    let rows: Vec<Vec<String>> = lines[2..].iter()
+        .take_while(|line| !line.trim().is_empty() && line.contains('|'))
+        .map(|line| {
+            let mut row = split_row(line);
+            row.resize(header.len(), String::new());
+            row
+        })
+        .collect();
+
+    let len = 2 + rows.len();
+    Some((Table { alignments: alignments, header: header, rows: rows }, len))
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Cuts `text` down to `width` columns, ending it with `ellipsis` if anything was cut and there's
+/// room for it.
+fn truncate(text: &str, width: usize, ellipsis: &str) -> String {
+    if text_width(text) <= width {
+        return text.to_string();
+    }
+    let ellipsis_width = text_width(ellipsis);
+    if width < ellipsis_width {
+        return text.chars().take(width).collect();
+    }
+
+    let mut cut: String = text.chars().take(width - ellipsis_width).collect();
+    cut.push_str(ellipsis);
+    cut
+}
+
+fn pad(text: &str, width: usize, alignment: Alignment) -> String {
+    let space = width - text_width(text);
+    let (before, after) = match alignment {
+        Alignment::Left => (0, space),
+        Alignment::Right => (space, 0),
+        Alignment::Center => (space / 2, space - space / 2),
+    };
+    format!("{}{}{}", " ".repeat(before), text, " ".repeat(after))
+}
+
+impl Table {
+    /// The width of each column, sized to its widest cell. If that doesn't fit in `width`, the
+    /// widest columns are narrowed until it does, down to a single character each.
+    fn column_widths(&self, width: usize, decorations: &Decorations) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.header.iter().map(|cell| text_width(cell)).collect();
+        for row in &self.rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(text_width(cell));
+            }
+        }
+
+        let separators = text_width(decorations.column_separator) * widths.len().saturating_sub(1);
+        let available = width.saturating_sub(separators);
+        while widths.iter().sum::<usize>() > available {
+            let widest = match widths.iter().enumerate().max_by_key(|&(_, w)| *w) {
+                Some((i, &w)) if w > 1 => i,
+                _ => break,
+            };
+            widths[widest] -= 1;
+        }
+        widths
+    }
+
+    fn render_row(&self, row: &[String], widths: &[usize], decorations: &Decorations) -> String {
+        let cells: Vec<String> = row.iter()
+            .zip(widths)
+            .zip(&self.alignments)
+            .map(|((cell, &width), &alignment)| {
+                pad(&truncate(cell, width, decorations.ellipsis), width, alignment)
+            })
+            .collect();
+        cells.join(decorations.column_separator).trim_right().to_string()
+    }
+
+    /// Lays the table out in columns fitting in `width`, with the header in the theme's header
+    /// style. Cells too wide for their column are cut short with an ellipsis. The borders are
+    /// drawn in ASCII if the theme asks for it.
+    pub fn render(&self, width: usize, theme: &Theme, support: Option<ColorSupport>) -> String {
+        let decorations = if theme.ascii { &ASCII_DECORATIONS } else { &UNICODE_DECORATIONS };
+        let widths = self.column_widths(width, decorations);
+
+        let mut lines = Vec::new();
+        lines.push(theme.header.paint(self.render_row(&self.header, &widths, decorations),
+                                      support));
+        let rule: Vec<String> = widths.iter()
+            .map(|&w| decorations.header_rule.repeat(w))
+            .collect();
+        lines.push(rule.join(decorations.header_rule_crossing));
+        for row in &self.rows {
+            lines.push(self.render_row(row, &widths, decorations));
+        }
+        lines.join("\n")
+    }
+}
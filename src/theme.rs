@@ -0,0 +1,205 @@
+use std::env;
+use std::sync::Mutex;
+
+use ansi_term::{Colour, Style};
+
+/// How many colors the terminal can show, which decides how theme colors are written out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorSupport {
+    /// The 16 standard colors.
+    Basic,
+    /// The 256-color palette.
+    Extended,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Guesses the color support of the terminal from `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        ColorSupport::from_env(&colorterm, &term)
+    }
+
+    pub fn from_env(colorterm: &str, term: &str) -> Self {
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Extended
+        } else {
+            ColorSupport::Basic
+        }
+    }
+}
+
+/// The styling of one kind of markup, like headers or code.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ThemeStyle {
+    pub fg: Option<(u8, u8, u8)>,
+    pub bold: bool,
+}
+
+impl ThemeStyle {
+    pub fn plain() -> Self {
+        ThemeStyle::default()
+    }
+
+    pub fn bold() -> Self {
+        ThemeStyle { fg: None, bold: true }
+    }
+
+    pub fn colored(r: u8, g: u8, b: u8, bold: bool) -> Self {
+        ThemeStyle { fg: Some((r, g, b)), bold: bold }
+    }
+
+    pub fn to_style(&self, support: ColorSupport) -> Style {
+        let mut style = Style::new();
+        if let Some((r, g, b)) = self.fg {
+            style = style.fg(degrade_color(r, g, b, support));
+        }
+        if self.bold {
+            style = style.bold();
+        }
+        style
+    }
+
+    /// Styles `text` for a terminal with the given color support, or leaves it as is if `support`
+    /// is `None`.
+    pub fn paint(&self, text: String, support: Option<ColorSupport>) -> String {
+        match support {
+            Some(support) => self.to_style(support).paint(text).to_string(),
+            None => text,
+        }
+    }
+}
+
+/// The colors used for each kind of markup, and for highlighting Rust code examples.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Theme {
+    pub header: ThemeStyle,
+    pub section: ThemeStyle,
+    pub rule: ThemeStyle,
+    pub code: ThemeStyle,
+    /// Visibility keywords like `pub` in signatures.
+    pub visibility: ThemeStyle,
+    pub keyword: ThemeStyle,
+    pub string: ThemeStyle,
+    pub comment: ThemeStyle,
+    pub ty: ThemeStyle,
+    /// Draw decorations like table borders with ASCII characters only. The text of the
+    /// documentation itself is left as it is.
+    pub ascii: bool,
+}
+
+/// The names of the built-in themes accepted by `Theme::by_name`.
+pub const THEME_NAMES: [&str; 3] = ["default", "mono", "solarized"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            header: ThemeStyle::bold(),
+            section: ThemeStyle::bold(),
+            rule: ThemeStyle::plain(),
+            code: ThemeStyle::plain(),
+            visibility: ThemeStyle::colored(0, 0, 238, true),
+            keyword: ThemeStyle::colored(205, 0, 205, false),
+            string: ThemeStyle::colored(0, 205, 0, false),
+            comment: ThemeStyle::colored(0, 205, 205, false),
+            ty: ThemeStyle::colored(205, 205, 0, false),
+            ascii: false,
+        }
+    }
+}
+
+impl Theme {
+    /// A theme without any colors or styling.
+    pub fn mono() -> Self {
+        Theme {
+            header: ThemeStyle::plain(),
+            section: ThemeStyle::plain(),
+            rule: ThemeStyle::plain(),
+            code: ThemeStyle::plain(),
+            visibility: ThemeStyle::plain(),
+            keyword: ThemeStyle::plain(),
+            string: ThemeStyle::plain(),
+            comment: ThemeStyle::plain(),
+            ty: ThemeStyle::plain(),
+            ascii: false,
+        }
+    }
+
+    pub fn solarized() -> Self {
+        Theme {
+            header: ThemeStyle::colored(181, 137, 0, true),
+            section: ThemeStyle::colored(38, 139, 210, true),
+            rule: ThemeStyle::colored(88, 110, 117, false),
+            code: ThemeStyle::colored(42, 161, 152, false),
+            visibility: ThemeStyle::colored(108, 113, 196, true),
+            keyword: ThemeStyle::colored(133, 153, 0, false),
+            string: ThemeStyle::colored(42, 161, 152, false),
+            comment: ThemeStyle::colored(88, 110, 117, false),
+            ty: ThemeStyle::colored(181, 137, 0, false),
+            ascii: false,
+        }
+    }
+
+    /// Looks up one of the built-in themes by name.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::default()),
+            "mono" => Some(Theme::mono()),
+            "solarized" => Some(Theme::solarized()),
+            _ => None,
+        }
+    }
+}
+
+lazy_static! {
+    static ref ACTIVE_THEME: Mutex<Theme> = Mutex::new(Theme::default());
+}
+
+/// Sets the theme used when rendering markup.
+pub fn set_active_theme(theme: Theme) {
+    *ACTIVE_THEME.lock().unwrap() = theme;
+}
+
+pub fn active_theme() -> Theme {
+    ACTIVE_THEME.lock().unwrap().clone()
+}
+
+/// The standard 8 colors with their usual RGB values, used to approximate colors on terminals that
+/// only support 16 colors.
+const BASIC_COLORS: [(Colour, (u8, u8, u8)); 8] = [
+    (Colour::Black, (0, 0, 0)),
+    (Colour::Red, (205, 0, 0)),
+    (Colour::Green, (0, 205, 0)),
+    (Colour::Yellow, (205, 205, 0)),
+    (Colour::Blue, (0, 0, 238)),
+    (Colour::Purple, (205, 0, 205)),
+    (Colour::Cyan, (0, 205, 205)),
+    (Colour::White, (229, 229, 229)),
+];
+
+fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Converts an RGB color to the closest one the terminal can show.
+pub fn degrade_color(r: u8, g: u8, b: u8, support: ColorSupport) -> Colour {
+    match support {
+        ColorSupport::TrueColor => Colour::RGB(r, g, b),
+        ColorSupport::Extended => {
+            // Index into the 6x6x6 color cube that starts at 16.
+            let level = |c: u8| ((c as u16 * 5 + 127) / 255) as u8;
+            Colour::Fixed(16 + 36 * level(r) + 6 * level(g) + level(b))
+        }
+        ColorSupport::Basic => {
+            BASIC_COLORS.iter()
+                .min_by_key(|&&(_, rgb)| color_distance(rgb, (r, g, b)))
+                .map(|&(colour, _)| colour)
+                .unwrap()
+        }
+    }
+}
@@ -7,14 +7,16 @@ use cursive::traits::*;
 use cursive::views::{EditView, LinearLayout, Dialog, SelectView, TextView};
 use driver::Driver;
 use markup::{MarkupDoc, Format};
-use store::{Store, StoreLocation};
+use store::{Store, StoreFormat, StoreLocation};
 use errors::*;
 
 lazy_static! {
-    static ref STORE: Mutex<Store> = Mutex::new(Store::load());
+    static ref STORE: Mutex<Store> = Mutex::new(Store::new(StoreFormat::default()));
 }
 
 pub fn run() -> Result<()> {
+    *STORE.lock().unwrap() = Store::load()?;
+
     let mut siv = Cursive::new();
 
     show_search_screen(&mut siv);
@@ -1 +1,3 @@
 mod test_paths;
+mod test_items;
+mod test_doc_type;
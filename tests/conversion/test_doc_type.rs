@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use bincode::{self, Infinite};
+use serde_json;
+
+use oxidoc::conversion::{DocType, DOC_TYPES};
+
+#[test]
+fn test_doc_type_keys() {
+    assert_eq!(serde_json::to_string(&DocType::TraitItemMethod).unwrap(), "\"trait_item_method\"");
+    assert_eq!(DocType::from_key("trait_item_method"), Some(DocType::TraitItemMethod));
+    assert_eq!(DocType::from_key("TraitItemMethod"), None);
+
+    for ty in DOC_TYPES.iter() {
+        assert_eq!(DocType::from_key(ty.key()).as_ref(), Some(ty));
+    }
+}
+
+#[test]
+fn test_doc_type_map_round_trip() {
+    let mut links = BTreeMap::new();
+    links.insert(DocType::Struct, vec!["a".to_string()]);
+    links.insert(DocType::TraitItemMethod, vec!["b".to_string(), "c".to_string()]);
+    links.insert(DocType::SeeAlso, vec![]);
+
+    let json = serde_json::to_string(&links).unwrap();
+    assert_eq!(json, r#"{"struct":["a"],"trait_item_method":["b","c"],"see_also":[]}"#);
+    let from_json: BTreeMap<DocType, Vec<String>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(from_json, links);
+
+    let encoded = bincode::serialize(&links, Infinite).unwrap();
+    let decoded: BTreeMap<DocType, Vec<String>> = bincode::deserialize(&encoded).unwrap();
+    assert_eq!(decoded, links);
+
+    let unknown: Result<BTreeMap<DocType, Vec<String>>, _> = serde_json::from_str(r#"{"nope":[]}"#);
+    assert!(unknown.is_err());
+}
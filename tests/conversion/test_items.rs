@@ -0,0 +1,787 @@
+use std::env;
+use std::fs::File;
+use std::io::Write;
+
+use oxidoc::conversion::*;
+use oxidoc::document::ModPath;
+use oxidoc::generation::ast_ty_wrappers::{Cfg, FnKind, StabilityLevel};
+
+use util::{source_to_docs, source_to_crate_docs, source_to_docs_with_locations, find_doc};
+use util;
+
+#[test]
+fn test_enum_variants() {
+    let docs = source_to_docs(r#"
+pub enum Shape {
+    Empty,
+    Circle(f32),
+    Rect { w: u32, h: u32 },
+}
+"#);
+
+    let shape = match find_doc(&docs, "crate::Shape").inner_data {
+        EnumDoc(ref e) => e,
+        ref other => panic!("Expected enum, got {:?}", other),
+    };
+
+    let names: Vec<&str> = shape.variants.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, vec!["Empty", "Circle", "Rect"]);
+
+    assert_eq!(shape.variants[0].kind, VariantKind::Unit);
+    match shape.variants[1].kind {
+        VariantKind::Tuple(ref fields) => {
+            let types: Vec<&str> = fields.iter().map(|f| f.ty.name.as_str()).collect();
+            assert_eq!(types, vec!["f32"]);
+        }
+        ref other => panic!("Expected tuple variant, got {:?}", other),
+    }
+    match shape.variants[2].kind {
+        VariantKind::Struct(ref fields) => {
+            let idents: Vec<Option<String>> = fields.iter().map(|f| f.ident.clone()).collect();
+            assert_eq!(idents, vec![Some("w".to_string()), Some("h".to_string())]);
+        }
+        ref other => panic!("Expected struct variant, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_struct_fields() {
+    let docs = source_to_docs(r#"
+pub struct Point {
+    pub x: i32,
+    y: Vec<String>,
+}
+"#);
+    let doc = find_doc(&docs, "crate::Point");
+
+    let fields = match doc.inner_data {
+        StructDoc(ref s) => &s.fields,
+        ref other => panic!("Expected struct, got {:?}", other),
+    };
+
+    let captured: Vec<(Option<String>, &str, Visibility, String)> = fields.iter()
+        .map(|f| (f.ident.clone(), f.ty.name.as_str(), f.vis.clone(), f.path.to_string()))
+        .collect();
+    assert_eq!(captured, vec![
+        (Some("x".to_string()), "i32", Visibility::Public, "crate::Point::x".to_string()),
+        (Some("y".to_string()), "Vec<String>", Visibility::Inherited, "crate::Point::y".to_string()),
+    ]);
+
+    let links = &doc.links[&DocType::StructField];
+    assert_eq!(links.iter().map(|l| l.path.to_string()).collect::<Vec<String>>(),
+               vec!["crate::Point::x", "crate::Point::y"]);
+}
+
+#[test]
+fn test_typedefs() {
+    let docs = source_to_docs(r#"
+pub type Id = u64;
+pub type Result<T> = ::std::result::Result<T, MyError>;
+"#);
+
+    match find_doc(&docs, "crate::Id").inner_data {
+        TypedefDoc(ref t) => {
+            assert_eq!(t.ty.name, "u64");
+            assert!(t.generics.is_empty());
+        }
+        ref other => panic!("Expected typedef, got {:?}", other),
+    }
+
+    match find_doc(&docs, "crate::Result").inner_data {
+        TypedefDoc(ref t) => {
+            assert_eq!(t.ty.name, "::std::result::Result<T, MyError>");
+            let params: Vec<&str> = t.generics.ty_params.iter().map(|p| p.name.as_str()).collect();
+            assert_eq!(params, vec!["T"]);
+        }
+        ref other => panic!("Expected typedef, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_statics() {
+    let docs = source_to_docs(r#"
+pub static GREETING: &str = "hello";
+pub static mut COUNTER: u32 = 0;
+"#);
+
+    match find_doc(&docs, "crate::GREETING").inner_data {
+        StaticDoc(ref s) => {
+            assert_eq!(s.ty.name, "&str");
+            assert_eq!(s.mutability, Mutability::Immutable);
+            assert_eq!(s.expr, Some("\"hello\"".to_string()));
+        }
+        ref other => panic!("Expected static, got {:?}", other),
+    }
+
+    match find_doc(&docs, "crate::COUNTER").inner_data {
+        StaticDoc(ref s) => {
+            assert_eq!(s.ty.name, "u32");
+            assert_eq!(s.mutability, Mutability::Mutable);
+            assert_eq!(s.expr, Some("0".to_string()));
+        }
+        ref other => panic!("Expected static, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_union_fields() {
+    let docs = source_to_docs(r#"
+pub union IntOrFloat {
+    pub i: u32,
+    f: f32,
+}
+"#);
+
+    match find_doc(&docs, "crate::IntOrFloat").inner_data {
+        UnionDoc(ref u) => {
+            let fields: Vec<(Option<String>, &str, Visibility)> = u.fields.iter()
+                .map(|f| (f.ident.clone(), f.ty.name.as_str(), f.vis.clone()))
+                .collect();
+            assert_eq!(fields, vec![(Some("i".to_string()), "u32", Visibility::Public),
+                                    (Some("f".to_string()), "f32", Visibility::Inherited)]);
+        }
+        ref other => panic!("Expected union, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_macros() {
+    let docs = source_to_docs(r#"
+/// Squares a number.
+#[macro_export]
+macro_rules! square {
+    ($x:expr) => { $x * $x };
+    () => { 0 };
+}
+
+#[macro_export]
+macro_rules! undocumented {
+    () => {};
+}
+
+macro_rules! not_exported {
+    () => {};
+}
+"#);
+
+    let square = find_doc(&docs, "crate::square");
+    assert_eq!(square.attrs.doc_strings, vec!["Squares a number.".to_string()]);
+    match square.inner_data {
+        MacroDoc(ref m) => assert_eq!(m.matchers, vec!["($x:expr)".to_string(), "()".to_string()]),
+        ref other => panic!("Expected macro, got {:?}", other),
+    }
+
+    let undocumented = find_doc(&docs, "crate::undocumented");
+    assert!(undocumented.attrs.doc_strings.is_empty());
+
+    assert!(docs.iter().all(|doc| doc.name != "not_exported"));
+}
+
+#[test]
+fn test_impl_methods() {
+    let docs = source_to_docs(r#"
+pub struct Point;
+
+impl Point {
+    pub fn new() -> Point { Point }
+    pub fn len(&self) -> f32 { 0.0 }
+}
+"#);
+
+    let point = find_doc(&docs, "crate::Point");
+    let links = |doc_type: DocType| -> Vec<String> {
+        point.links[&doc_type].iter().map(|link| link.path.to_string()).collect()
+    };
+    assert_eq!(links(DocType::AssocFn), vec!["crate::Point::new"]);
+    assert_eq!(links(DocType::Method), vec!["crate::Point::len"]);
+
+    for name in &["new", "len"] {
+        let method = find_doc(&docs, &format!("crate::Point::{}", name));
+        assert_eq!(method.mod_path.name().unwrap().identifier, *name);
+        assert_eq!(method.get_type(), DocType::Method);
+        match method.inner_data {
+            FnDoc(ref f) => assert_eq!(f.kind, FnKind::MethodFromImpl),
+            ref other => panic!("Expected function, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_trait_impls() {
+    let docs = source_to_docs(r#"
+pub trait Shape {
+    fn area(&self) -> f32;
+}
+
+pub struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> f32 { 1.0 }
+}
+
+impl<T> Shape for Vec<T> {
+    fn area(&self) -> f32 { 0.0 }
+}
+"#);
+
+    let shape = find_doc(&docs, "crate::Shape");
+    let mut implementors: Vec<(String, String)> = shape.links[&DocType::Implementor].iter()
+        .map(|link| (link.name.clone(), link.path.to_string()))
+        .collect();
+    implementors.sort();
+    assert_eq!(implementors, vec![("Square".to_string(), "crate::Square".to_string()),
+                                  ("Vec<T>".to_string(), "Vec".to_string())]);
+
+    let square = find_doc(&docs, "crate::Square");
+    let traits: Vec<(String, String)> = square.links[&DocType::ImplementedTrait].iter()
+        .map(|link| (link.name.clone(), link.path.to_string()))
+        .collect();
+    assert_eq!(traits, vec![("Shape".to_string(), "crate::Shape".to_string())]);
+}
+
+#[test]
+fn test_fn_generics() {
+    let docs = source_to_docs(r#"
+pub fn longest<'a, 'b: 'a>(x: &'a str, y: &'b str) -> &'a str { x }
+pub fn parse<T: Clone + Default, U = u32>(t: T) -> U { unimplemented!() }
+"#);
+
+    match find_doc(&docs, "crate::longest").inner_data {
+        FnDoc(ref f) => {
+            assert_eq!(f.generics.lifetimes, vec!["'a", "'b: 'a"]);
+            assert!(f.generics.ty_params.is_empty());
+        }
+        ref other => panic!("Expected function, got {:?}", other),
+    }
+
+    match find_doc(&docs, "crate::parse").inner_data {
+        FnDoc(ref f) => {
+            assert!(f.generics.lifetimes.is_empty());
+            let params = &f.generics.ty_params;
+            assert_eq!(params[0].name, "T");
+            assert_eq!(params[0].bounds, vec!["Clone", "Default"]);
+            assert_eq!(params[0].default, None);
+            assert_eq!(params[1].name, "U");
+            assert_eq!(params[1].default.as_ref().map(|ty| ty.name.as_str()), Some("u32"));
+        }
+        ref other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_where_clauses() {
+    let docs = source_to_docs(r#"
+pub fn spawn<F, T>(f: F) -> T where F: FnOnce() -> T + Send, T: Send + 'static { f() }
+pub trait Cache<K> where K: Eq + ::std::hash::Hash {}
+pub fn noop<T>(t: T) where {}
+"#);
+
+    match find_doc(&docs, "crate::spawn").inner_data {
+        FnDoc(ref f) => assert_eq!(f.generics.where_predicates,
+                                   vec!["F: FnOnce() -> T + Send", "T: Send + 'static"]),
+        ref other => panic!("Expected function, got {:?}", other),
+    }
+
+    match find_doc(&docs, "crate::Cache").inner_data {
+        TraitDoc(ref t) => assert_eq!(t.generics.where_predicates,
+                                      vec!["K: Eq + ::std::hash::Hash"]),
+        ref other => panic!("Expected trait, got {:?}", other),
+    }
+
+    match find_doc(&docs, "crate::noop").inner_data {
+        FnDoc(ref f) => assert!(f.generics.where_predicates.is_empty()),
+        ref other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_trait_bounds() {
+    let docs = source_to_docs(r#"
+pub trait Named {}
+pub trait Entity: Named + Clone + 'static {}
+"#);
+
+    match find_doc(&docs, "crate::Named").inner_data {
+        TraitDoc(ref t) => assert!(t.bounds.is_empty()),
+        ref other => panic!("Expected trait, got {:?}", other),
+    }
+
+    match find_doc(&docs, "crate::Entity").inner_data {
+        TraitDoc(ref t) => assert_eq!(t.bounds, vec!["Named", "Clone", "'static"]),
+        ref other => panic!("Expected trait, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_deprecation() {
+    let docs = source_to_docs(r#"
+#[deprecated(since = "0.2.0", note = "Use `bar` instead.")]
+pub fn foo() {}
+#[deprecated]
+pub fn old() {}
+pub fn bar() {}
+"#);
+
+    let foo = find_doc(&docs, "crate::foo").attrs.deprecation.clone().unwrap();
+    assert_eq!(foo.since, Some("0.2.0".to_string()));
+    assert_eq!(foo.note, Some("Use `bar` instead.".to_string()));
+
+    let old = find_doc(&docs, "crate::old").attrs.deprecation.clone().unwrap();
+    assert_eq!(old.since, None);
+    assert_eq!(old.note, None);
+
+    assert!(find_doc(&docs, "crate::bar").attrs.deprecation.is_none());
+}
+
+#[test]
+fn test_stability() {
+    let docs = source_to_docs(r#"
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn stable() {}
+#[unstable(feature = "fancy", issue = "1234")]
+#[rustc_deprecated(since = "1.2.0", reason = "not fancy enough")]
+pub fn unstable() {}
+"#);
+
+    let stable = find_doc(&docs, "crate::stable").attrs.stability.clone().unwrap();
+    assert_eq!(stable.level, StabilityLevel::Stable);
+    assert_eq!(stable.feature, Some("rust1".to_string()));
+    assert_eq!(stable.since, Some("1.0.0".to_string()));
+
+    let attrs = &find_doc(&docs, "crate::unstable").attrs;
+    let unstable = attrs.stability.clone().unwrap();
+    assert_eq!(unstable.level, StabilityLevel::Unstable);
+    assert_eq!(unstable.feature, Some("fancy".to_string()));
+    assert_eq!(unstable.since, None);
+
+    let deprecation = attrs.deprecation.clone().unwrap();
+    assert_eq!(deprecation.since, Some("1.2.0".to_string()));
+    assert_eq!(deprecation.note, Some("not fancy enough".to_string()));
+}
+
+#[test]
+fn test_cfg() {
+    let docs = source_to_docs(r#"
+#[cfg(all(unix, feature = "net"))]
+pub fn gated() {}
+#[cfg(windows)]
+#[cfg(not(any(test, debug_assertions)))]
+pub fn twice() {}
+pub fn always() {}
+"#);
+
+    let gated = find_doc(&docs, "crate::gated").attrs.cfg.clone().unwrap();
+    assert_eq!(gated, Cfg::All(vec![Cfg::Name("unix".to_string()),
+                                    Cfg::NameValue("feature".to_string(), "net".to_string())]));
+    assert_eq!(gated.to_string(), "unix and feature \"net\"");
+
+    let twice = find_doc(&docs, "crate::twice").attrs.cfg.clone().unwrap();
+    assert_eq!(twice.to_string(), "windows and not (test or debug_assertions)");
+
+    assert!(find_doc(&docs, "crate::always").attrs.cfg.is_none());
+}
+
+#[test]
+fn test_must_use() {
+    let docs = source_to_docs(r#"
+#[must_use]
+pub struct Guard;
+#[must_use = "the sum is returned, not stored"]
+pub fn add(a: u32, b: u32) -> u32 { a + b }
+pub enum Ignored { A }
+"#);
+
+    assert_eq!(find_doc(&docs, "crate::Guard").attrs.must_use, Some(String::new()));
+    assert_eq!(find_doc(&docs, "crate::add").attrs.must_use,
+               Some("the sum is returned, not stored".to_string()));
+    assert!(find_doc(&docs, "crate::Ignored").attrs.must_use.is_none());
+}
+
+#[test]
+fn test_unindent_line_docs() {
+    let docs = source_to_docs(r#"
+/// Runs a thing.
+///
+///     indented code
+///
+///   Less indented.
+pub fn run() {}
+"#);
+
+    let run = find_doc(&docs, "crate::run");
+    assert_eq!(run.attrs.doc_strings.join("\n"),
+               "Runs a thing.\n\n    indented code\n\n  Less indented.");
+}
+
+#[test]
+fn test_unindent_mixed_docs() {
+    let docs = source_to_docs(r#"
+/**
+ * Starts in a block.
+ *
+ *     let code = 1;
+ */
+///  Then a line.
+pub fn mixed() {}
+"#);
+
+    let mixed = find_doc(&docs, "crate::mixed");
+    assert_eq!(mixed.attrs.doc_strings.join("\n"),
+               "Starts in a block.\n\n    let code = 1;\n Then a line.");
+}
+
+#[test]
+fn test_doc_attribute_and_comment() {
+    let docs = source_to_docs(r#"
+#[doc = "First line."]
+/// Second line.
+#[doc = "Third line."]
+pub fn mixed() {}
+"#);
+
+    let mixed = find_doc(&docs, "crate::mixed");
+    assert_eq!(mixed.attrs.doc_strings,
+               vec!["First line.".to_string(),
+                    "Second line.".to_string(),
+                    "Third line.".to_string()]);
+}
+
+#[test]
+fn test_doc_include() {
+    let mut path = env::temp_dir();
+    path.push("oxidoc-doc-include.md");
+    File::create(&path).unwrap().write_all(b"Included text.").unwrap();
+
+    let src = format!(r#"
+/// Before.
+#[doc(include = "{}")]
+#[doc(include = "does-not-exist.md")]
+pub fn included() {{}}
+"#, path.display());
+    let docs = source_to_docs(&src);
+
+    let included = find_doc(&docs, "crate::included");
+    assert_eq!(included.attrs.doc_strings,
+               vec!["Before.".to_string(), "Included text.".to_string()]);
+}
+
+#[test]
+fn test_restricted_visibility() {
+    let docs = source_to_docs(r#"
+pub struct Fields {
+    pub a: u8,
+    pub(crate) b: u8,
+    pub(in a::b) c: u8,
+    pub(super) d: u8,
+    e: u8,
+}
+"#);
+
+    let fields = match find_doc(&docs, "crate::Fields").inner_data {
+        StructDoc(ref s) => s.fields.iter().map(|f| f.vis.clone()).collect::<Vec<Visibility>>(),
+        ref other => panic!("Expected struct, got {:?}", other),
+    };
+    assert_eq!(fields, vec![Visibility::Public,
+                            Visibility::Crate,
+                            Visibility::Restricted(ModPath::from("a::b".to_string())),
+                            Visibility::Restricted(ModPath::from("super".to_string())),
+                            Visibility::Inherited]);
+}
+
+#[test]
+fn test_renamed_reexport() {
+    let docs = source_to_docs(r#"
+pub mod a {
+    pub struct B;
+    pub mod inner {
+        pub fn f() {}
+    }
+}
+
+pub use a::B as C;
+pub use a::inner::*;
+"#);
+
+    let reexports = match find_doc(&docs, "crate").inner_data {
+        ModuleDoc(ref m) => &m.reexports,
+        ref other => panic!("Expected module, got {:?}", other),
+    };
+    assert_eq!(*reexports, vec![
+        Reexport {
+            alias: ModPath::from("crate::C".to_string()),
+            original: ModPath::from("crate::a::B".to_string()),
+            is_glob: false,
+        },
+        Reexport {
+            alias: ModPath::from("crate".to_string()),
+            original: ModPath::from("crate::a::inner".to_string()),
+            is_glob: true,
+        },
+    ]);
+}
+
+#[test]
+fn test_foreign_items() {
+    let docs = source_to_docs(r#"
+extern "C" {
+    /// Absolute value.
+    pub fn abs(x: i32) -> i32;
+    pub static mut errno: i32;
+    fn private();
+}
+"#);
+
+    match find_doc(&docs, "crate::abs").inner_data {
+        FnDoc(ref f) => {
+            assert!(f.is_foreign);
+            assert_eq!(f.abi, Abi::C);
+            assert_eq!(f.header.to_string(), "(x: i32) -> i32");
+        }
+        ref other => panic!("Expected function, got {:?}", other),
+    }
+    match find_doc(&docs, "crate::errno").inner_data {
+        StaticDoc(ref s) => {
+            assert_eq!(s.abi, Some(Abi::C));
+            assert_eq!(s.mutability, Mutability::Mutable);
+            assert_eq!(s.expr, None);
+        }
+        ref other => panic!("Expected static, got {:?}", other),
+    }
+    assert!(docs.iter().all(|doc| doc.name != "private"));
+}
+
+#[test]
+fn test_source_location() {
+    let docs = source_to_docs_with_locations(r#"
+pub struct First;
+
+/// Documented.
+pub fn second() {}
+"#);
+
+    let source = find_doc(&docs, "crate::second").source.clone().expect("No source location");
+    assert_eq!(source.file, "test.rs");
+    assert_eq!(source.line, 5);
+    assert_eq!(find_doc(&docs, "crate::First").source.as_ref().map(|s| s.line), Some(2));
+
+    let docs = source_to_docs("pub fn no_codemap() {}");
+    assert_eq!(find_doc(&docs, "crate::no_codemap").source, None);
+}
+
+#[test]
+fn test_conversion_order_is_deterministic() {
+    let src = r#"
+pub mod b { pub fn z() {} pub fn a() {} }
+pub struct A;
+pub mod a { pub const C: u8 = 0; }
+"#;
+    let first = source_to_docs(src);
+    let second = source_to_docs(src);
+    assert_eq!(first, second);
+
+    let paths: Vec<String> = first.iter().map(|doc| doc.mod_path.to_string()).collect();
+    assert_eq!(paths, vec!["crate", "crate::A", "crate::a", "crate::a::C",
+                           "crate::b", "crate::b::a", "crate::b::z"]);
+}
+
+#[test]
+fn test_crate_info() {
+    let docs = source_to_crate_docs("mycrate", "0.3.1", "pub mod m { pub fn f() {} }");
+    let expected = util::get_crate_info("mycrate", "0.3.1");
+
+    assert!(docs.iter().all(|doc| doc.crate_info == expected));
+
+    // The crate root is named after the crate, not an item in the source.
+    let root = find_doc(&docs, "mycrate");
+    assert_eq!(root.name, "mycrate");
+    assert_eq!(find_doc(&docs, "mycrate::m::f").crate_info, expected);
+}
+
+#[test]
+fn test_fn_header_inputs() {
+    let docs = source_to_docs(r#"
+pub struct Reader;
+impl Reader {
+    pub fn read<'a>(&'a mut self, buf: &mut [u8], (a, b): (u8, u8)) -> usize { 0 }
+    pub fn boxed(self: Box<Self>) {}
+}
+extern "C" {
+    pub fn printf(format: *const u8, ...) -> i32;
+}
+"#);
+
+    let header = |path: &str| match find_doc(&docs, path).inner_data {
+        FnDoc(ref f) => f.header.clone(),
+        ref other => panic!("Expected function, got {:?}", other),
+    };
+    let pair = |pat: &str, ty: &str| (pat.to_string(), ty.to_string());
+
+    let read = header("crate::Reader::read");
+    assert_eq!(read.inputs, vec![pair("self", "&'a mut Self"),
+                                 pair("buf", "&mut [u8]"),
+                                 pair("(a, b)", "(u8, u8)")]);
+    assert_eq!(read.output, Some("usize".to_string()));
+    assert_eq!(read.to_string(), "(&'a mut self, buf: &mut [u8], (a, b): (u8, u8)) -> usize");
+
+    let boxed = header("crate::Reader::boxed");
+    assert_eq!(boxed.inputs, vec![pair("self", "Box<Self>")]);
+    assert_eq!(boxed.to_string(), "(self: Box<Self>)");
+
+    let printf = header("crate::printf");
+    assert_eq!(printf.inputs, vec![pair("format", "*const u8")]);
+    assert!(printf.variadic);
+    assert_eq!(printf.to_string(), "(format: *const u8, ...) -> i32");
+}
+
+#[test]
+fn test_required_and_provided_methods() {
+    let docs = source_to_docs(r#"
+pub trait Greet {
+    fn name(&self) -> String;
+    fn greet(&self) -> String { format!("Hello, {}", self.name()) }
+}
+"#);
+
+    let is_provided = |path: &str| match find_doc(&docs, path).inner_data {
+        TraitItemDoc(TraitItem { node: TraitItemKind::Method(_, provided), .. }) => provided,
+        ref other => panic!("Expected trait method, got {:?}", other),
+    };
+    assert!(!is_provided("crate::Greet::name"));
+    assert!(is_provided("crate::Greet::greet"));
+}
+
+#[test]
+fn test_module_child_links() {
+    let docs = source_to_docs(r#"
+pub mod items {
+    pub enum E { A }
+    pub struct S;
+    pub mod inner {}
+    pub fn f() {}
+    pub const C: u8 = 0;
+    pub static V: u8 = 0;
+}
+"#);
+
+    let module = find_doc(&docs, "crate::items");
+    let kinds: Vec<&str> = module.links.keys().map(|kind| kind.as_str()).collect();
+    assert_eq!(kinds, vec!["fn", "mod", "enum", "struct", "const", "static"]);
+
+    let names: Vec<&str> = module.links[&DocType::Struct].iter()
+        .map(|link| link.name.as_str()).collect();
+    assert_eq!(names, vec!["S"]);
+}
+
+#[test]
+fn test_summary() {
+    let docs = source_to_docs(r#"
+/// Reads a **line** from [`Stdin`](struct.Stdin.html). Appends it to `buf`.
+///
+/// More details.
+pub fn read_line() {}
+
+/// # Overview
+///
+/// Parses the input_text
+/// into tokens
+///
+/// Second paragraph.
+pub fn tokenize() {}
+
+/// No period here
+pub fn unfinished() {}
+
+pub fn undocumented() {}
+"#);
+
+    assert_eq!(find_doc(&docs, "crate::read_line").summary(), "Reads a line from Stdin.");
+    assert_eq!(find_doc(&docs, "crate::tokenize").summary(),
+               "Parses the input_text into tokens");
+    assert_eq!(find_doc(&docs, "crate::unfinished").summary(), "No period here");
+    assert_eq!(find_doc(&docs, "crate::undocumented").summary(), "");
+}
+
+#[test]
+fn test_summary_skips_leading_code() {
+    let docs = source_to_docs(r#"
+/// ```
+/// let x = 1.5;
+/// ```
+///
+/// Uses `f64::max`. Then returns.
+pub fn clamp() {}
+"#);
+
+    assert_eq!(find_doc(&docs, "crate::clamp").summary(), "Uses f64::max.");
+}
+
+#[test]
+fn test_impl_trait_fn_header() {
+    let docs = source_to_docs("pub fn digits(x: impl Into<String> + Clone, y: &mut impl Write) \
+                               -> impl Iterator<Item = u8> { 0..1 }");
+
+    match find_doc(&docs, "crate::digits").inner_data {
+        FnDoc(ref func) => {
+            assert_eq!(func.header.args(), vec!["x: impl Into<String> + Clone",
+                                                "y: &mut impl Write"]);
+            assert_eq!(func.header.output, Some("impl Iterator<Item = u8>".to_string()));
+            assert!(func.generics.is_empty());
+        }
+        ref other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_other_attrs() {
+    let docs = source_to_docs(r#"
+/// A point in C layout.
+#[repr(C)]
+#[derive(Clone)]
+#[deprecated]
+pub struct Point { pub x: i32 }
+"#);
+
+    let attrs = &find_doc(&docs, "crate::Point").attrs;
+    assert_eq!(attrs.doc_strings, vec!["A point in C layout.".to_string()]);
+    assert_eq!(attrs.other_attrs, vec!["#[repr(C)]".to_string(), "#[derive(Clone)]".to_string()]);
+}
+
+#[test]
+fn test_method_receivers() {
+    let docs = source_to_docs(r#"
+pub struct Counter;
+
+impl Counter {
+    pub fn new() -> Counter { Counter }
+    pub fn get(&self) -> u32 { 0 }
+    pub fn bump<'a>(&'a mut self) {}
+    pub fn into_inner(self) -> u32 { 0 }
+    pub fn take(mut self) -> u32 { 0 }
+    pub fn boxed(self: Box<Self>) {}
+    pub fn boxed_mut(mut self: Box<Self>) {}
+}
+"#);
+
+    let receiver = |path: &str| match find_doc(&docs, path).inner_data {
+        FnDoc(ref f) => f.header.receiver.clone(),
+        ref other => panic!("Expected function, got {:?}", other),
+    };
+    assert_eq!(receiver("crate::Counter::new"), None);
+    assert_eq!(receiver("crate::Counter::get"), Some(Receiver::Ref));
+    assert_eq!(receiver("crate::Counter::bump"), Some(Receiver::RefMut));
+    assert_eq!(receiver("crate::Counter::into_inner"), Some(Receiver::Value));
+    assert_eq!(receiver("crate::Counter::take"), Some(Receiver::Value));
+    assert_eq!(receiver("crate::Counter::boxed"), Some(Receiver::Typed));
+
+    let args = |path: &str| match find_doc(&docs, path).inner_data {
+        FnDoc(ref f) => (f.header.is_method(), f.header.args()),
+        ref other => panic!("Expected function, got {:?}", other),
+    };
+    assert_eq!(args("crate::Counter::new"), (false, vec![]));
+    assert_eq!(args("crate::Counter::get"), (true, vec!["&self".to_string()]));
+    assert_eq!(args("crate::Counter::bump"), (true, vec!["&'a mut self".to_string()]));
+    assert_eq!(args("crate::Counter::take"), (true, vec!["mut self".to_string()]));
+    assert_eq!(args("crate::Counter::boxed"), (true, vec!["self: Box<Self>".to_string()]));
+    assert_eq!(args("crate::Counter::boxed_mut"),
+               (true, vec!["mut self: Box<Self>".to_string()]));
+}
@@ -1,7 +1,7 @@
 use oxidoc::conversion::Documentation;
 use oxidoc::document::ModPath;
 
-use util::{source_to_docs, print_paths};
+use util::{source_to_docs, source_to_docs_with_hidden, source_to_docs_with_private, print_paths};
 
 fn assert_paths_found(converted: &Vec<Documentation>, mut paths: Vec<&str>) {
     let mut converted_strings: Vec<String> = converted
@@ -245,3 +245,66 @@ pub mod a {
         ]
     );
 }
+
+#[test]
+fn test_doc_hidden_fn() {
+    let source = r#"
+pub fn shown() {}
+
+#[doc(hidden)]
+pub fn hidden() {}
+"#;
+    assert_paths_found(&source_to_docs(source), vec!["crate", "crate::shown"]);
+    assert_paths_found(&source_to_docs_with_hidden(source, true),
+                       vec!["crate", "crate::shown", "crate::hidden"]);
+}
+
+#[test]
+fn test_doc_hidden_module() {
+    let source = r#"
+#[doc(hidden)]
+pub mod internal {
+    pub fn helper() {}
+    pub mod nested {
+        pub struct Detail;
+    }
+}
+"#;
+    assert_paths_found(&source_to_docs(source), vec!["crate"]);
+    assert_paths_found(&source_to_docs_with_hidden(source, true),
+                       vec!["crate",
+                            "crate::internal",
+                            "crate::internal::helper",
+                            "crate::internal::nested",
+                            "crate::internal::nested::Detail"]);
+}
+
+#[test]
+fn test_private_items() {
+    let source = r#"
+pub fn shown() {}
+
+fn private() {}
+
+struct Private { field: u8 }
+
+impl Private {
+    pub fn method(&self) {}
+}
+
+mod imp {
+    pub fn reexported() {}
+}
+
+pub use imp::reexported;
+"#;
+    assert_paths_found(&source_to_docs(source), vec!["crate", "crate::shown"]);
+    assert_paths_found(&source_to_docs_with_private(source, true),
+                       vec!["crate",
+                            "crate::shown",
+                            "crate::private",
+                            "crate::Private",
+                            "crate::Private::method",
+                            "crate::imp",
+                            "crate::imp::reexported"]);
+}
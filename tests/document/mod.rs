@@ -0,0 +1,2 @@
+mod test_mod_path;
+mod test_crate_info;
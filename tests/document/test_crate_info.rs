@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use oxidoc::document::CrateInfo;
+
+fn crate_info(name: &str, version: &str) -> CrateInfo {
+    CrateInfo {
+        name: name.to_string(),
+        version: version.to_string(),
+        lib_path: None,
+    }
+}
+
+#[test]
+fn test_display_with_version() {
+    let info = crate_info("serde", "1.0.130");
+    assert_eq!(info.to_string(), "serde 1.0.130");
+    assert_eq!(info.to_path_prefix(), PathBuf::from("serde-1.0.130"));
+}
+
+#[test]
+fn test_display_without_version() {
+    let info = crate_info("serde", "");
+    assert_eq!(info.to_string(), "serde");
+    assert_eq!(info.to_path_prefix(), PathBuf::from("serde"));
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use oxidoc::document::ModPath;
+
+#[test]
+fn test_join_with() {
+    let path = ModPath::from("std::fmt::Display".to_string());
+    assert_eq!(path.join_with("::"), "std::fmt::Display");
+    assert_eq!(path.join_with("/"), "std/fmt/Display");
+    assert_eq!(path.join_with("::"), path.to_string());
+}
+
+#[test]
+fn test_file_stem() {
+    let path = ModPath::from("std::fmt::Display".to_string());
+    assert_eq!(path.file_stem(), "std.fmt.Display");
+}
+
+#[test]
+fn test_file_stem_escapes_illegal_characters() {
+    let mut path = ModPath::from("krate".to_string());
+    path.push_string("<impl Foo for *const Bar>".to_string());
+    assert_eq!(path.file_stem(), "krate.%3Cimpl Foo for %2Aconst Bar%3E");
+    assert_eq!(path.to_filepath(),
+               PathBuf::from("krate").join("%3Cimpl Foo for %2Aconst Bar%3E"));
+}
+
+fn path(s: &str) -> ModPath {
+    ModPath::from(s.to_string())
+}
+
+#[test]
+fn test_is_ancestor_of() {
+    let root = path("krate");
+    assert!(root.is_ancestor_of(&path("krate::a")));
+    assert!(root.is_ancestor_of(&path("krate::a::b")));
+    assert!(path("krate::a").is_ancestor_of(&path("krate::a::b")));
+
+    assert!(!root.is_ancestor_of(&root));
+    assert!(!path("krate::a").is_ancestor_of(&root));
+    assert!(!path("krate::a").is_ancestor_of(&path("krate::ab")));
+    assert!(!path("other").is_ancestor_of(&path("krate::a")));
+    assert_eq!(root.parent(), None);
+}
+
+#[test]
+fn test_children() {
+    let paths = vec![path("krate"), path("krate::b"), path("krate::a"), path("krate::a::x"),
+                     path("krate::a::y::z"), path("krate::b")];
+
+    assert_eq!(path("krate").children(&paths), vec![path("krate::a"), path("krate::b")]);
+    assert_eq!(path("krate::a").children(&paths), vec![path("krate::a::x")]);
+    assert_eq!(path("krate::b").children(&paths), vec![]);
+}
@@ -0,0 +1,2 @@
+mod test_generate;
+mod test_rustdoc_json;
@@ -0,0 +1,53 @@
+use bincode::{self, Infinite};
+
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+const CRATE_SRC: &str = r#"
+pub mod a {
+    pub struct Alpha { pub x: u32 }
+    pub enum Beta { One, Two }
+    pub fn gamma() {}
+    pub const DELTA: u32 = 4;
+}
+
+pub mod b {
+    pub trait Epsilon {
+        const ZETA: u32;
+        type Eta;
+        fn theta(&self);
+    }
+}
+
+pub struct Iota;
+
+impl Iota {
+    pub fn kappa() {}
+    pub fn lambda() {}
+}
+"#;
+
+fn generate_bytes(src: &str) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let docs = util::source_to_docs(src);
+    let doc_bytes = docs.iter()
+        .map(|doc| bincode::serialize(doc, Infinite).unwrap())
+        .collect();
+
+    let docset = generation::make_docset(docs).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("crate", "1.0.0"), docset);
+    let store_bytes = bincode::serialize(&store, Infinite).unwrap();
+
+    (doc_bytes, store_bytes)
+}
+
+#[test]
+fn test_generation_is_idempotent() {
+    let (first_docs, first_store) = generate_bytes(CRATE_SRC);
+    let (second_docs, second_store) = generate_bytes(CRATE_SRC);
+
+    assert!(first_docs == second_docs, "Documents differ between generations");
+    assert!(first_store == second_store, "Store differs between generations");
+}
@@ -0,0 +1,220 @@
+use oxidoc::conversion::*;
+use oxidoc::generation::ast_ty_wrappers::StructKind;
+use oxidoc::markup::Format;
+use oxidoc::markup::Markup::Signature;
+use oxidoc::rustdoc_json;
+
+use util::{find_doc, get_crate_info};
+
+// Output of `rustdoc --output-format json` for:
+//
+//     /// Adds two numbers.
+//     pub fn add(x: i32, y: i32) -> i32 { x + y }
+//     pub struct Point { pub x: f64, pub y: f64 }
+//     pub trait Shape {
+//         fn area(&self) -> f64;
+//         fn name(&self) -> &'static str { "shape" }
+//     }
+const FIXTURE: &str = r#"{
+  "root": 0,
+  "crate_version": "0.1.0",
+  "format_version": 30,
+  "index": {
+    "0": {"id": 0, "crate_id": 0, "name": "krate", "docs": null, "visibility": "public",
+          "inner": {"module": {"is_crate": true, "items": [1, 2, 5]}}},
+    "1": {"id": 1, "crate_id": 0, "name": "add", "docs": "Adds two numbers.",
+          "visibility": "public",
+          "span": {"filename": "src/lib.rs", "begin": [2, 0], "end": [2, 43]},
+          "inner": {"function": {
+            "sig": {"inputs": [["x", {"primitive": "i32"}], ["y", {"primitive": "i32"}]],
+                    "output": {"primitive": "i32"}, "is_c_variadic": false},
+            "generics": {"params": [], "where_predicates": []},
+            "header": {"is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust"},
+            "has_body": true}}},
+    "2": {"id": 2, "crate_id": 0, "name": "Point", "docs": null, "visibility": "public",
+          "inner": {"struct": {"kind": {"plain": {"fields": [3, 4], "has_stripped_fields": false}},
+                               "generics": {"params": [], "where_predicates": []},
+                               "impls": []}}},
+    "3": {"id": 3, "crate_id": 0, "name": "x", "docs": null, "visibility": "public",
+          "inner": {"struct_field": {"primitive": "f64"}}},
+    "4": {"id": 4, "crate_id": 0, "name": "y", "docs": null, "visibility": "public",
+          "inner": {"struct_field": {"primitive": "f64"}}},
+    "5": {"id": 5, "crate_id": 0, "name": "Shape", "docs": null, "visibility": "public",
+          "inner": {"trait": {"is_unsafe": false, "items": [6, 7], "bounds": [],
+                              "generics": {"params": [], "where_predicates": []},
+                              "implementations": []}}},
+    "6": {"id": 6, "crate_id": 0, "name": "area", "docs": null, "visibility": "default",
+          "inner": {"function": {
+            "sig": {"inputs": [["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false,
+                                                          "type": {"generic": "Self"}}}]],
+                    "output": {"primitive": "f64"}, "is_c_variadic": false},
+            "generics": {"params": [], "where_predicates": []},
+            "header": {"is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust"},
+            "has_body": false}}},
+    "7": {"id": 7, "crate_id": 0, "name": "name", "docs": null, "visibility": "default",
+          "inner": {"function": {
+            "sig": {"inputs": [["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false,
+                                                          "type": {"generic": "Self"}}}]],
+                    "output": {"borrowed_ref": {"lifetime": "'static", "is_mutable": false,
+                                                "type": {"primitive": "str"}}},
+                    "is_c_variadic": false},
+            "generics": {"params": [], "where_predicates": []},
+            "header": {"is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust"},
+            "has_body": true}}},
+    "8": {"id": 8, "crate_id": 1, "name": "Clone", "docs": null, "visibility": "public",
+          "inner": {"trait": {"is_unsafe": false, "items": [], "bounds": [],
+                              "generics": {"params": [], "where_predicates": []},
+                              "implementations": []}}}
+  },
+  "paths": {
+    "0": {"crate_id": 0, "path": ["krate"], "kind": "module"},
+    "1": {"crate_id": 0, "path": ["krate", "add"], "kind": "function"},
+    "2": {"crate_id": 0, "path": ["krate", "Point"], "kind": "struct"},
+    "5": {"crate_id": 0, "path": ["krate", "Shape"], "kind": "trait"},
+    "8": {"crate_id": 1, "path": ["core", "clone", "Clone"], "kind": "trait"}
+  },
+  "external_crates": {"1": {"name": "core", "html_root_url": null}}
+}"#;
+
+#[test]
+fn test_rustdoc_json_items() {
+    let docs = rustdoc_json::convert_rustdoc_json(FIXTURE, get_crate_info("krate", "0.1.0"))
+        .unwrap();
+
+    let mut paths: Vec<(String, DocType)> = docs.iter()
+        .map(|doc| (doc.mod_path.to_string(), doc.get_type()))
+        .collect();
+    paths.sort();
+    assert_eq!(paths, vec![("krate::Point".to_string(), DocType::Struct),
+                           ("krate::Shape".to_string(), DocType::Trait),
+                           ("krate::Shape::area".to_string(), DocType::TraitItemMethod),
+                           ("krate::Shape::name".to_string(), DocType::TraitItemMethod),
+                           ("krate::add".to_string(), DocType::Function)]);
+
+    let add = find_doc(&docs, "krate::add");
+    assert_eq!(add.name, "add");
+    assert_eq!(add.attrs.doc_strings, vec!["Adds two numbers.".to_string()]);
+    assert_eq!(add.source.as_ref().map(|source| source.line), Some(2));
+    match add.inner_data {
+        FnDoc(ref func) => {
+            assert_eq!(func.header.args(), vec!["x: i32", "y: i32"]);
+            assert_eq!(func.header.output, Some("i32".to_string()));
+        }
+        ref other => panic!("Expected function, got {:?}", other),
+    }
+
+    match find_doc(&docs, "krate::Point").inner_data {
+        StructDoc(ref struct_) => {
+            assert_eq!(struct_.kind, StructKind::Named);
+            let fields: Vec<(Option<String>, String)> = struct_.fields.iter()
+                .map(|field| (field.ident.clone(), field.ty.name.clone()))
+                .collect();
+            assert_eq!(fields, vec![(Some("x".to_string()), "f64".to_string()),
+                                    (Some("y".to_string()), "f64".to_string())]);
+        }
+        ref other => panic!("Expected struct, got {:?}", other),
+    }
+
+    let shape = find_doc(&docs, "krate::Shape");
+    let link_names = |ty: DocType| -> Vec<String> {
+        shape.links[&ty].iter().map(|link| link.name.clone()).collect()
+    };
+    assert_eq!(link_names(DocType::TraitItemMethod), vec!["area"]);
+    assert_eq!(link_names(DocType::ProvidedMethod), vec!["name"]);
+
+    match find_doc(&docs, "krate::Shape::name").inner_data {
+        TraitItemDoc(TraitItem { node: TraitItemKind::Method(ref sig, provided) }) => {
+            assert!(provided);
+            assert_eq!(sig.header.args(), vec!["&self"]);
+            assert_eq!(sig.header.output, Some("&'static str".to_string()));
+        }
+        ref other => panic!("Expected trait method, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_rustdoc_json_async_fn() {
+    // pub async fn fetch() -> u8 { 0 }
+    let json = r#"{
+  "root": 0,
+  "index": {
+    "0": {"id": 0, "crate_id": 0, "name": "krate", "docs": null, "visibility": "public",
+          "inner": {"module": {"is_crate": true, "items": [1]}}},
+    "1": {"id": 1, "crate_id": 0, "name": "fetch", "docs": null, "visibility": "public",
+          "inner": {"function": {
+            "sig": {"inputs": [], "output": {"primitive": "u8"}, "is_c_variadic": false},
+            "generics": {"params": [], "where_predicates": []},
+            "header": {"is_const": false, "is_unsafe": false, "is_async": true, "abi": "Rust"},
+            "has_body": true}}}
+  },
+  "paths": {
+    "0": {"crate_id": 0, "path": ["krate"], "kind": "module"},
+    "1": {"crate_id": 0, "path": ["krate", "fetch"], "kind": "function"}
+  },
+  "external_crates": {}
+}"#;
+    let docs = rustdoc_json::convert_rustdoc_json(json, get_crate_info("krate", "0.1.0")).unwrap();
+
+    let parts = find_doc(&docs, "krate::fetch").format().parts;
+    assert!(parts.contains(&Signature("pub ".to_string(), "async fn fetch() -> u8".to_string())),
+            "{:?}", parts);
+}
+
+#[test]
+fn test_rustdoc_json_impl_trait() {
+    // pub fn show(x: impl Display) -> impl Iterator<Item = u8> { 0..1 }
+    let json = r#"{
+  "root": 0,
+  "index": {
+    "0": {"id": 0, "crate_id": 0, "name": "krate", "docs": null, "visibility": "public",
+          "inner": {"module": {"is_crate": true, "items": [1]}}},
+    "1": {"id": 1, "crate_id": 0, "name": "show", "docs": null, "visibility": "public",
+          "inner": {"function": {
+            "sig": {"inputs": [["x", {"generic": "impl Display"}]],
+                    "output": {"impl_trait": [{"trait_bound": {
+                      "trait": {"path": "Iterator", "id": 5, "args": {"angle_bracketed": {
+                        "args": [],
+                        "constraints": [{"name": "Item", "args": null,
+                                         "binding": {"equality": {"type": {"primitive": "u8"}}}}]
+                      }}},
+                      "generic_params": [], "modifier": "none"}}]},
+                    "is_c_variadic": false},
+            "generics": {"params": [{"name": "impl Display", "kind": {"type": {
+                           "bounds": [{"trait_bound": {
+                             "trait": {"path": "Display", "id": 6, "args": null},
+                             "generic_params": [], "modifier": "none"}}],
+                           "default": null, "is_synthetic": true}}}],
+                         "where_predicates": []},
+            "header": {"is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust"},
+            "has_body": true}}}
+  },
+  "paths": {
+    "0": {"crate_id": 0, "path": ["krate"], "kind": "module"},
+    "1": {"crate_id": 0, "path": ["krate", "show"], "kind": "function"}
+  },
+  "external_crates": {}
+}"#;
+    let docs = rustdoc_json::convert_rustdoc_json(json, get_crate_info("krate", "0.1.0")).unwrap();
+
+    let parts = find_doc(&docs, "krate::show").format().parts;
+    let signature = Signature("pub ".to_string(),
+                              "fn show(x: impl Display) -> impl Iterator<Item = u8>".to_string());
+    assert!(parts.contains(&signature), "{:?}", parts);
+}
+
+#[test]
+fn test_rustdoc_json_crate_version() {
+    let docs = rustdoc_json::convert_rustdoc_json(FIXTURE, get_crate_info("krate", "")).unwrap();
+    assert_eq!(find_doc(&docs, "krate::add").crate_info.to_string(), "krate 0.1.0");
+
+    let docs = rustdoc_json::convert_rustdoc_json(FIXTURE, get_crate_info("krate", "2.0.0"))
+        .unwrap();
+    assert_eq!(find_doc(&docs, "krate::add").crate_info.version, "2.0.0");
+}
+
+#[test]
+fn test_rustdoc_json_invalid() {
+    let crate_info = get_crate_info("krate", "0.1.0");
+    assert!(rustdoc_json::convert_rustdoc_json("not json", crate_info.clone()).is_err());
+    assert!(rustdoc_json::convert_rustdoc_json("{}", crate_info).is_err());
+}
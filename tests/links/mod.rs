@@ -0,0 +1,2 @@
+mod test_intra_links;
+mod test_see_also;
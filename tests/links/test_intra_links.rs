@@ -0,0 +1,79 @@
+use oxidoc::conversion::{DocLink, DocType};
+use oxidoc::document::ModPath;
+use oxidoc::intra_links;
+use oxidoc::markup::Format;
+use oxidoc::markup::Markup::*;
+use oxidoc::store::{Store, StoreFormat};
+
+use util::{source_to_docs, find_doc};
+
+const LINKING_SRC: &str = r#"
+pub mod shapes {
+    pub struct Circle;
+}
+
+/// Makes a [`Circle`], unlike [`Square`].
+///
+/// See also [the module](crate::shapes) and [docs](https://example.com).
+pub fn make() {}
+"#;
+
+#[test]
+fn test_find_intra_links() {
+    let links = intra_links::find_intra_links("A [`Vec`], [push](Vec::push) and `v[i]`.\n\
+                                               ```\nlet x = [a];\n```\n[web](http://a.b)");
+    let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+
+    assert_eq!(targets, ["Vec", "Vec::push"]);
+    assert_eq!(links[0].text, "`Vec`");
+}
+
+#[test]
+fn test_find_intra_links_skips_code_blocks() {
+    let links = intra_links::find_intra_links("~~~\nlet x = v[i];\n```\n~~~\n\n    let y = w[j];\n\n\
+                                               [Vec]");
+    let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+
+    assert_eq!(targets, ["Vec"]);
+}
+
+#[test]
+fn test_unresolved_shortcut_link_kept() {
+    let md = "Takes an [optional] value, see [note] and [1].";
+
+    assert_eq!(intra_links::rewrite_intra_links(md, &[]), md);
+}
+
+#[test]
+fn test_resolve_intra_links() {
+    let mut docs = source_to_docs(LINKING_SRC);
+    intra_links::resolve_intra_links(&mut docs, &Store::new(StoreFormat::default()));
+
+    let doc = find_doc(&docs, "crate::make");
+    assert_eq!(doc.links.get(&DocType::IntraDocLink).unwrap(),
+               &vec![DocLink {
+                         name: "Circle".to_string(),
+                         path: ModPath::from("crate::shapes::Circle".to_string()),
+                     },
+                     DocLink {
+                         name: "crate::shapes".to_string(),
+                         path: ModPath::from("crate::shapes".to_string()),
+                     }]);
+}
+
+#[test]
+fn test_render_intra_links() {
+    let mut docs = source_to_docs(LINKING_SRC);
+    intra_links::resolve_intra_links(&mut docs, &Store::new(StoreFormat::default()));
+
+    let parts = find_doc(&docs, "crate::make").format().parts;
+    match *parts.last().unwrap() {
+        Markdown(ref md) => {
+            assert!(md.contains("[`Circle`](crate::shapes::Circle)"), "{}", md);
+            assert!(md.contains("unlike [`Square`]."), "{}", md);
+            assert!(md.contains("[the module](crate::shapes)"), "{}", md);
+            assert!(md.contains("[docs](https://example.com)"), "{}", md);
+        }
+        ref other => panic!("Expected body markdown, got {:?}", other),
+    }
+}
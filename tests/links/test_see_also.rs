@@ -0,0 +1,61 @@
+use oxidoc::conversion::{DocLink, Documentation};
+use oxidoc::document::ModPath;
+use oxidoc::markup::Format;
+use oxidoc::markup::Markup::*;
+use oxidoc::see_also;
+
+use util::{source_to_docs, find_doc};
+
+fn link(name: &str, path: &str) -> DocLink {
+    DocLink { name: name.to_string(), path: ModPath::from(path.to_string()) }
+}
+
+fn see_also_names(doc: &Documentation) -> Vec<String> {
+    doc.see_also().iter().map(|link| link.name.clone()).collect()
+}
+
+#[test]
+fn test_see_also_section() {
+    let docs = source_to_docs("pub fn open() {}");
+    let doc = docs.into_iter().find(|doc| doc.name == "open").unwrap()
+        .with_see_also(vec![link("close", "crate::close"), link("File", "crate::fs::File")]);
+
+    let parts = doc.format().parts;
+    let section = parts.iter().position(|part| *part == Section("See Also".to_string()))
+        .expect("No See Also section");
+    assert_eq!(parts[section + 1],
+               Markdown("- [close](crate::close)\n- [File](crate::fs::File)".to_string()));
+}
+
+#[test]
+fn test_empty_see_also() {
+    let docs = source_to_docs("pub fn open() {}");
+    let doc = docs.into_iter().find(|doc| doc.name == "open").unwrap()
+        .with_see_also(vec![link("close", "crate::close")])
+        .with_see_also(vec![]);
+
+    assert!(doc.see_also().is_empty());
+    assert!(!doc.format().parts.contains(&Section("See Also".to_string())));
+}
+
+#[test]
+fn test_add_see_also() {
+    let mut docs = source_to_docs(r#"
+pub fn read_line() {}
+pub fn read_to_end() {}
+pub struct read_only;
+#[doc(alias = "write_all")]
+pub fn flush() {}
+pub fn write_all() {}
+pub mod other {
+    pub fn read_bytes() {}
+}
+"#);
+    see_also::add_see_also(&mut docs);
+
+    assert_eq!(see_also_names(find_doc(&docs, "crate::read_line")), vec!["read_to_end"]);
+    assert_eq!(see_also_names(find_doc(&docs, "crate::flush")), vec!["write_all"]);
+    assert!(find_doc(&docs, "crate::write_all").see_also().is_empty());
+    assert!(find_doc(&docs, "crate::other::read_bytes").see_also().is_empty());
+    assert!(find_doc(&docs, "crate::read_only").see_also().is_empty());
+}
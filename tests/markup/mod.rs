@@ -0,0 +1,2 @@
+mod test_format;
+mod test_table;
@@ -0,0 +1,1073 @@
+use oxidoc::markup::{self, Format, FormatOptions, Markup, MarkupDoc};
+use oxidoc::markup::Markup::*;
+use oxidoc::highlight;
+use oxidoc::intra_links;
+use oxidoc::store::{Store, StoreFormat};
+use oxidoc::theme::{ColorSupport, Theme};
+
+use util::{source_to_docs, source_to_docs_with_locations, find_doc};
+
+const DOCUMENTED_FN: &str = r#"
+/// Adds one.
+///
+/// ```
+/// assert_eq!(add_one(1), 2);
+/// ```
+///
+/// More text.
+pub fn add_one(x: i32) -> i32 { x + 1 }
+"#;
+
+#[test]
+fn test_extract_first_example() {
+    let doc = "Text.\n```text\nnot rust\n```\n```rust\nlet a = 1;\n```\nMore.";
+    let (code, rest) = markup::extract_first_example(doc).unwrap();
+    assert_eq!(code, "let a = 1;");
+    assert_eq!(rest, "Text.\n```text\nnot rust\n```\nMore.");
+
+    assert!(markup::extract_first_example("No examples.").is_none());
+}
+
+#[test]
+fn test_extract_examples() {
+    let docs: Vec<String> = "Adds one.\n\n# Examples\n\n```\nassert_eq!(add_one(1), 2);\n```\n\n\
+                             # Panics\n\nNever."
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    let examples = markup::extract_examples(&docs).unwrap();
+
+    assert_eq!(examples.code_blocks, vec!["assert_eq!(add_one(1), 2);".to_string()]);
+    assert_eq!(examples.text, "```\nassert_eq!(add_one(1), 2);\n```");
+    assert_eq!(examples.rest, "Adds one.\n\n# Panics\n\nNever.");
+
+    let no_examples = vec!["Adds one.".to_string(), "```".to_string(), "let a = 1;".to_string(),
+                           "```".to_string()];
+    assert!(markup::extract_examples(&no_examples).is_none());
+}
+
+#[test]
+fn test_examples_section() {
+    let docs = source_to_docs(r#"
+/// Adds one.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(add_one(1), 2);
+/// ```
+pub fn add_one(x: i32) -> i32 { x + 1 }
+
+/// Adds two.
+pub fn add_two(x: i32) -> i32 { x + 2 }
+"#);
+
+    let parts = find_doc(&docs, "crate::add_one").format().parts;
+    let pos = parts.iter().position(|p| *p == Section("Examples".to_string()))
+        .expect("No examples section");
+    match parts[pos + 1] {
+        Markdown(ref md) => assert!(md.contains("assert_eq!(add_one(1), 2);"), "{}", md),
+        ref other => panic!("Expected example markdown, got {:?}", other),
+    }
+    assert!(!parts.iter().any(|part| match *part {
+        Markdown(ref md) => md.contains("# Examples"),
+        _ => false,
+    }));
+
+    let parts = find_doc(&docs, "crate::add_two").format().parts;
+    assert!(!parts.contains(&Section("Examples".to_string())));
+    assert!(!parts.contains(&Section("Example".to_string())));
+}
+
+#[test]
+fn test_example_first() {
+    let docs = source_to_docs(DOCUMENTED_FN);
+    let parts = find_doc(&docs, "crate::add_one").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Example".to_string()))
+        .expect("No example section");
+    match parts[pos + 1] {
+        Markdown(ref md) => assert!(md.contains("assert_eq!(add_one(1), 2);"), "{}", md),
+        ref other => panic!("Expected example markdown, got {:?}", other),
+    }
+    match *parts.last().unwrap() {
+        Markdown(ref md) => {
+            assert!(md.contains("More text."), "{}", md);
+            assert!(!md.contains("assert_eq!"), "{}", md);
+        }
+        ref other => panic!("Expected body markdown, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_no_example_first() {
+    let docs = source_to_docs(DOCUMENTED_FN);
+    let options = FormatOptions { example_first: false, ..FormatOptions::default() };
+    let parts = find_doc(&docs, "crate::add_one").format_with(&options).parts;
+
+    assert!(!parts.contains(&Section("Example".to_string())));
+    match *parts.last().unwrap() {
+        Markdown(ref md) => assert_eq!(md, "Adds one.\n\n```\nassert_eq!(add_one(1), 2);\n```\n\n\
+                                      More text."),
+        ref other => panic!("Expected body markdown, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_no_example_first_keeps_examples_section() {
+    let docs = source_to_docs(r#"
+/// # Examples
+///
+/// ```
+/// assert_eq!(add_one(1), 2);
+/// ```
+///
+/// Adds one.
+pub fn add_one(x: i32) -> i32 { x + 1 }
+"#);
+    let options = FormatOptions { example_first: false, ..FormatOptions::default() };
+    let parts = find_doc(&docs, "crate::add_one").format_with(&options).parts;
+
+    assert!(!parts.contains(&Section("Examples".to_string())));
+    match *parts.last().unwrap() {
+        Markdown(ref md) => assert!(md.starts_with("# Examples\n"), "{}", md),
+        ref other => panic!("Expected body markdown, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_tilde_and_indented_code_blocks() {
+    let doc = "Text.\n\n~~~\n# Examples\n```\n~~~\n\n    # Examples\n\n# Examples\n\n\
+               ~~~rust\nlet a = 1;\n~~~";
+    let examples = markup::extract_examples(&[doc.to_string()]).unwrap();
+    assert_eq!(examples.code_blocks, vec!["let a = 1;".to_string()]);
+    assert_eq!(examples.rest, "Text.\n\n~~~\n# Examples\n```\n~~~\n\n    # Examples\n");
+
+    let (code, _) = markup::extract_first_example("~~~text\n```\n~~~\n```\nlet b = 2;\n```")
+        .unwrap();
+    assert_eq!(code, "let b = 2;");
+}
+
+#[test]
+fn test_enum_signature() {
+    assert_signature("pub enum Shape { Empty, Circle(f32), Rect { w: u32, h: u32 } }",
+                     "crate::Shape",
+                     "  pub enum Shape {
+      Empty,
+      Circle(f32),
+      Rect { w: u32, h: u32 },
+  }");
+}
+
+#[test]
+fn test_generic_struct_signature() {
+    assert_signature("pub struct Wrapper<T: Clone> { pub inner: T }",
+                     "crate::Wrapper",
+                     "  pub struct Wrapper<T: Clone> {
+      pub inner: T,
+  }");
+    assert_signature("pub struct Pair<'a, T>(pub &'a T, pub T) where T: Default;",
+                     "crate::Pair",
+                     "  pub struct Pair<'a, T>(pub &'a T, pub T)
+  where T: Default;");
+}
+
+#[test]
+fn test_generic_enum_signature() {
+    assert_signature("pub enum Either<L, R> { Left(L), Right(R) }",
+                     "crate::Either",
+                     "  pub enum Either<L, R> {
+      Left(L),
+      Right(R),
+  }");
+}
+
+#[test]
+fn test_enum_signature_discriminants() {
+    assert_signature("pub enum Code { Ok = 0, Retry, Fatal = 1 << 4, Unknown }",
+                     "crate::Code",
+                     "  pub enum Code {
+      Ok = 0,
+      Retry,
+      Fatal = 1 << 4,
+      Unknown,
+  }");
+}
+
+#[test]
+fn test_cfg_note() {
+    let docs = source_to_docs("#[cfg(all(unix, feature = \"net\"))]\npub fn gated() {}\npub fn always() {}");
+
+    let parts = find_doc(&docs, "crate::gated").format().parts;
+    assert!(parts.contains(&Block("Available on unix and feature \"net\"".to_string())),
+            "{:?}", parts);
+
+    let parts = find_doc(&docs, "crate::always").format().parts;
+    assert!(!parts.iter().any(|part| match *part {
+        Block(ref text) => text.starts_with("Available on"),
+        _ => false,
+    }), "{:?}", parts);
+}
+
+#[test]
+fn test_must_use_note() {
+    let docs = source_to_docs(r#"
+#[must_use]
+pub struct Guard;
+#[must_use = "does nothing unless polled"]
+pub enum Task { Ready }
+"#);
+
+    let parts = find_doc(&docs, "crate::Guard").format().parts;
+    assert!(parts.contains(&Block("#[must_use]".to_string())), "{:?}", parts);
+
+    let parts = find_doc(&docs, "crate::Task").format().parts;
+    assert!(parts.contains(&Block("#[must_use = \"does nothing unless polled\"]".to_string())),
+            "{:?}", parts);
+}
+
+#[test]
+fn test_fn_qualifiers() {
+    let src = r#"
+pub const fn zero() -> u32 { 0 }
+pub unsafe fn raw() {}
+pub extern "C" fn callback() {}
+pub extern fn default_abi() {}
+pub unsafe extern "system" fn both() {}
+pub const unsafe fn risky() {}
+pub fn plain() {}
+"#;
+    assert_signature(src, "crate::zero", "  pub const fn zero() -> u32");
+    assert_signature(src, "crate::raw", "  pub unsafe fn raw()");
+    assert_signature(src, "crate::callback", "  pub extern \"C\" fn callback()");
+    assert_signature(src, "crate::default_abi", "  pub extern \"C\" fn default_abi()");
+    assert_signature(src, "crate::both", "  pub unsafe extern \"system\" fn both()");
+    assert_signature(src, "crate::risky", "  pub const unsafe fn risky()");
+    assert_signature(src, "crate::plain", "  pub fn plain()");
+}
+
+#[test]
+fn test_trait_method_qualifiers() {
+    let src = r#"
+pub trait Raw {
+    unsafe fn read(&self) -> u8;
+    extern "C" fn callback();
+    fn plain(&self);
+}
+"#;
+    assert_signature(src, "crate::Raw::read", "  unsafe fn read(&self) -> u8");
+    assert_signature(src, "crate::Raw::callback", "  extern \"C\" fn callback()");
+    assert_signature(src, "crate::Raw::plain", "  fn plain(&self)");
+}
+
+/// The signature among `parts` as it's shown, with its indentation and visibility.
+fn signature_of(parts: &[Markup]) -> Option<String> {
+    parts.iter()
+        .filter_map(|part| match *part {
+            Signature(ref vis, ref decl) => Some(format!("  {}{}", vis, decl)),
+            _ => None,
+        })
+        .next()
+}
+
+fn assert_signature(src: &str, path: &str, expected: &str) {
+    let docs = source_to_docs(src);
+    let parts = find_doc(&docs, path).format().parts;
+
+    assert!(signature_of(&parts).as_ref().map(|s| s.as_str()) == Some(expected),
+            "\nExpected signature\n{}\n\nFound\n{:?}\n", expected, parts);
+}
+
+#[test]
+fn test_named_struct_signature() {
+    assert_signature("pub struct Point { pub x: i32, y: Vec<String> }",
+                     "crate::Point",
+                     "  pub struct Point {
+      pub x: i32,
+      y: Vec<String>,
+  }");
+}
+
+#[test]
+fn test_empty_struct_signature() {
+    assert_signature("pub struct Empty {}", "crate::Empty", "  pub struct Empty {}");
+}
+
+#[test]
+fn test_tuple_struct_signature() {
+    assert_signature("pub struct Pair(pub u8, String);", "crate::Pair",
+                     "  pub struct Pair(pub u8, String);");
+}
+
+#[test]
+fn test_unit_struct_signature() {
+    assert_signature("pub struct Unit;", "crate::Unit", "  pub struct Unit;");
+}
+
+#[test]
+fn test_typedef_signature() {
+    assert_signature("pub type Result<T> = ::std::result::Result<T, MyError>;",
+                     "crate::Result",
+                     "  pub type Result<T> = ::std::result::Result<T, MyError>;");
+}
+
+#[test]
+fn test_static_signatures() {
+    assert_signature("pub static NAME: &str = \"oxidoc\";", "crate::NAME",
+                     "  pub static NAME: &str = \"oxidoc\";");
+    assert_signature("pub static mut COUNT: u32 = 0;", "crate::COUNT",
+                     "  pub static mut COUNT: u32 = 0;");
+}
+
+#[test]
+fn test_union_signature() {
+    assert_signature("pub union IntOrFloat { pub i: u32, f: f32 }",
+                     "crate::IntOrFloat",
+                     "  pub union IntOrFloat {
+      pub i: u32,
+      f: f32,
+  }");
+}
+
+#[test]
+fn test_macro_signature() {
+    assert_signature("#[macro_export] macro_rules! square { ($x:expr) => { $x * $x }; }",
+                     "crate::square",
+                     "  macro_rules! square {
+      ($x:expr) => { ... };
+  }");
+}
+
+#[test]
+fn test_fn_generics_signature() {
+    assert_signature("pub fn first<'a>(items: &'a [u8]) -> &'a u8 { &items[0] }",
+                     "crate::first",
+                     "  pub fn first<'a>(items: &'a [u8]) -> &'a u8");
+    assert_signature("pub fn make<T: Clone, U = u32>(t: T) -> U { unimplemented!() }",
+                     "crate::make",
+                     "  pub fn make<T: Clone, U = u32>(t: T) -> U");
+}
+
+#[test]
+fn test_where_clause_signatures() {
+    assert_signature("pub fn send<T>(t: T) where T: Clone + Send {}",
+                     "crate::send",
+                     "  pub fn send<T>(t: T)\n  where T: Clone + Send");
+    assert_signature("pub trait Pool<T> where T: Clone + Send {}",
+                     "crate::Pool",
+                     "  pub trait Pool<T>\n  where T: Clone + Send { /* fields omitted */ }");
+    assert_signature("pub fn noop<T>(t: T) where {}",
+                     "crate::noop",
+                     "  pub fn noop<T>(t: T)");
+}
+
+#[test]
+fn test_trait_bounds_signature() {
+    assert_signature("pub trait Named {}",
+                     "crate::Named",
+                     "  pub trait Named { /* fields omitted */ }");
+    assert_signature("pub trait Entity: Named + Clone {}",
+                     "crate::Entity",
+                     "  pub trait Entity: Named + Clone { /* fields omitted */ }");
+}
+
+#[test]
+fn test_deprecation_notice() {
+    let docs = source_to_docs(r#"
+/// Does foo.
+#[deprecated(since = "0.2.0", note = "Use `bar` instead.")]
+pub fn foo() {}
+#[deprecated]
+pub fn old() {}
+"#);
+
+    let parts = find_doc(&docs, "crate::foo").format().parts;
+    let notice = parts.iter().position(|p| *p == Section("Deprecated".to_string())).unwrap();
+    let body = parts.iter().position(|p| *p == Markdown("Does foo.".to_string())).unwrap();
+    assert!(notice < body);
+    assert_eq!(parts[notice + 1], Markdown("Since 0.2.0. Use `bar` instead.".to_string()));
+
+    let parts = find_doc(&docs, "crate::old").format().parts;
+    let notice = parts.iter().position(|p| *p == Section("Deprecated".to_string())).unwrap();
+    assert_eq!(parts[notice + 1], LineBreak);
+}
+
+#[test]
+fn test_stability_notice() {
+    let docs = source_to_docs(r#"
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn stable() {}
+#[unstable(feature = "fancy", issue = "1234")]
+#[rustc_deprecated(since = "1.2.0", reason = "not fancy enough")]
+pub fn unstable() {}
+"#);
+
+    let parts = find_doc(&docs, "crate::stable").format().parts;
+    assert!(!parts.iter().any(|p| match *p {
+        Section(ref text) => text.starts_with("Unstable"),
+        _ => false,
+    }));
+
+    let parts = find_doc(&docs, "crate::unstable").format().parts;
+    let unstable = parts.iter()
+        .position(|p| *p == Section("Unstable (feature = \"fancy\")".to_string()))
+        .unwrap();
+    let deprecated = parts.iter().position(|p| *p == Section("Deprecated".to_string())).unwrap();
+    assert!(unstable < deprecated);
+}
+
+#[test]
+fn test_fn_markdown() {
+    let docs = source_to_docs("/// Adds one.\npub fn add_one(x: i32) -> i32 { x + 1 }");
+    let markdown = find_doc(&docs, "crate::add_one").format().to_markdown();
+
+    assert_eq!(markdown, "(crate 1.0.0)\n\
+                          ## Function crate::add_one\n\
+                          \n\
+                          ---\n\
+                          \n\
+                          ```rust\n  \
+                          pub fn add_one(x: i32) -> i32\n\
+                          ```\n\
+                          \n\
+                          ---\n\
+                          \n\
+                          Adds one.\n");
+}
+
+#[test]
+fn test_markdown_fence_nested_code() {
+    let doc = MarkupDoc::new(vec![Block("  let s = \"```\";".to_string())]);
+
+    assert_eq!(doc.to_markdown(), "````rust\n  let s = \"```\";\n````\n");
+}
+
+#[test]
+fn test_const_html() {
+    let docs = source_to_docs("/// Limit for `a < b`.\npub const LIMIT: &str = \"<max>\";");
+    let html = find_doc(&docs, "crate::LIMIT").format().to_html();
+
+    assert!(html.contains("<h2>Constant crate::LIMIT</h2>"), "{}", html);
+    assert!(html.contains("<pre>  pub const LIMIT: &amp;str = &quot;&lt;max&gt;&quot;</pre>"),
+            "{}", html);
+    assert!(html.contains("<code>a &lt; b</code>"), "{}", html);
+    assert!(!html.contains("<max>"), "{}", html);
+
+    for tag in &["h2", "pre", "p", "code"] {
+        assert_eq!(html.matches(&format!("<{}>", tag)).count(),
+                   html.matches(&format!("</{}>", tag)).count(),
+                   "Unbalanced <{}> in {}", tag, html);
+    }
+}
+
+#[test]
+fn test_term_width_override() {
+    assert_eq!(markup::choose_term_width(Some("100"), Some(120)), 100);
+    assert_eq!(markup::choose_term_width(None, Some(120)), 120);
+    assert_eq!(markup::choose_term_width(Some("wide"), Some(120)), 120);
+    assert_eq!(markup::choose_term_width(None, None), 80);
+    assert_eq!(markup::choose_term_width(Some(" 64 "), None), 64);
+}
+
+#[test]
+fn test_term_width_clamp() {
+    assert_eq!(markup::choose_term_width(Some("0"), Some(120)), 20);
+    assert_eq!(markup::choose_term_width(None, Some(5)), 20);
+    assert_eq!(markup::choose_term_width(Some("99999999"), None), 80);
+}
+
+#[test]
+fn test_no_color_render() {
+    let docs = source_to_docs(DOCUMENTED_FN);
+    let doc = find_doc(&docs, "crate::add_one").format();
+    let plain = doc.render(false);
+
+    assert!(!plain.contains('\x1b'), "{:?}", plain);
+    assert!(plain.contains("==== Function crate::add_one"), "{}", plain);
+    assert!(plain.contains("== Example"), "{}", plain);
+
+    assert!(doc.render(true).contains('\x1b'));
+}
+
+#[test]
+fn test_module_contents() {
+    let docs = source_to_docs("pub mod shapes { pub fn zoom() {} pub struct Circle; pub fn area() {} }");
+    let parts = find_doc(&docs, "crate::shapes").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Contents".to_string()))
+        .expect("No contents section");
+    assert_eq!(&parts[pos + 1..],
+               &[Markdown("**Functions**\n\n- area\n- zoom".to_string()),
+                 Markdown("**Structs**\n\n- Circle".to_string())]);
+}
+
+#[test]
+fn test_module_contents_leave_out_links() {
+    let mut docs = source_to_docs("/// Holds a [`Circle`].\npub mod shapes { pub struct Circle; }");
+    intra_links::resolve_intra_links(&mut docs, &Store::new(StoreFormat::default()));
+    let parts = find_doc(&docs, "crate::shapes").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Contents".to_string()))
+        .expect("No contents section");
+    assert_eq!(&parts[pos + 1..], &[Markdown("**Structs**\n\n- Circle".to_string())]);
+}
+
+#[test]
+fn test_empty_module_contents() {
+    let docs = source_to_docs("pub mod nothing {}");
+    let parts = find_doc(&docs, "crate::nothing").format().parts;
+
+    assert!(!parts.contains(&Section("Contents".to_string())));
+}
+
+#[test]
+fn test_trait_items() {
+    let docs = source_to_docs(r#"
+pub trait Shape {
+    const SIDES: u32;
+    type Unit;
+    fn area(&self) -> f32;
+}
+"#);
+    let parts = find_doc(&docs, "crate::Shape").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Associated Types".to_string()))
+        .expect("No associated types section");
+    assert_eq!(&parts[pos..],
+               &[Section("Associated Types".to_string()),
+                 Markdown("- Unit".to_string()),
+                 LineBreak,
+                 Section("Associated Constants".to_string()),
+                 Markdown("- SIDES".to_string()),
+                 LineBreak,
+                 Section("Required Methods".to_string()),
+                 Markdown("- area".to_string())]);
+}
+
+#[test]
+fn test_trait_provided_methods() {
+    let docs = source_to_docs("pub trait Named { fn name(&self) -> String; fn greet(&self) {} }");
+    let parts = find_doc(&docs, "crate::Named").format().parts;
+
+    let required = parts.iter().position(|p| *p == Section("Required Methods".to_string()))
+        .expect("No required methods section");
+    assert_eq!(parts[required + 1], Markdown("- name".to_string()));
+
+    let provided = parts.iter().position(|p| *p == Section("Provided Methods".to_string()))
+        .expect("No provided methods section");
+    assert_eq!(parts[provided + 1], Markdown("- greet".to_string()));
+
+    assert!(!parts.contains(&Section("Associated Types".to_string())));
+}
+
+#[test]
+fn test_struct_methods() {
+    let docs = source_to_docs(r#"
+pub struct Point;
+impl Point {
+    pub fn scale(&self) {}
+    pub fn length(&self) -> f32 { 0.0 }
+}
+"#);
+    let parts = find_doc(&docs, "crate::Point").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Methods".to_string()))
+        .expect("No methods section");
+    assert_eq!(parts[pos + 1], Markdown("- length\n- scale".to_string()));
+}
+
+#[test]
+fn test_implemented_traits() {
+    let docs = source_to_docs(r#"
+pub trait Shape { fn area(&self) -> f32; }
+pub struct Square;
+impl Square {
+    pub fn side(&self) -> f32 { 1.0 }
+}
+impl Shape for Square {
+    fn area(&self) -> f32 { 1.0 }
+}
+impl Clone for Square { fn clone(&self) -> Square { Square } }
+pub enum Plain { A }
+"#);
+    let parts = find_doc(&docs, "crate::Square").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Implements".to_string()))
+        .expect("No implements section");
+    assert_eq!(parts[pos + 1], Markdown("- [Clone](Clone)\n- [Shape](crate::Shape)".to_string()));
+    let pos = parts.iter().position(|p| *p == Section("Methods".to_string()))
+        .expect("No methods section");
+    assert_eq!(parts[pos + 1], Markdown("- side".to_string()));
+
+    let parts = find_doc(&docs, "crate::Plain").format().parts;
+    assert!(!parts.contains(&Section("Implements".to_string())));
+}
+
+#[test]
+fn test_functions_and_methods_sections() {
+    let docs = source_to_docs(r#"
+pub struct Stack;
+impl Stack {
+    pub fn new() -> Stack { Stack }
+    pub fn with_capacity(n: usize) -> Stack { Stack }
+    pub fn len(&self) -> usize { 0 }
+}
+pub struct Unit;
+impl Unit {
+    pub fn get(&self) {}
+}
+"#);
+    let parts = find_doc(&docs, "crate::Stack").format().parts;
+
+    let functions = parts.iter().position(|p| *p == Section("Functions".to_string()))
+        .expect("No functions section");
+    assert_eq!(parts[functions + 1], Markdown("- new\n- with_capacity".to_string()));
+    let methods = parts.iter().position(|p| *p == Section("Methods".to_string()))
+        .expect("No methods section");
+    assert_eq!(parts[methods + 1], Markdown("- len".to_string()));
+    assert!(functions < methods);
+
+    let parts = find_doc(&docs, "crate::Unit").format().parts;
+    assert!(!parts.contains(&Section("Functions".to_string())));
+    assert!(parts.contains(&Section("Methods".to_string())));
+}
+
+#[test]
+fn test_no_methods_section() {
+    let docs = source_to_docs("pub enum Empty {}");
+    let parts = find_doc(&docs, "crate::Empty").format().parts;
+
+    assert!(!parts.contains(&Section("Methods".to_string())));
+}
+
+#[test]
+fn test_field_docs() {
+    let docs = source_to_docs(r#"
+pub struct Point {
+    /// Distance from the left.
+    ///
+    /// Can be negative.
+    pub x: f32,
+    pub y: f32,
+}
+pub struct Bare { pub x: f32 }
+"#);
+    let parts = find_doc(&docs, "crate::Point").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Fields".to_string()))
+        .expect("No fields section");
+    assert_eq!(parts[pos + 1], Markdown("- `x`: Distance from the left.\n- `y`".to_string()));
+
+    let parts = find_doc(&docs, "crate::Bare").format().parts;
+    assert!(!parts.contains(&Section("Fields".to_string())));
+}
+
+#[test]
+fn test_variant_docs() {
+    let docs = source_to_docs(r#"
+pub enum Shape {
+    /// A point with no area.
+    Dot,
+    /// ```
+    /// let unit = Shape::Circle(1.0);
+    /// ```
+    ///
+    /// A round shape. Its radius is given.
+    Circle(f32),
+    Square(f32),
+}
+"#);
+    let parts = find_doc(&docs, "crate::Shape").format().parts;
+
+    let pos = parts.iter().position(|p| *p == Section("Variants".to_string()))
+        .expect("No variants section");
+    assert_eq!(parts[pos + 1],
+               Markdown("- `Dot`: A point with no area.\n- `Circle`: A round shape.\n- `Square`"
+                        .to_string()));
+}
+
+#[test]
+fn test_highlight_rust_block() {
+    let theme = Theme::default();
+    let support = Some(ColorSupport::TrueColor);
+
+    let rust = Markdown("```rust\nlet s: String = \"a\"; // note\n```".to_string());
+    let rendered = rust.render_with_theme(&theme, support);
+    assert!(rendered.contains("\x1b[38;2;205;0;205mlet"), "{:?}", rendered);
+    assert!(rendered.contains("\x1b[38;2;205;205;0mString"), "{:?}", rendered);
+    assert!(rendered.contains("\x1b[38;2;0;205;0m\"a\""), "{:?}", rendered);
+    assert!(rendered.contains("\x1b[38;2;0;205;205m// note"), "{:?}", rendered);
+
+    let text = Markdown("```text\nlet s: String = \"a\";\n```".to_string());
+    let rendered = text.render_with_theme(&theme, support);
+    assert!(!rendered.contains("\x1b[38;2;205;0;205m"), "{:?}", rendered);
+    assert!(rendered.contains("let s: String = \"a\";"), "{:?}", rendered);
+}
+
+#[test]
+fn test_highlight_keeps_code() {
+    let code = "fn main() { let é = \"\\\"q\\\"\"; /* c */ }";
+    assert_eq!(highlight::highlight_rust(code, &Theme::mono(), ColorSupport::TrueColor), code);
+}
+
+#[test]
+fn test_restricted_visibility_signature() {
+    assert_signature("pub struct Fields { pub(crate) a: u8, pub(in a::b) b: u8, pub(self) c: u8, d: u8 }",
+                     "crate::Fields",
+                     "  pub struct Fields {
+      pub(crate) a: u8,
+      pub(in a::b) b: u8,
+      pub(self) c: u8,
+      d: u8,
+  }");
+}
+
+#[test]
+fn test_foreign_signature() {
+    let src = r#"extern "C" { pub fn abs(x: i32) -> i32; pub static mut errno: i32; }"#;
+    assert_signature(src, "crate::abs", "  pub extern \"C\" fn abs(x: i32) -> i32");
+    assert_signature(src, "crate::errno", "  pub extern \"C\" static mut errno: i32;");
+}
+
+#[test]
+fn test_source_location_header() {
+    let docs = source_to_docs_with_locations("\npub fn located() {}");
+    let parts = find_doc(&docs, "crate::located").format().parts;
+
+    assert!(parts.contains(&Block("Defined in test.rs:2".to_string())), "{:?}", parts);
+}
+
+/// The signature of the item at `path` fit to `width`, found by the `keyword` declaring it.
+fn signature_with_width(src: &str, path: &str, keyword: &str, width: u16) -> String {
+    let docs = source_to_docs(src);
+    let options = FormatOptions { width: width, ..FormatOptions::default() };
+    let parts = find_doc(&docs, path).format_with(&options).parts;
+    match signature_of(&parts) {
+        Some(ref text) if text.contains(keyword) => text.clone(),
+        _ => panic!("No signature found in {:?}", parts),
+    }
+}
+
+fn fn_signature(src: &str, path: &str, width: u16) -> String {
+    signature_with_width(src, path, "fn ", width)
+}
+
+#[test]
+fn test_short_fn_signature() {
+    let src = "pub fn add(a: u32, b: u32) -> u32 { a + b }";
+    assert_eq!(fn_signature(src, "crate::add", 80), "  pub fn add(a: u32, b: u32) -> u32");
+}
+
+#[test]
+fn test_long_fn_signature() {
+    let src = "pub fn connect<T: Into<String>>(host: T, port: u16, timeout: Option<u64>) \
+               -> Result<(), String> { Ok(()) }";
+    assert_eq!(fn_signature(src, "crate::connect", 40),
+               "  pub fn connect<T: Into<String>>(
+      host: T,
+      port: u16,
+      timeout: Option<u64>,
+  ) -> Result<(), String>");
+}
+
+#[test]
+fn test_fn_pointer_const_signature() {
+    let src = "pub const HANDLER: fn(u32, &str) -> Option<u32> = handle;";
+    assert_eq!(signature_with_width(src, "crate::HANDLER", "const ", 80),
+               "  pub const HANDLER: fn(u32, &str) -> Option<u32> = handle");
+    assert_eq!(signature_with_width(src, "crate::HANDLER", "const ", 40),
+               "  pub const HANDLER:
+      fn(u32, &str) -> Option<u32> = handle");
+}
+
+#[test]
+fn test_boxed_closure_static_signature() {
+    let src = "pub static CALLBACK: Box<Fn(u32) -> bool + Send + 'static> = callback;";
+    assert_eq!(signature_with_width(src, "crate::CALLBACK", "static ", 80),
+               "  pub static CALLBACK: Box<Fn(u32) -> bool + Send + 'static> = callback;");
+}
+
+#[test]
+fn test_long_fn_pointer_type_on_one_line() {
+    let src = "pub const LONG: fn(first_argument: SomeLongTypeName, second_argument: \
+               AnotherLongTypeName, third: YetAnother) -> Result<Vec<u8>, String> = long;";
+    assert_eq!(signature_with_width(src, "crate::LONG", "const ", 80),
+               "  pub const LONG:
+      fn(first_argument: SomeLongTypeName, second_argument: AnotherLongTypeName, \
+third: YetAnother) -> Result<Vec<u8>, String> = long");
+}
+
+#[test]
+fn test_impl_trait_signatures() {
+    let src = "pub fn digits(x: impl Display) -> impl Iterator<Item = u8> { 0..1 }";
+    assert_eq!(fn_signature(src, "crate::digits", 80),
+               "  pub fn digits(x: impl Display) -> impl Iterator<Item = u8>");
+
+    let src = "pub fn adder<'a>(base: &'a u8, f: impl Fn(u8) -> u8 + Send) -> impl Fn(u8) -> u8 + 'a \
+               { move |x| x }";
+    assert_eq!(fn_signature(src, "crate::adder", 40),
+               "  pub fn adder<'a>(
+      base: &'a u8,
+      f: impl Fn(u8) -> u8 + Send,
+  ) -> impl Fn(u8) -> u8 + 'a");
+}
+
+#[test]
+fn test_associated_const_signatures() {
+    let src = "pub trait Sized { const N: usize = 4; const M: u8; }";
+    assert_signature(src, "crate::Sized::N", "  const N: usize = 4;");
+    assert_signature(src, "crate::Sized::M", "  const M: u8;");
+}
+
+#[test]
+fn test_associated_type_signatures() {
+    let src = "pub trait Container { type Item: Clone + Send; type Key = u8; type Plain; }";
+    assert_signature(src, "crate::Container::Item", "  type Item: Clone + Send;");
+    assert_signature(src, "crate::Container::Key", "  type Key = u8;");
+    assert_signature(src, "crate::Container::Plain", "  type Plain;");
+}
+
+#[test]
+fn test_trait_method_headers() {
+    let docs = source_to_docs("pub trait T { fn required(&self); fn provided(&self) {} }");
+    let header = |path: &str| find_doc(&docs, path).format().parts[1].clone();
+
+    assert_eq!(header("crate::T::required"), Header("Required Method crate::T::required".to_string()));
+    assert_eq!(header("crate::T::provided"), Header("Provided Method crate::T::provided".to_string()));
+}
+
+struct FakeTerminal {
+    tty: bool,
+    height: Option<usize>,
+}
+
+impl markup::OutputTarget for FakeTerminal {
+    fn is_tty(&self) -> bool {
+        self.tty
+    }
+
+    fn height(&self) -> Option<usize> {
+        self.height
+    }
+}
+
+#[test]
+fn test_should_page() {
+    let text = "one\ntwo\nthree";
+    let terminal = |tty, height| FakeTerminal { tty: tty, height: height };
+
+    assert!(markup::should_page(text, &terminal(true, Some(2))));
+    assert!(!markup::should_page(text, &terminal(true, Some(3))));
+    assert!(!markup::should_page(text, &terminal(false, Some(2))));
+    assert!(!markup::should_page(text, &terminal(true, None)));
+}
+
+#[test]
+fn test_pager_command() {
+    assert_eq!(markup::pager_command(Some("most")), "most");
+    assert_eq!(markup::pager_command(Some("")), "less -R");
+    assert_eq!(markup::pager_command(None), "less -R");
+}
+
+#[test]
+fn test_wrap_paragraph() {
+    let md = "The quick brown fox jumps over the lazy dog and keeps running until the end of \
+              the line.\n\nSecond paragraph.";
+    let wrapped = markup::wrap_markdown(md, 30);
+
+    assert_eq!(wrapped, "The quick brown fox jumps over
+the lazy dog and keeps running
+until the end of the line.
+
+Second paragraph.");
+}
+
+#[test]
+fn test_wrap_list() {
+    let md = "- A list item that is much too long to fit\n- Short\n  1. Nested item that wraps too";
+    let wrapped = markup::wrap_markdown(md, 20);
+
+    assert_eq!(wrapped, "- A list item that
+  is much too long
+  to fit
+- Short
+  1. Nested item
+     that wraps too");
+}
+
+#[test]
+fn test_wrap_keeps_code_spans_and_long_words() {
+    let md = "Call `Vec::with_capacity(n)` or `a b c d`, see \
+              https://doc.rust-lang.org/std/vec/struct.Vec.html for more.";
+    let wrapped = markup::wrap_markdown(md, 20);
+
+    assert_eq!(wrapped, "Call
+`Vec::with_capacity(n)`
+or `a b c d`, see
+https://doc.rust-lang.org/std/vec/struct.Vec.html
+for more.");
+}
+
+#[test]
+fn test_wrap_skips_code_and_headings() {
+    let md = "# A heading that is long enough to wrap\n\n```\nlet x = some_long_function_call(a, b);\n```\n\
+              Line with a hard break  \nnext line";
+    let wrapped = markup::wrap_markdown(md, 20);
+
+    assert_eq!(wrapped, "# A heading that is long enough to wrap
+
+```
+let x = some_long_function_call(a, b);
+```
+Line with a hard
+break  
+next line");
+}
+
+#[test]
+fn test_markdown_headings() {
+    let theme = Theme::default();
+    let support = Some(ColorSupport::TrueColor);
+    let md = Markdown("Intro.\n\n## Examples\n\nText.".to_string());
+
+    let rendered = md.render_with_theme(&theme, support);
+    assert!(!rendered.contains("##"));
+    assert!(rendered.contains(&Section("Examples".to_string()).render_with_theme(&theme, support)));
+
+    let plain = Markdown("# Title #\n### Details".to_string()).render(false);
+    assert_eq!(plain, "==== Title\nDetails");
+}
+
+#[test]
+fn test_markdown_heading_needs_space() {
+    let plain = Markdown("#hashtag".to_string()).render(false);
+    assert_eq!(plain, "#hashtag");
+}
+
+#[test]
+fn test_markdown_headings_skip_code_blocks() {
+    let md = "~~~sh\n# A comment\n```\n# Still a comment\n~~~\n\n    # Indented";
+    let plain = Markdown(md.to_string()).render(false);
+    assert!(plain.contains("# A comment\n"), "{}", plain);
+    assert!(plain.contains("# Still a comment\n"), "{}", plain);
+    assert!(plain.contains("    # Indented"), "{}", plain);
+    assert!(!plain.contains("===="), "{}", plain);
+}
+
+#[test]
+fn test_rule_spans_width() {
+    let docs = source_to_docs(DOCUMENTED_FN);
+    let doc = find_doc(&docs, "crate::add_one");
+
+    for &(width, expected) in &[(40, 40), (80, 80), (300, 100)] {
+        let options = FormatOptions { width: width, ..FormatOptions::default() };
+        let parts = doc.format_with(&options).parts;
+        let rules: Vec<&Markup> = parts.iter()
+            .filter(|part| match **part { Rule(..) => true, _ => false })
+            .collect();
+        assert_eq!(rules, vec![&Rule(expected), &Rule(expected)]);
+
+        let rendered = rules[0].render(false);
+        assert_eq!(rendered, "-".repeat(expected));
+    }
+}
+
+#[test]
+fn test_markdown_fits_format_width() {
+    let docs = source_to_docs(r#"
+/// Some words that are long enough to be wrapped at a narrow width but not a wide one.
+///
+/// | Name | Meaning |
+/// |------|---------|
+/// | first | the first of several cells that will not fit |
+pub fn wrapped() {}
+"#);
+    let doc = find_doc(&docs, "crate::wrapped");
+
+    for &width in &[30, 120] {
+        let options = FormatOptions { width: width, ..FormatOptions::default() };
+        let rendered = doc.format_with(&options).render(false);
+        let widest = rendered.lines().map(|line| line.chars().count()).max().unwrap();
+
+        assert!(widest <= width as usize, "Wider than {}:\n{}", width, rendered);
+        assert_eq!(rendered.contains("wrapped at a narrow width"), width == 120, "{}", rendered);
+    }
+}
+
+#[test]
+fn test_visibility_styled() {
+    let theme = Theme::default();
+    let support = Some(ColorSupport::TrueColor);
+    let docs = source_to_docs("pub fn public() {}\npub trait T { fn inherited(&self); }");
+
+    let rendered = find_doc(&docs, "crate::public").format().render_with_theme(&theme, support);
+    let styled_pub = theme.visibility.paint("pub ".to_string(), support);
+    let visibility_code = &styled_pub[..styled_pub.find("pub").unwrap()];
+    assert!(visibility_code.contains('\x1b'));
+    assert!(rendered.contains(&styled_pub), "{:?}", rendered);
+
+    let rendered = find_doc(&docs, "crate::T::inherited").format()
+        .render_with_theme(&theme, support);
+    assert!(rendered.contains("\n  fn inherited"), "{:?}", rendered);
+    assert!(!rendered.contains(visibility_code), "{:?}", rendered);
+
+    let plain = find_doc(&docs, "crate::public").format().render(false);
+    assert!(plain.contains("  pub fn public()"), "{}", plain);
+}
+
+#[test]
+fn test_other_attrs_above_signature() {
+    let docs = source_to_docs("/// Docs.\n#[repr(C)]\n#[inline]\npub fn ffi() {}");
+    let rendered = find_doc(&docs, "crate::ffi").format().render(false);
+
+    assert!(rendered.contains("  #[repr(C)]\n  #[inline]\n  pub fn ffi()"), "{}", rendered);
+}
+
+#[test]
+fn test_repr_above_adt_signatures() {
+    let docs = source_to_docs(r#"
+#[repr(C)]
+pub struct Point { pub x: i32 }
+
+#[repr(C, packed)]
+pub struct Packed { pub x: u8 }
+
+#[repr(u8)]
+#[repr(C)]
+pub enum Tag { A }
+
+#[repr(C)]
+pub union Bits { pub i: u32 }
+"#);
+    let render = |path: &str| find_doc(&docs, path).format().render(false);
+
+    assert!(render("crate::Point").contains("  #[repr(C)]\n  pub struct Point {"),
+            "{}", render("crate::Point"));
+    assert!(render("crate::Packed").contains("  #[repr(C, packed)]\n  pub struct Packed {"),
+            "{}", render("crate::Packed"));
+    assert!(render("crate::Tag").contains("  #[repr(u8, C)]\n  pub enum Tag {"),
+            "{}", render("crate::Tag"));
+    assert!(render("crate::Bits").contains("  #[repr(C)]\n  pub union Bits {"),
+            "{}", render("crate::Bits"));
+}
+
+#[test]
+fn test_control_chars_escaped() {
+    let docs = source_to_docs("/// Colors:\n///\n/// ```text\n/// \u{1b}[31mred\u{1b}[0m\n/// ```\n\
+                               pub fn paint() {}");
+    let doc = find_doc(&docs, "crate::paint");
+    assert!(doc.attrs.doc_strings.join("\n").contains("\u{1b}[31m"));
+
+    for &color in &[false, true] {
+        let rendered = doc.format().render_with_theme(&Theme::mono(), if color {
+            Some(ColorSupport::TrueColor)
+        } else {
+            None
+        });
+        assert!(!rendered.contains("\u{1b}[31m"), "{:?}", rendered);
+        assert!(rendered.contains("\\u{1b}[31mred"), "{:?}", rendered);
+    }
+
+    let block = Markup::Block("  \u{1b}[2J\tfn f()\u{7}".to_string()).render(false);
+    assert_eq!(block, "  \\u{1b}[2J\tfn f()\\u{7}");
+}
@@ -0,0 +1,115 @@
+use oxidoc::markup::Markup;
+use oxidoc::table::{self, Alignment};
+use oxidoc::theme::{ColorSupport, Theme};
+
+fn render(md: &str, width: usize) -> String {
+    let lines: Vec<&str> = md.lines().collect();
+    let (table, len) = table::parse_table(&lines).expect("no table found");
+    assert_eq!(len, lines.len());
+    table.render(width, &Theme::default(), None)
+}
+
+#[test]
+fn test_two_column_table() {
+    let md = "| Flag | Meaning |
+|------|--------:|
+| `-q` | quiet |
+| `--verbose` | more output |";
+
+    assert_eq!(render(md, 80), "Flag        │     Meaning
+────────────┼────────────
+`-q`        │       quiet
+`--verbose` │ more output");
+}
+
+#[test]
+fn test_table_alignments() {
+    let lines = ["a | b | c", ":-- | :-: | --:", "1 | 2 | 3"];
+    let (table, len) = table::parse_table(&lines).unwrap();
+
+    assert_eq!(len, 3);
+    assert_eq!(table.alignments, vec![Alignment::Left, Alignment::Center, Alignment::Right]);
+    assert_eq!(table.rows, vec![vec!["1".to_string(), "2".to_string(), "3".to_string()]]);
+}
+
+#[test]
+fn test_table_truncated_to_width() {
+    let md = "| Name | Description |
+|---|---|
+| a | The first of several rather long descriptions |";
+
+    assert_eq!(render(md, 20), "Name │ Description
+─────┼──────────────
+a    │ The first of…");
+}
+
+#[test]
+fn test_table_stops_at_blank_line() {
+    let lines = ["| a | b |", "|---|---|", "| 1 | 2 |", "", "| not | a row |"];
+    let (table, len) = table::parse_table(&lines).unwrap();
+
+    assert_eq!(len, 3);
+    assert_eq!(table.rows.len(), 1);
+}
+
+#[test]
+fn test_table_escaped_pipe() {
+    let lines = ["| op | meaning |", "|----|---------|", "| `a \\| b` | or |"];
+    let (table, _) = table::parse_table(&lines).unwrap();
+
+    assert_eq!(table.rows[0][0], "`a | b`");
+}
+
+#[test]
+fn test_prose_pipes_are_not_tables() {
+    assert!(table::parse_table(&["Returns `a | b` when set.", "Otherwise | nothing."]).is_none());
+    assert!(table::parse_table(&["| a | b |", "| c | d |"]).is_none());
+    assert!(table::parse_table(&["| a | b |", "|---|---|---|"]).is_none());
+
+    let md = "Either `a | b` or `c`.";
+    assert_eq!(Markup::Markdown(md.to_string()).render(false), md);
+}
+
+#[test]
+fn test_ascii_decorations() {
+    let theme = Theme { ascii: true, ..Theme::default() };
+    let md = "Prices in €.
+
+| Item | Price |
+|------|------:|
+| Café au lait | 3.50 |
+
+More text.";
+    let rendered = Markup::Markdown(md.to_string()).render_with_theme(&theme, None);
+
+    assert!(rendered.contains("Item         | Price\n-------------+------\n"), "{}", rendered);
+    assert!(rendered.contains("Café au lait |  3.50"), "{}", rendered);
+    let decorations: String = rendered.replace("€", "").replace("é", "");
+    assert!(decorations.is_ascii(), "{}", rendered);
+
+    let lines: Vec<&str> = md.lines().skip(2).take(3).collect();
+    let (table, _) = table::parse_table(&lines).unwrap();
+    assert_eq!(table.render(10, &theme, None), "Item | ...\n-----+----\nC... | ...");
+}
+
+#[test]
+fn test_table_markup() {
+    let table = Markup::Table {
+        headers: vec!["Crate".to_string(), "Items".to_string()],
+        rows: vec![vec!["std".to_string(), "1024".to_string()],
+                   vec!["a|b".to_string()]],
+    };
+
+    let rendered = table.render_with_theme(&Theme::default(), None);
+    assert_eq!(rendered, "Crate │ Items\n──────┼──────\nstd   │ 1024\na|b   │");
+    let colored = table.render_with_theme(&Theme::default(), Some(ColorSupport::TrueColor));
+    assert!(colored.contains('\x1b'), "{:?}", colored);
+    assert!(colored.contains("std   │ 1024"), "{:?}", colored);
+
+    assert_eq!(table.to_markdown(),
+               "| Crate | Items |\n|---|---|\n| std | 1024 |\n| a\\|b |  |");
+    assert_eq!(table.to_html(),
+               "<table><thead><tr><th>Crate</th><th>Items</th></tr></thead>\
+                <tbody><tr><td>std</td><td>1024</td></tr><tr><td>a|b</td><td></td></tr></tbody>\
+                </table>");
+}
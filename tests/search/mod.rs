@@ -1 +1,2 @@
 mod test_lookup;
+mod test_search;
@@ -1,4 +1,5 @@
-use oxidoc::store::Store;
+use oxidoc::document::ModPath;
+use oxidoc::store::{Store, StoreFormat};
 use oxidoc::generation;
 use util;
 
@@ -7,17 +8,11 @@ fn add_docs(store: &mut Store, krate_name: &str, version: &str, src: &str) {
     let docset = generation::make_docset(docs).unwrap();
 
     store.add_docset(util::get_crate_info(krate_name, version), docset);
+    store.save_documents().unwrap();
 }
 
 fn store_from_source(src: &str) -> Store {
-    store_from_crate_source("crate", "1.0.0", src)
-}
-
-fn store_from_crate_source(krate_name: &str, version: &str, src: &str) -> Store {
-    let mut store = Store::new();
-    add_docs(&mut store, krate_name, version, src);
-
-    store
+    util::store_from_source("crate", "1.0.0", src)
 }
 
 fn assert_search_query(store: &Store, query: &str, expected_paths: Vec<&str>) {
@@ -66,7 +61,7 @@ pub mod nyanko {
 
 #[test]
 fn test_search_for_removed_item() {
-    let mut store = Store::new();
+    let mut store = Store::new(StoreFormat::default());
     add_docs(&mut store, "crate", "0.0.1", r#"
 pub mod stuff {
     pub fn depreciated() {}
@@ -78,3 +73,53 @@ pub mod stuff {
 "#);
     assert_search_query(&store, "stuff::depreciated", vec!["crate::stuff::depreciated"]);
 }
+
+#[test]
+fn test_load_doc() {
+    let mut store = store_from_source("pub mod a { pub struct Thing; }");
+    store.save_documents().unwrap();
+    let path = ModPath::from("crate::a::Thing".to_string());
+
+    let doc = store.load_doc(&path).unwrap();
+    assert_eq!(doc.name, "Thing");
+    assert_eq!(doc.mod_path, path);
+}
+
+#[test]
+fn test_load_missing_doc() {
+    let store = store_from_source("pub struct Thing;");
+    assert!(store.load_doc(&ModPath::from("crate::Other".to_string())).is_err());
+    assert!(store.load_doc(&ModPath::from("nonexistent::Thing".to_string())).is_err());
+}
+
+#[test]
+fn test_lookup_follows_reexport() {
+    let store = store_from_source(r#"
+pub mod a {
+    pub struct Original;
+}
+
+pub use a::Original as Renamed;
+"#);
+    assert_search_query(&store, "Renamed", vec!["crate::a::Original"]);
+}
+
+#[test]
+fn test_child_paths() {
+    let store = store_from_source(r#"
+pub mod a {
+    pub mod b {
+        pub fn deep() {}
+    }
+    pub fn shallow() {}
+}
+pub struct Top;
+"#);
+
+    let children = |s: &str| -> Vec<String> {
+        store.child_paths(&ModPath::from(s.to_string())).iter().map(|p| p.to_string()).collect()
+    };
+    assert_eq!(children("crate"), vec!["crate::Top", "crate::a"]);
+    assert_eq!(children("crate::a"), vec!["crate::a::b", "crate::a::shallow"]);
+    assert_eq!(children("crate::a::b::deep"), Vec::<String>::new());
+}
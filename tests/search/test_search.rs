@@ -0,0 +1,227 @@
+use oxidoc::conversion::DocType;
+use oxidoc::document::ModPath;
+use oxidoc::store::{self, Store, StoreFormat};
+use oxidoc::generation;
+use util;
+
+fn store_from_source(src: &str) -> Store {
+    util::store_from_source("crate", "1.0.0", src)
+}
+
+fn search_results(store: &Store, query: &str) -> Vec<(String, DocType)> {
+    store.search(query)
+        .into_iter()
+        .map(|loc| (loc.mod_path.to_string(), loc.doc_type.clone()))
+        .collect()
+}
+
+const SOURCE: &str = r#"
+pub struct Parser;
+pub fn parse() {}
+pub fn parse_all() {}
+pub fn reparse() {}
+pub struct Unrelated;
+"#;
+
+#[test]
+fn test_search_exact() {
+    let store = store_from_source(SOURCE);
+    let results = search_results(&store, "PARSER");
+    assert_eq!(results, vec![("crate::Parser".to_string(), DocType::Struct)]);
+}
+
+#[test]
+fn test_search_prefix_and_substring() {
+    let store = store_from_source(SOURCE);
+    let results = search_results(&store, "parse");
+    assert_eq!(results, vec![("crate::parse".to_string(), DocType::Function),
+                             ("crate::Parser".to_string(), DocType::Struct),
+                             ("crate::parse_all".to_string(), DocType::Function),
+                             ("crate::reparse".to_string(), DocType::Function)]);
+}
+
+#[test]
+fn test_search_substring() {
+    let store = store_from_source(SOURCE);
+    let results = search_results(&store, "rel");
+    assert_eq!(results, vec![("crate::Unrelated".to_string(), DocType::Struct)]);
+
+    assert!(store.search("nothing").is_empty());
+}
+
+fn fuzzy_results(store: &Store, query: &str, limit: usize) -> Vec<String> {
+    store.fuzzy_search(query, limit)
+        .into_iter()
+        .map(|loc| loc.mod_path.to_string())
+        .collect()
+}
+
+#[test]
+fn test_fuzzy_search_abbreviation() {
+    let store = store_from_source(r#"
+pub struct HashMapBuilder;
+pub struct HashMap;
+pub struct HashSet;
+pub fn hash_map_len() {}
+"#);
+
+    let results = fuzzy_results(&store, "HshMp", 10);
+    assert_eq!(results[0], "crate::HashMap");
+    assert!(results.contains(&"crate::HashMapBuilder".to_string()));
+    assert!(!results.contains(&"crate::HashSet".to_string()));
+}
+
+#[test]
+fn test_fuzzy_search_ties_and_limit() {
+    let store = store_from_source(r#"
+pub fn mapxyz() {}
+pub fn amap() {}
+pub fn map() {}
+"#);
+
+    // "amap" and "mapxyz" score the same, so the shorter name wins.
+    assert_eq!(fuzzy_results(&store, "map", 10), vec!["crate::map", "crate::amap", "crate::mapxyz"]);
+    assert_eq!(fuzzy_results(&store, "map", 1), vec!["crate::map"]);
+}
+
+// Kept in separate modules, since items whose paths differ only in case share a store entry.
+const SAME_NAMES: &str = r#"
+pub mod iter {
+    pub struct Map;
+    pub fn iterator() {}
+}
+pub fn map() {}
+pub trait Iterator {}
+"#;
+
+fn typed_results(store: &Store, query: &str, ty: DocType) -> Vec<(String, DocType)> {
+    store.search_by_type(query, ty)
+        .into_iter()
+        .map(|loc| (loc.mod_path.to_string(), loc.doc_type.clone()))
+        .collect()
+}
+
+#[test]
+fn test_search_functions_only() {
+    let store = store_from_source(SAME_NAMES);
+    assert_eq!(typed_results(&store, "map", DocType::Function),
+               vec![("crate::map".to_string(), DocType::Function)]);
+    assert_eq!(typed_results(&store, "map", DocType::Struct),
+               vec![("crate::iter::Map".to_string(), DocType::Struct)]);
+}
+
+#[test]
+fn test_search_traits_only() {
+    let store = store_from_source(SAME_NAMES);
+    assert_eq!(typed_results(&store, "iterator", DocType::Trait),
+               vec![("crate::Iterator".to_string(), DocType::Trait)]);
+}
+
+#[test]
+fn test_search_aliases() {
+    let store = store_from_source(r#"
+#[doc(alias = "grow")]
+#[doc(alias = "expand")]
+pub fn reserve() {}
+#[doc(alias = "growing")]
+pub fn grow_fast() {}
+pub fn shrink() {}
+"#);
+
+    assert_eq!(search_results(&store, "grow"),
+               vec![("crate::reserve".to_string(), DocType::Function),
+                    ("crate::grow_fast".to_string(), DocType::Function)]);
+    assert_eq!(search_results(&store, "EXPAND"),
+               vec![("crate::reserve".to_string(), DocType::Function)]);
+}
+
+#[test]
+fn test_parse_query_prefixes() {
+    let cases = [("fn:map", DocType::Function),
+                 ("method:push", DocType::Method),
+                 ("mod:vec", DocType::Module),
+                 ("enum:Option", DocType::Enum),
+                 ("variant:Some", DocType::Variant),
+                 ("struct:Vec", DocType::Struct),
+                 ("union:Bits", DocType::Union),
+                 ("field:len", DocType::StructField),
+                 ("const:MAX", DocType::Const),
+                 ("static:COUNT", DocType::Static),
+                 ("type:Result", DocType::Typedef),
+                 ("trait:Iter", DocType::Trait),
+                 ("macro:vec", DocType::Macro)];
+    for &(query, ref doc_type) in &cases {
+        let text = query[query.find(':').unwrap() + 1..].to_string();
+        assert_eq!(store::parse_query(query), (Some(doc_type.clone()), text));
+    }
+}
+
+#[test]
+fn test_parse_query_without_prefix() {
+    assert_eq!(store::parse_query("map"), (None, "map".to_string()));
+    assert_eq!(store::parse_query("vec::Vec"), (None, "vec::Vec".to_string()));
+    assert_eq!(store::parse_query("fn::map"), (None, "fn::map".to_string()));
+    assert_eq!(store::parse_query("unknown:map"), (None, "unknown:map".to_string()));
+    assert_eq!(store::parse_query("fn:a:b"), (Some(DocType::Function), "a:b".to_string()));
+}
+
+#[test]
+fn test_search_with_parsed_query() {
+    let store = store_from_source(SOURCE);
+    let (doc_type, text) = store::parse_query("struct:parse");
+    let results: Vec<String> = store.search_by_type(&text, doc_type.unwrap())
+        .into_iter()
+        .map(|loc| loc.mod_path.to_string())
+        .collect();
+    assert_eq!(results, vec!["crate::Parser"]);
+}
+
+fn resolve_partial_results(store: &Store, suffix: &str) -> Vec<String> {
+    store.resolve_partial(&ModPath::from(suffix.to_string()))
+        .into_iter()
+        .map(|loc| loc.mod_path.to_string())
+        .collect()
+}
+
+#[test]
+fn test_resolve_partial() {
+    let mut store = Store::new(StoreFormat::default());
+    for krate in &["alpha", "beta"] {
+        let docs = util::source_to_crate_docs(krate, "1.0.0", "pub mod foo { pub struct Bar; }");
+        let docset = generation::make_docset(docs).unwrap();
+        store.add_docset(util::get_crate_info(krate, "1.0.0"), docset);
+    }
+
+    assert_eq!(resolve_partial_results(&store, "foo::Bar"),
+               vec!["alpha::foo::Bar".to_string(), "beta::foo::Bar".to_string()]);
+    assert_eq!(resolve_partial_results(&store, "alpha::foo::Bar"),
+               vec!["alpha::foo::Bar".to_string()]);
+    assert!(resolve_partial_results(&store, "oo::Bar").is_empty());
+    assert!(store.resolve_partial(&ModPath::new()).is_empty());
+}
+
+#[test]
+fn test_search_by_signature() {
+    let store = store_from_source(r#"
+pub fn is_even(n: usize) -> bool { n % 2 == 0 }
+pub fn is_prime(candidate: usize) -> bool { false }
+pub fn is_small(n: u8) -> bool { n < 10 }
+pub fn describe(n: usize) -> String { String::new() }
+pub fn clear(items: &mut Vec<u8>) {}
+
+pub struct Set;
+impl Set {
+    pub fn contains(&self, n: usize) -> bool { false }
+}
+"#);
+    let results = |query: &str| -> Vec<String> {
+        store.search_by_signature(query).iter().map(|loc| loc.mod_path.to_string()).collect()
+    };
+
+    assert_eq!(results("(usize) -> bool"), vec!["crate::is_even", "crate::is_prime"]);
+    assert_eq!(results("( usize )->bool"), vec!["crate::is_even", "crate::is_prime"]);
+    assert_eq!(results("(&Self, usize) -> bool"), vec!["crate::Set::contains"]);
+    assert_eq!(results("(&mut Vec<u8>)"), vec!["crate::clear"]);
+    assert_eq!(results("(&mut Vec< u8 >) -> ()"), vec!["crate::clear"]);
+    assert!(results("(usize)").is_empty());
+}
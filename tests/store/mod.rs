@@ -0,0 +1,14 @@
+mod test_save;
+mod test_index;
+mod test_merge;
+mod test_store_file;
+mod test_json;
+mod test_remove;
+mod test_iter;
+mod test_incremental;
+mod test_logging;
+mod test_stats;
+mod test_registry;
+mod test_validate;
+mod test_save_plan;
+mod test_path_for;
@@ -0,0 +1,106 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use oxidoc::document::{ModPath, SourceLocation};
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+fn write_source(name: &str, contents: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("oxidoc-incremental-{}.rs", name));
+    File::create(&path).unwrap().write_all(contents.as_bytes()).unwrap();
+    path
+}
+
+/// A store for a crate whose items in `inner` are recorded as defined in `source`. Nothing is
+/// saved.
+fn unsaved_store_from_file(krate: &str, source: &PathBuf) -> Store {
+    let mut docs = util::source_to_crate_docs(krate, "1.0.0", "pub mod inner { pub fn f() {} }");
+    for doc in docs.iter_mut().filter(|doc| doc.mod_path.to_string().contains("inner")) {
+        doc.source = Some(SourceLocation {
+            file: source.display().to_string(),
+            line: 1,
+            col: 0,
+        });
+    }
+
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info(krate, "1.0.0"),
+                     generation::make_docset(docs).unwrap());
+    store
+}
+
+/// Like `unsaved_store_from_file`, but with the documents saved.
+fn store_from_file(krate: &str, source: &PathBuf) -> Store {
+    let mut store = unsaved_store_from_file(krate, source);
+    store.save_documents().unwrap();
+    store
+}
+
+#[test]
+fn test_needs_rebuild() {
+    let source = write_source("changed", "pub mod inner { pub fn f() {} }");
+    let store = store_from_file("incremental", &source);
+    let files = vec![source.clone()];
+
+    assert!(store.needs_rebuild(&files).is_empty());
+
+    // Leave time for the modification time to move on.
+    thread::sleep(Duration::from_millis(50));
+    write_source("changed", "pub mod inner { pub fn f() {} pub fn g() {} }");
+
+    assert_eq!(store.needs_rebuild(&files).modules,
+               vec![ModPath::from("incremental::inner".to_string())]);
+    assert!(store.needs_rebuild(&[]).is_empty());
+}
+
+#[test]
+fn test_deleted_source() {
+    let source = write_source("deleted", "pub mod inner { pub fn f() {} }");
+    let mut store = store_from_file("incremental_deleted", &source);
+    assert_eq!(store.lookup_name("incremental_deleted::inner::f").len(), 1);
+
+    fs::remove_file(&source).unwrap();
+    assert_eq!(store.needs_rebuild(&[]).modules,
+               vec![ModPath::from("incremental_deleted::inner".to_string())]);
+
+    let mut removed: Vec<String> = store.remove_deleted_sources().unwrap().iter()
+        .map(|path| path.to_string())
+        .collect();
+    removed.sort();
+    assert_eq!(removed, vec!["incremental_deleted::inner", "incremental_deleted::inner::f"]);
+    assert!(store.lookup_name("incremental_deleted::inner::f").is_empty());
+    assert!(store.needs_rebuild(&[]).is_empty());
+}
+
+#[test]
+fn test_changed_before_save() {
+    let source = write_source("changed-before-save", "pub mod inner { pub fn f() {} }");
+    let mut store = unsaved_store_from_file("incremental_unsaved", &source);
+    let files = vec![source.clone()];
+    assert!(store.needs_rebuild(&files).is_empty());
+
+    thread::sleep(Duration::from_millis(50));
+    write_source("changed-before-save", "pub mod inner { pub fn g() {} }");
+    store.save_documents().unwrap();
+
+    assert_eq!(store.needs_rebuild(&files).modules,
+               vec![ModPath::from("incremental_unsaved::inner".to_string())]);
+}
+
+#[test]
+fn test_untracked_source() {
+    let source = write_source("tracked", "pub mod inner { pub fn f() {} }");
+    let added = write_source("untracked", "pub fn added() {}");
+    let store = store_from_file("incremental_untracked", &source);
+
+    let stale = store.needs_rebuild(&[source.clone(), added.clone()]);
+    assert!(stale.modules.is_empty());
+    assert_eq!(stale.untracked, vec![added]);
+}
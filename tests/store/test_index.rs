@@ -0,0 +1,43 @@
+use oxidoc::document::ModPath;
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+fn methods_of(store: &Store, scope: &str) -> Vec<String> {
+    store.get_methods(&ModPath::from(scope.to_string()))
+        .map(|methods| methods.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_methods_indexed_by_scope() {
+    let docs = util::source_to_docs(r#"
+pub mod a {
+    pub struct Widget;
+    impl Widget {
+        pub fn new() -> Widget { Widget }
+        pub fn draw(&self) {}
+    }
+}
+
+pub mod b {
+    pub struct Widget;
+    impl Widget {
+        pub fn new() -> Widget { Widget }
+    }
+}
+
+pub trait Render {
+    fn render(&self);
+}
+"#);
+    let docset = generation::make_docset(docs).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("crate", "1.0.0"), docset);
+
+    assert_eq!(methods_of(&store, "crate::a::Widget"), vec!["draw", "new"]);
+    assert_eq!(methods_of(&store, "crate::b::Widget"), vec!["new"]);
+    assert_eq!(methods_of(&store, "crate::Render"), vec!["render"]);
+    assert!(store.get_methods(&ModPath::from("crate::a".to_string())).is_none());
+}
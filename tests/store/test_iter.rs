@@ -0,0 +1,66 @@
+use oxidoc::conversion::Visibility;
+use oxidoc::document::ModPath;
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+/// A store whose `hidden` items are made private, since only `pub` items come out of conversion.
+fn mixed_store() -> Store {
+    let mut docs = util::source_to_docs(r#"
+pub struct Shown;
+pub fn hidden() {}
+pub mod inner {
+    pub fn shown_inner() {}
+    pub(crate) fn crate_only() {}
+    pub trait Tr { fn item(&self); }
+}
+"#);
+    for doc in docs.iter_mut().filter(|doc| doc.name == "hidden") {
+        doc.visibility = Some(Visibility::Inherited);
+    }
+
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("crate", "1.0.0"), generation::make_docset(docs).unwrap());
+    store
+}
+
+fn paths<'a, I: Iterator<Item = ::oxidoc::store::DocRef<'a>>>(docs: I) -> Vec<String> {
+    docs.map(|doc| doc.mod_path.to_string()).collect()
+}
+
+#[test]
+fn test_iter_all() {
+    let store = mixed_store();
+    let all = paths(store.iter());
+    assert!(all.contains(&"crate::Shown".to_string()));
+    assert!(all.contains(&"crate::hidden".to_string()));
+    assert!(all.contains(&"crate::inner::Tr::item".to_string()));
+
+    let mut sorted = all.clone();
+    sorted.sort();
+    assert_eq!(all, sorted);
+}
+
+#[test]
+fn test_iter_by_visibility() {
+    let store = mixed_store();
+
+    let public = paths(store.iter().by_visibility(Visibility::Public));
+    assert!(public.contains(&"crate::Shown".to_string()));
+    assert!(public.contains(&"crate::inner::Tr::item".to_string()));
+    assert!(!public.contains(&"crate::hidden".to_string()));
+
+    let private = paths(store.iter().by_visibility(Visibility::Private));
+    assert_eq!(private, vec!["crate::hidden"]);
+}
+
+#[test]
+fn test_iter_in_module() {
+    let store = mixed_store();
+    let inner = ModPath::from("crate::inner".to_string());
+
+    let in_inner = paths(store.iter().in_module(&inner).by_visibility(Visibility::Public));
+    assert_eq!(in_inner, vec!["crate::inner::Tr", "crate::inner::Tr::item",
+                              "crate::inner::shown_inner"]);
+}
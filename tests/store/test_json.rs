@@ -0,0 +1,88 @@
+use oxidoc::conversion::{DocType, Documentation};
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat, STORE_VERSION};
+
+use serde_json;
+
+use util;
+
+fn json_store() -> Store {
+    let docs = util::source_to_docs(r#"
+/// A function.
+pub fn function(a: u32) -> u32 { a }
+
+/// A struct.
+pub struct Thing { pub field: u32 }
+
+impl Thing {
+    pub fn method(&self) {}
+}
+
+/// A trait.
+pub trait Trait {
+    fn required(&self);
+}
+"#);
+    let docset = generation::make_docset(docs).unwrap();
+
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("crate", "1.0.0"), docset);
+    store
+}
+
+#[test]
+fn test_json_round_trip() {
+    let json = json_store().to_json().unwrap();
+    let store = Store::from_json(&json).unwrap();
+
+    assert_eq!(store.to_json().unwrap(), json);
+
+    let function = store.lookup_name("function");
+    assert_eq!(function.len(), 1);
+    assert_eq!(function[0].doc_type, DocType::Function);
+
+    // The closest match comes first, ahead of the items inside it.
+    let thing = store.lookup_name("Thing");
+    assert_eq!(thing[0].doc_type, DocType::Struct);
+    assert_eq!(store.lookup_name("Thing::method")[0].doc_type, DocType::Method);
+
+    let trait_ = store.lookup_name("Trait");
+    assert_eq!(trait_[0].doc_type, DocType::Trait);
+}
+
+#[test]
+fn test_json_readable_keys() {
+    let json = json_store().to_json().unwrap();
+
+    assert!(json.contains("\"mod_path\": \"crate::Thing::method\""));
+    assert!(json.contains("\"Method\": ["));
+}
+
+#[test]
+fn test_json_version_mismatch() {
+    let json = json_store().to_json().unwrap();
+    let old = json.replacen(&format!("\"version\": {}", STORE_VERSION), "\"version\": 0", 1);
+
+    assert!(Store::from_json(&old).is_err());
+}
+
+#[test]
+fn test_write_jsonl() {
+    let store = json_store();
+    let mut output = Vec::new();
+    store.write_jsonl(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), store.all_locations().len());
+    assert!(output.ends_with('\n'));
+
+    let docs: Vec<Documentation> = lines.iter()
+        .map(|line| serde_json::from_str(line).expect(line))
+        .collect();
+    let paths: Vec<String> = docs.iter().map(|doc| doc.mod_path.to_string()).collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+    assert!(paths.contains(&"crate::Thing::method".to_string()), "{:?}", paths);
+}
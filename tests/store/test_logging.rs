@@ -0,0 +1,86 @@
+use std::cell::RefCell;
+use std::env;
+use std::sync::{Once, ONCE_INIT};
+
+use log::{self, Log, LogLevelFilter, LogMetadata, LogRecord};
+use oxidoc::store::Store;
+
+use util;
+
+thread_local! {
+    /// The messages logged on this thread while capturing, or `None` if not capturing. Each test
+    /// runs on its own thread, so tests logging at the same time don't see each other's messages.
+    static CAPTURED: RefCell<Option<Vec<String>>> = RefCell::new(None);
+}
+
+struct CaptureLogger;
+
+impl Log for CaptureLogger {
+    fn enabled(&self, _metadata: &LogMetadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &LogRecord) {
+        CAPTURED.with(|captured| {
+            if let Some(ref mut messages) = *captured.borrow_mut() {
+                messages.push(record.args().to_string());
+            }
+        });
+    }
+}
+
+static INIT: Once = ONCE_INIT;
+
+/// Returns the messages logged on this thread while running `f`.
+fn capture_logs<F: FnOnce()>(f: F) -> Vec<String> {
+    INIT.call_once(|| {
+        log::set_logger(|max_level| {
+            max_level.set(LogLevelFilter::Trace);
+            Box::new(CaptureLogger)
+        }).unwrap();
+    });
+
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    f();
+    CAPTURED.with(|captured| captured.borrow_mut().take().unwrap())
+}
+
+#[test]
+fn test_get_modpaths_does_not_log() {
+    let store = util::store_from_source("crate", "1.0.0", "pub struct Quiet; pub fn hush() {}");
+
+    let mut paths = Vec::new();
+    let logs = capture_logs(|| {
+        paths = store.get_modpaths().iter().map(|path| path.to_string()).collect();
+    });
+
+    assert_eq!(paths, vec!["crate", "crate::Quiet", "crate::hush"]);
+    assert!(logs.is_empty(), "Unexpected logs: {:?}", logs);
+}
+
+#[test]
+fn test_debug_dump_modpaths() {
+    let store = util::store_from_source("crate", "1.0.0", "pub struct Loud;");
+
+    let logs = capture_logs(|| store.debug_dump_modpaths());
+
+    assert_eq!(logs, vec!["crate", "crate::Loud"]);
+}
+
+#[test]
+fn test_get_modpaths_follows_changes() {
+    let mut store = util::store_from_source("crate", "1.0.0", "pub struct Quiet;");
+    store.merge(util::store_from_source("other", "1.0.0", "pub fn hush() {}"));
+
+    let path = env::temp_dir().join("oxidoc-modpaths.odoc");
+    store.save_to_file(&path).unwrap();
+    let loaded = Store::load_from_file(&path).unwrap();
+
+    let logs = capture_logs(|| {
+        for store in &[&store, &loaded] {
+            let paths: Vec<String> = store.get_modpaths().iter().map(|p| p.to_string()).collect();
+            assert_eq!(paths, vec!["crate", "crate::Quiet", "other", "other::hush"]);
+        }
+    });
+    assert!(logs.is_empty(), "Unexpected logs: {:?}", logs);
+}
@@ -0,0 +1,62 @@
+use oxidoc::conversion::DocType;
+use oxidoc::document::ModPath;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+#[test]
+fn test_merge_counts() {
+    let mut store = util::store_from_source("crate", "1.0.0", "pub struct Thing; pub fn old() {}");
+    store.merge(util::store_from_source("crate", "1.0.0", "pub fn thing() {} pub fn new() {}"));
+    store.merge(util::store_from_source("crate", "1.1.0", "pub fn newer() {}"));
+
+    // The crate modules and `thing` are shared between the two 1.0.0 stores.
+    assert_eq!(store.all_locations().len(), 6);
+}
+
+#[test]
+fn test_merge_prefers_incoming() {
+    let mut store = util::store_from_source("crate", "1.0.0", "pub struct Thing; pub fn old() {}");
+    store.merge(util::store_from_source("crate", "1.0.0", "pub fn thing() {} pub fn new() {}"));
+
+    let results = store.lookup_name("thing");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].doc_type, DocType::Function);
+    assert_eq!(results[0].name, "thing");
+
+    assert_eq!(store.lookup_name("old").len(), 1);
+    assert_eq!(store.lookup_name("new").len(), 1);
+}
+
+#[test]
+fn test_merged_crates() {
+    let mut store = Store::new(StoreFormat::default());
+    for &(krate, src) in &[("beta", "pub fn b() {}"), ("alpha", "pub struct Point; pub fn a() {}")] {
+        store.merge(util::store_from_source(krate, "1.0.0", src));
+    }
+
+    assert_eq!(store.crates(), vec!["alpha", "beta"]);
+
+    let alpha: Vec<(String, String)> = store.documents_in_crate("alpha")
+        .map(|doc| (doc.crate_info.name.clone(), doc.mod_path.to_string()))
+        .collect();
+    assert_eq!(alpha, vec![("alpha".to_string(), "alpha".to_string()),
+                           ("alpha".to_string(), "alpha::Point".to_string()),
+                           ("alpha".to_string(), "alpha::a".to_string())]);
+    assert_eq!(store.documents_in_crate("beta").count(), 2);
+    assert_eq!(store.documents_in_crate("gamma").count(), 0);
+    assert_eq!(store.iter().count(), 5);
+}
+
+#[test]
+fn test_merge_replaces_unsaved_with_saved() {
+    let mut store = util::store_from_source("mergesaved", "1.0.0", "/// Old.\npub fn thing() {}");
+    let mut incoming = util::store_from_source("mergesaved", "1.0.0", "/// New.\npub fn thing() {}");
+    incoming.save_documents().unwrap();
+
+    store.merge(incoming);
+    store.save_documents().unwrap();
+
+    let doc = store.load_doc(&ModPath::from("mergesaved::thing".to_string())).unwrap();
+    assert_eq!(doc.attrs.doc_strings, vec!["New.".to_string()]);
+}
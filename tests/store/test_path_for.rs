@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use oxidoc::document::ModPath;
+use oxidoc::paths;
+
+use util;
+
+const SRC: &str = "pub mod shapes { pub struct Circle; impl Circle { pub fn area(&self) {} } }\n\
+                   pub fn draw() {}";
+
+#[test]
+fn test_path_for() {
+    let krate = "pathfor";
+    let paths = ["pathfor::shapes", "pathfor::shapes::Circle", "pathfor::shapes::Circle::area",
+                 "pathfor::draw", "pathfor::missing"];
+    let first = util::store_from_source(krate, "1.0.0", SRC);
+    let second = util::store_from_source(krate, "1.0.0", SRC);
+
+    let found: Vec<_> = paths.iter()
+        .map(|path| first.path_for(&ModPath::from(path.to_string())).unwrap())
+        .collect();
+    for (path, expected) in paths.iter().zip(&found) {
+        assert_eq!(&second.path_for(&ModPath::from(path.to_string())).unwrap(), expected);
+        assert!(expected.extension().map_or(false, |ext| ext == "odoc"), "{}", expected.display());
+    }
+
+    let distinct: HashSet<_> = found.iter().collect();
+    assert_eq!(distinct.len(), found.len(), "{:?}", found);
+
+    let docs = util::source_to_crate_docs(krate, "1.0.0", SRC);
+    let circle = docs.iter().find(|doc| doc.mod_path.to_string() == "pathfor::shapes::Circle")
+        .unwrap();
+    assert_eq!(found[1], circle.store_path().unwrap());
+}
+
+#[test]
+fn test_saved_docs_load_from_path_for() {
+    let krate = "pathforload";
+    let mut store = util::store_from_source(krate, "1.0.0", SRC);
+    let save_plan = store.save_plan().unwrap();
+    store.save_documents().unwrap();
+
+    let mod_path = ModPath::from(format!("{}::shapes::Circle", krate));
+    assert!(save_plan.contains(&store.path_for(&mod_path).unwrap()));
+    assert!(store.path_for(&mod_path).unwrap().exists());
+    assert_eq!(store.load_doc(&mod_path).unwrap().mod_path, mod_path);
+}
+
+#[test]
+fn test_path_for_unstored() {
+    let store = util::store_from_source("pathforunstored", "1.0.0", SRC);
+    let path = store.path_for(&ModPath::from("pathforunstored::no.such".to_string())).unwrap();
+    let crate_dir = paths::crate_doc_path(&util::get_crate_info("pathforunstored", "1.0.0"))
+        .unwrap();
+
+    assert_eq!(path, crate_dir.join("pathforunstored").join("no%2Esuch").join("no%2Esuch.odoc"));
+}
+
+#[test]
+fn test_saved_docs_keep_their_version() {
+    let krate = "pathforversions";
+    let mut store = util::store_from_source(krate, "1.0.0", SRC);
+    store.merge(util::store_from_source(krate, "1.1.0", SRC));
+    store.save_documents().unwrap();
+
+    for version in &["1.0.0", "1.1.0"] {
+        let crate_dir = paths::crate_doc_path(&util::get_crate_info(krate, version)).unwrap();
+        assert!(crate_dir.join(krate).join("draw").join("draw.odoc").exists(), "{}", version);
+    }
+}
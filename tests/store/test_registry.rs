@@ -0,0 +1,60 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use oxidoc::document::ModPath;
+use oxidoc::store::StoreRegistry;
+
+use util;
+
+/// Saves a store documenting `src` as the crate `krate` to `dir`.
+fn save_store(dir: &PathBuf, krate: &str, src: &str) {
+    let mut store = util::store_from_source(krate, "1.0.0", src);
+    store.save_documents().unwrap();
+
+    fs::create_dir_all(dir).unwrap();
+    store.save_to_file(dir.join("store")).unwrap();
+}
+
+fn make_root(name: &str) -> PathBuf {
+    let mut root = env::temp_dir();
+    root.push(format!("oxidoc-registry-{}", name));
+    let _ = fs::remove_dir_all(&root);
+
+    save_store(&root.join("alpha"), &format!("{}alpha", name),
+               "/// The first.\npub struct Shared; pub fn one() {}");
+    save_store(&root.join("beta"), &format!("{}beta", name),
+               "/// The second.\npub struct Shared; pub fn two() {}");
+    fs::create_dir_all(root.join("empty")).unwrap();
+    root
+}
+
+#[test]
+fn test_registry() {
+    let root = make_root("resolve");
+    let registry = StoreRegistry::scan(&root).unwrap();
+    assert_eq!(registry.store_dirs(), vec![root.join("alpha"), root.join("beta")]);
+
+    let one = registry.resolve(&ModPath::from("resolvealpha::one".to_string())).unwrap();
+    assert_eq!(one.name, "one");
+    let two = registry.resolve(&ModPath::from("resolvebeta::two".to_string())).unwrap();
+    assert_eq!(two.name, "two");
+
+    let first = registry.resolve(&ModPath::from("resolvealpha::Shared".to_string())).unwrap();
+    assert_eq!(first.attrs.doc_strings, vec!["The first.".to_string()]);
+    let second = registry.resolve(&ModPath::from("resolvebeta::Shared".to_string())).unwrap();
+    assert_eq!(second.attrs.doc_strings, vec!["The second.".to_string()]);
+
+    assert!(registry.resolve(&ModPath::from("resolvemissing::one".to_string())).is_err());
+}
+
+#[test]
+fn test_registry_search() {
+    let root = make_root("search");
+    let registry = StoreRegistry::scan(&root).unwrap();
+
+    let paths: Vec<String> = registry.search("shared").iter()
+        .map(|loc| loc.mod_path.to_string())
+        .collect();
+    assert_eq!(paths, vec!["searchalpha::Shared", "searchbeta::Shared"]);
+}
@@ -0,0 +1,34 @@
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+fn add_crate(store: &mut Store, name: &str, src: &str) {
+    let docs = util::source_to_crate_docs(name, "1.0.0", src);
+    let docset = generation::make_docset(docs).unwrap();
+    store.add_docset(util::get_crate_info(name, "1.0.0"), docset);
+}
+
+#[test]
+fn test_remove_crate() {
+    let mut store = Store::new(StoreFormat::default());
+    add_crate(&mut store, "removed", "pub struct Gone; impl Gone { pub fn method(&self) {} }");
+    add_crate(&mut store, "kept", "pub struct Stays;");
+    store.save().unwrap();
+
+    let removed_files: Vec<_> = store.all_locations().into_iter()
+        .filter(|loc| loc.crate_info.name == "removed")
+        .map(|loc| loc.to_filepath().unwrap())
+        .collect();
+    assert!(!removed_files.is_empty());
+
+    store.remove_crate("removed").unwrap();
+
+    assert!(removed_files.iter().all(|path| !path.exists()));
+    assert!(store.lookup_name("gone").is_empty());
+    assert!(store.search("method").is_empty());
+    assert_eq!(store.lookup_name("stays").len(), 1);
+    assert!(store.all_locations().iter().all(|loc| loc.crate_info.name == "kept"));
+
+    assert!(store.remove_crate("removed").is_err());
+}
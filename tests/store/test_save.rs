@@ -0,0 +1,113 @@
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+
+use bincode::{self, Infinite};
+use serde_json;
+
+use oxidoc::conversion::Documentation;
+use oxidoc::generation;
+use oxidoc::store::{self, Store, StoreFormat};
+
+use util;
+
+#[test]
+fn test_save_documents() {
+    let docs = util::source_to_docs(r#"
+pub mod saved {
+    pub struct First;
+    pub fn second() {}
+}
+"#);
+    let docset = generation::make_docset(docs).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("crate", "1.0.0"), docset);
+
+    store.save_documents().unwrap();
+
+    let locations = store.all_locations();
+    let mut paths: Vec<String> = locations.iter().map(|loc| loc.mod_path.to_string()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["crate",
+                           "crate::saved",
+                           "crate::saved::First",
+                           "crate::saved::second"]);
+
+    for location in locations {
+        let path = location.to_filepath().unwrap();
+        assert!(path.exists(), "{} was not saved", path.display());
+
+        let doc: Documentation = store::deserialize_object(&path).unwrap();
+        assert_eq!(doc.mod_path, location.mod_path);
+        assert_eq!(doc.name, location.name);
+    }
+}
+
+#[test]
+fn test_compressed_documents() {
+    let doc_lines: Vec<String> = (0..50)
+        .map(|i| format!("/// Line {} of a long and repetitive doc comment.", i))
+        .collect();
+    let docs = util::source_to_docs(&format!("{}\npub struct Big;", doc_lines.join("\n")));
+    let doc = docs.into_iter().find(|doc| doc.name == "Big").unwrap();
+
+    let mut path = env::temp_dir();
+    path.push("oxidoc-compressed.odoc");
+    store::serialize_object(&doc, &path).unwrap();
+
+    let mut bytes = Vec::new();
+    File::open(&path).unwrap().read_to_end(&mut bytes).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+    assert!(bytes.len() < serde_json::to_string(&doc).unwrap().len());
+
+    let loaded: Documentation = store::deserialize_object(&path).unwrap();
+    assert_eq!(loaded, doc);
+}
+
+#[test]
+fn test_uncompressed_documents_still_load() {
+    let docs = util::source_to_docs("/// Old.\npub struct Old;");
+    let doc = docs.into_iter().find(|doc| doc.name == "Old").unwrap();
+
+    let mut path = env::temp_dir();
+    path.push("oxidoc-uncompressed.odoc");
+    let data = bincode::serialize(&doc, Infinite).unwrap();
+    File::create(&path).unwrap().write_all(&data).unwrap();
+
+    let loaded: Documentation = store::deserialize_object(&path).unwrap();
+    assert_eq!(loaded, doc);
+}
+
+#[test]
+fn test_round_trip_both_formats() {
+    let docs = util::source_to_docs("/// Both.\npub struct Both;");
+    let doc = docs.into_iter().find(|doc| doc.name == "Both").unwrap();
+
+    for &(format, name) in &[(StoreFormat::Bincode, "bincode"), (StoreFormat::Json, "json")] {
+        let mut path = env::temp_dir();
+        path.push(format!("oxidoc-format-{}.odoc", name));
+        store::serialize_object_as(&doc, &path, format).unwrap();
+
+        let loaded: Documentation = store::deserialize_object(&path).unwrap();
+        assert_eq!(loaded, doc, "{:?} did not round trip", format);
+    }
+}
+
+#[test]
+fn test_save_documents_as_json() {
+    let docs = util::source_to_docs("pub struct Readable;");
+    let docset = generation::make_docset(docs).unwrap();
+    assert_eq!(StoreFormat::default(), StoreFormat::Json);
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("jsonformat", "1.0.0"), docset);
+
+    store.save_documents().unwrap();
+
+    let locations = store.all_locations();
+    assert!(!locations.is_empty());
+    for location in locations {
+        let path = location.to_filepath().unwrap();
+        let doc: Documentation = store::deserialize_object(path).unwrap();
+        assert_eq!(doc.mod_path, location.mod_path);
+    }
+}
@@ -0,0 +1,44 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use oxidoc::paths;
+
+use util;
+
+fn files_under(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files_under(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+#[test]
+fn test_save_plan() {
+    let krate = "saveplan";
+    let crate_dir = paths::crate_doc_path(&util::get_crate_info(krate, "1.0.0")).unwrap();
+    let _ = fs::remove_dir_all(&crate_dir);
+
+    let src = "pub mod shapes { pub struct Circle; impl Circle { pub fn area(&self) {} } }\n\
+               pub fn draw() {}";
+    let mut store = util::store_from_source(krate, "1.0.0", src);
+
+    let mut plan = store.save_plan().unwrap();
+    let store_file = plan.pop().unwrap();
+    assert!(plan.iter().all(|path| !path.exists()));
+    assert!(!crate_dir.exists());
+
+    store.save().unwrap();
+
+    let mut written = Vec::new();
+    files_under(&crate_dir, &mut written);
+    written.sort();
+    assert_eq!(plan, written);
+    assert_eq!(plan.len(), store.all_locations().len());
+    assert!(store_file.exists());
+
+    assert_eq!(store.save_plan().unwrap(), vec![store_file]);
+}
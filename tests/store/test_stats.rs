@@ -0,0 +1,42 @@
+use oxidoc::conversion::DocType;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+const SOURCE: &str = r#"
+pub mod shapes {
+    pub struct Circle;
+    pub struct Square;
+    impl Circle {
+        pub fn area(&self) -> f32 { 0.0 }
+    }
+    pub trait Shape {}
+}
+pub fn draw() {}
+pub fn erase() {}
+"#;
+
+#[test]
+fn test_stats() {
+    let mut store = util::store_from_source("crate", "1.0.0", SOURCE);
+    store.merge(util::store_from_source("crate", "1.1.0", SOURCE));
+
+    let stats = store.stats();
+    assert_eq!(stats.crates, 1);
+    assert_eq!(stats.documents, 16);
+    assert_eq!(stats.modpaths, 8);
+    assert_eq!(stats.by_type[&DocType::Module], 4);
+    assert_eq!(stats.by_type[&DocType::Struct], 4);
+    assert_eq!(stats.by_type[&DocType::Function], 4);
+    assert_eq!(stats.by_type[&DocType::Method], 2);
+    assert_eq!(stats.by_type[&DocType::Trait], 2);
+    assert!(stats.to_string().starts_with("1 crates, 16 documents at 8 paths"), "{}", stats);
+}
+
+#[test]
+fn test_empty_stats() {
+    let stats = Store::new(StoreFormat::default()).stats();
+    assert_eq!(stats.documents, 0);
+    assert_eq!(stats.modpaths, 0);
+    assert!(stats.by_type.is_empty());
+}
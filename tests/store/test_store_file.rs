@@ -0,0 +1,136 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use bincode::{self, Infinite};
+use oxidoc::document::ModPath;
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat, STORE_VERSION};
+
+use util;
+
+fn temp_store_path(name: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("oxidoc-{}.odoc", name));
+    path
+}
+
+fn write_bytes(path: &PathBuf, data: &[u8]) {
+    File::create(path).unwrap().write_all(data).unwrap();
+}
+
+#[test]
+fn test_load_store_file() {
+    let docs = util::source_to_docs("pub struct Thing; pub fn other() {}");
+    let docset = generation::make_docset(docs).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("crate", "1.0.0"), docset);
+
+    let path = temp_store_path("valid");
+    store.save_to_file(&path).unwrap();
+
+    let loaded = Store::load_from_file(&path).unwrap();
+    assert_eq!(loaded.all_locations().len(), store.all_locations().len());
+    assert_eq!(loaded.lookup_name("thing").len(), 1);
+}
+
+#[test]
+fn test_load_store_file_version_mismatch() {
+    let path = temp_store_path("old-version");
+    let mut data = bincode::serialize(&(STORE_VERSION + 1), Infinite).unwrap();
+    data.extend(bincode::serialize(&Store::new(StoreFormat::default()), Infinite).unwrap());
+    write_bytes(&path, &data);
+
+    let err = Store::load_from_file(&path).err().expect("Loaded store with wrong version");
+    assert!(err.to_string().contains("Regenerate"), "Unexpected error: {}", err);
+}
+
+#[test]
+fn test_load_or_new_reports_version_mismatch() {
+    let path = temp_store_path("old-version-kept");
+    let mut data = bincode::serialize(&(STORE_VERSION + 1), Infinite).unwrap();
+    data.extend(bincode::serialize(&Store::new(StoreFormat::default()), Infinite).unwrap());
+    write_bytes(&path, &data);
+
+    let err = Store::load_or_new(&path).err().expect("Replaced store with wrong version");
+    assert!(err.to_string().contains("Regenerate"), "Unexpected error: {}", err);
+
+    let mut kept = Vec::new();
+    File::open(&path).unwrap().read_to_end(&mut kept).unwrap();
+    assert_eq!(kept, data);
+}
+
+#[test]
+fn test_load_or_new_missing_store_file() {
+    let path = temp_store_path("missing");
+    let _ = fs::remove_file(&path);
+
+    let store = Store::load_or_new(&path).unwrap();
+    assert!(store.all_locations().is_empty());
+}
+
+#[test]
+fn test_load_corrupt_store_file() {
+    let path = temp_store_path("corrupt");
+    let mut data = bincode::serialize(&STORE_VERSION, Infinite).unwrap();
+    data.extend(&[0xff, 0xff, 0xff]);
+    write_bytes(&path, &data);
+
+    assert!(Store::load_from_file(&path).is_err());
+
+    write_bytes(&path, &[0x01]);
+    assert!(Store::load_from_file(&path).is_err());
+}
+
+#[test]
+fn test_load_truncated_store_file() {
+    let docs = util::source_to_docs("pub struct Thing;");
+    let docset = generation::make_docset(docs).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("crate", "1.0.0"), docset);
+
+    let path = temp_store_path("truncated");
+    store.save_to_file(&path).unwrap();
+
+    let mut data = Vec::new();
+    File::open(&path).unwrap().read_to_end(&mut data).unwrap();
+    let half = data.len() / 2;
+    write_bytes(&path, &data[..half]);
+
+    assert!(Store::load_from_file(&path).is_err());
+}
+
+#[test]
+fn test_prerelease_versions() {
+    let mut store = Store::new(StoreFormat::default());
+    for version in &["1.0.0-beta", "not.a.version", "0.9.0"] {
+        let docs = util::source_to_docs("pub struct Thing;");
+        let docset = generation::make_docset(docs).unwrap();
+        store.add_docset(util::get_crate_info("crate", version), docset);
+    }
+
+    assert_eq!(store.lookup_name("thing").len(), 1);
+}
+
+#[test]
+fn test_open_lazy() {
+    let docs = util::source_to_docs("/// Wanted.\npub struct Wanted; pub fn unwanted() {}");
+    let docset = generation::make_docset(docs).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info("lazy", "1.0.0"), docset);
+    store.save_documents().unwrap();
+
+    let path = temp_store_path("lazy");
+    store.save_to_file(&path).unwrap();
+
+    let lazy = Store::open_lazy(&path).unwrap();
+    let wanted = ModPath::from("lazy::Wanted".to_string());
+    let unwanted = ModPath::from("lazy::unwanted".to_string());
+    assert!(!lazy.is_cached(&wanted));
+
+    let doc = lazy.get_doc(&wanted).unwrap();
+    assert_eq!(doc.attrs.doc_strings, vec!["Wanted.".to_string()]);
+    assert!(lazy.is_cached(&wanted));
+    assert!(!lazy.is_cached(&unwanted));
+}
@@ -0,0 +1,91 @@
+use std::fs;
+
+use oxidoc::conversion::{DocLink, Documentation};
+use oxidoc::document::ModPath;
+use oxidoc::errors::ErrorKind;
+use oxidoc::generation;
+use oxidoc::store::{Store, StoreFormat};
+
+use util;
+
+const SOURCE: &str = r#"
+//! See [`shapes::Circle`].
+pub mod shapes {
+    /// A circle, which is a [`Shape`].
+    pub struct Circle;
+    impl Circle {
+        pub fn area(&self) -> f32 { 0.0 }
+    }
+    pub trait Shape {}
+    impl Shape for Circle {}
+    impl Clone for Circle { fn clone(&self) -> Self { Circle } }
+}
+pub fn draw() {}
+"#;
+
+fn store_from_docs(krate: &str, docs: Vec<Documentation>) -> Store {
+    let docset = generation::make_docset(docs).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(util::get_crate_info(krate, "1.0.0"), docset);
+    store
+}
+
+#[test]
+fn test_validate_consistent_store() {
+    let store = store_from_docs("validok", util::source_to_crate_docs("validok", "1.0.0", SOURCE));
+    store.validate().unwrap();
+}
+
+#[test]
+fn test_validate_dangling_link() {
+    let docs = util::source_to_crate_docs("validlink", "1.0.0", SOURCE).into_iter()
+        .map(|doc| {
+            if doc.mod_path.to_string() != "validlink::draw" {
+                return doc;
+            }
+            doc.with_see_also(vec![DocLink {
+                name: "Missing".to_string(),
+                path: ModPath::from("validlink::shapes::Missing".to_string()),
+            }])
+        })
+        .collect();
+    let store = store_from_docs("validlink", docs);
+
+    match *store.validate().unwrap_err().kind() {
+        ErrorKind::InconsistentStore(ref problems) => {
+            assert_eq!(problems, &vec!["validlink::draw links to validlink::shapes::Missing, \
+                                        which isn't documented".to_string()]);
+        }
+        ref other => panic!("Expected an inconsistent store, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_validate_missing_document() {
+    let mut store = store_from_docs("validmissing",
+                                    util::source_to_crate_docs("validmissing", "1.0.0", SOURCE));
+    store.save_documents().unwrap();
+    store.validate().unwrap();
+
+    let location = store.all_locations().into_iter()
+        .find(|loc| loc.mod_path.to_string() == "validmissing::draw")
+        .unwrap();
+    fs::remove_file(location.to_filepath().unwrap()).unwrap();
+
+    let err = store.validate().unwrap_err();
+    assert!(err.to_string().contains("validmissing::draw has no readable document"), "{}", err);
+}
+
+#[test]
+fn test_validate_duplicate_path() {
+    let source = "pub struct Foo;\npub fn foo() {}";
+    let store = store_from_docs("validdup", util::source_to_crate_docs("validdup", "1.0.0", source));
+
+    match *store.validate().unwrap_err().kind() {
+        ErrorKind::InconsistentStore(ref problems) => {
+            assert_eq!(problems, &vec!["foo is documented more than once in validdup-1.0.0, as \
+                                        validdup::Foo and validdup::foo".to_string()]);
+        }
+        ref other => panic!("Expected an inconsistent store, got {:?}", other),
+    }
+}
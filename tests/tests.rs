@@ -8,6 +8,7 @@ extern crate cursive;
 extern crate env_logger;
 extern crate regex;
 extern crate serde;
+extern crate serde_json;
 extern crate syntex_syntax as syntax;
 extern crate toml;
 extern crate oxidoc;
@@ -16,5 +17,11 @@ extern crate oxidoc;
 extern crate pager;
 
 mod conversion;
+mod document;
+mod generation;
+mod links;
+mod markup;
 mod search;
+mod store;
+mod theme;
 mod util;
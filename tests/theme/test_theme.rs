@@ -0,0 +1,38 @@
+use oxidoc::markup::Markup::*;
+use oxidoc::theme::{ColorSupport, Theme};
+
+#[test]
+fn test_themes_differ() {
+    let header = Header("Function crate::thing".to_string());
+    let support = Some(ColorSupport::TrueColor);
+
+    let default = header.render_with_theme(&Theme::default(), support);
+    let solarized = header.render_with_theme(&Theme::solarized(), support);
+
+    assert!(default.contains('\x1b'));
+    assert!(solarized.contains("\x1b[1;38;2;181;137;0m"), "{:?}", solarized);
+    assert_ne!(default, solarized);
+}
+
+#[test]
+fn test_theme_degrades() {
+    let header = Header("Function crate::thing".to_string());
+    let theme = Theme::solarized();
+
+    let extended = header.render_with_theme(&theme, Some(ColorSupport::Extended));
+    assert!(extended.contains("38;5;"), "{:?}", extended);
+
+    let basic = header.render_with_theme(&theme, Some(ColorSupport::Basic));
+    assert!(!basic.contains("38;5;") && !basic.contains("38;2;"), "{:?}", basic);
+    assert!(basic.contains("\x1b[1;33m"), "{:?}", basic);
+
+    let mono = header.render_with_theme(&Theme::mono(), Some(ColorSupport::TrueColor));
+    assert_eq!(mono, "==== Function crate::thing");
+}
+
+#[test]
+fn test_detect_color_support() {
+    assert_eq!(ColorSupport::from_env("truecolor", "xterm"), ColorSupport::TrueColor);
+    assert_eq!(ColorSupport::from_env("", "xterm-256color"), ColorSupport::Extended);
+    assert_eq!(ColorSupport::from_env("", "xterm"), ColorSupport::Basic);
+}
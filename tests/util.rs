@@ -1,12 +1,32 @@
 use oxidoc::conversion::Documentation;
 use oxidoc::document::{CrateInfo, ModPath};
-use oxidoc::generation;
+use oxidoc::generation::{self, ConvertOptions};
+use oxidoc::paths;
+use oxidoc::store::{Store, StoreFormat};
 
-use syntax::codemap::FilePathMapping;
+use std::env;
+use std::rc::Rc;
+use std::sync::{Once, ONCE_INIT};
+
+use syntax::codemap::{CodeMap, FilePathMapping};
 use syntax::parse::{self, ParseSess};
 use syntax::ast;
+use syntax::errors::{ColorConfig, Handler};
+
+static TEMP_REGISTRY: Once = ONCE_INIT;
+
+/// Points the doc registry at a scratch directory, so nothing the tests save ends up in the
+/// user's `~/.cargo/registry/doc`.
+fn use_temp_registry() {
+    TEMP_REGISTRY.call_once(|| {
+        paths::set_doc_registry_path(env::temp_dir().join("oxidoc-test-registry"));
+    });
+}
 
+/// Crate info for a test crate. Every test making documentation starts here, so this is also
+/// where the registry is moved out of the user's home directory.
 pub fn get_crate_info(name: &str, version: &str) -> CrateInfo {
+    use_temp_registry();
     CrateInfo {
         name: name.to_string(),
         version: version.to_string(),
@@ -15,10 +35,12 @@ pub fn get_crate_info(name: &str, version: &str) -> CrateInfo {
 }
 
 fn parse_crate_from_source(docs_string: String) -> ast::Crate {
-    let parse_session = ParseSess::new(FilePathMapping::empty());
+    parse_crate_with_session(docs_string, &ParseSess::new(FilePathMapping::empty()))
+}
 
+fn parse_crate_with_session(docs_string: String, parse_session: &ParseSess) -> ast::Crate {
     let result = parse::parse_crate_from_source_str("test.rs".to_string(), docs_string,
-                                                    &parse_session);
+                                                    parse_session);
 
     match result {
         Ok(_) if parse_session.span_diagnostic.has_errors() => panic!("Parse error"),
@@ -28,16 +50,67 @@ fn parse_crate_from_source(docs_string: String) -> ast::Crate {
 }
 
 pub fn source_to_docs(docs_str: &str) -> Vec<Documentation> {
+    source_to_docs_with_hidden(docs_str, false)
+}
+
+pub fn source_to_docs_with_hidden(docs_str: &str, include_hidden: bool) -> Vec<Documentation> {
     let krate = parse_crate_from_source(docs_str.to_string());
 
     let crate_info = get_crate_info("crate", "1.0.0");
-    let l = generation::generate_crate_docs(krate, crate_info).unwrap();
+    let options = ConvertOptions { include_hidden: include_hidden, ..ConvertOptions::default() };
+    let l = generation::generate_crate_docs_with(krate, crate_info, &options).unwrap();
     for i in l.iter() {
         debug!("{}", i.mod_path);
     }
     l
 }
 
+pub fn source_to_docs_with_private(docs_str: &str, document_private_items: bool)
+                                   -> Vec<Documentation> {
+    let krate = parse_crate_from_source(docs_str.to_string());
+
+    let crate_info = get_crate_info("crate", "1.0.0");
+    let options = ConvertOptions {
+        document_private_items: document_private_items,
+        ..ConvertOptions::default()
+    };
+    generation::generate_crate_docs_with(krate, crate_info, &options).unwrap()
+}
+
+/// Like `source_to_docs`, but keeps the parsed source around so each document records where it
+/// is defined in `test.rs`.
+pub fn source_to_docs_with_locations(docs_str: &str) -> Vec<Documentation> {
+    let codemap = Rc::new(CodeMap::new(FilePathMapping::empty()));
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(codemap.clone()));
+    let parse_session = ParseSess::with_span_handler(handler, codemap.clone());
+    let krate = parse_crate_with_session(docs_str.to_string(), &parse_session);
+
+    let crate_info = get_crate_info("crate", "1.0.0");
+    let options = ConvertOptions { codemap: Some(codemap), ..ConvertOptions::default() };
+    generation::generate_crate_docs_with(krate, crate_info, &options).unwrap()
+}
+
+/// Like `source_to_docs`, but for a crate with the given name and version.
+pub fn source_to_crate_docs(name: &str, version: &str, docs_str: &str) -> Vec<Documentation> {
+    let krate = parse_crate_from_source(docs_str.to_string());
+    generation::generate_crate_docs(krate, get_crate_info(name, version)).unwrap()
+}
+
+/// A store holding the documentation of `docs_str` as the given version of the crate `name`.
+/// Nothing is saved.
+pub fn store_from_source(name: &str, version: &str, docs_str: &str) -> Store {
+    let docset = generation::make_docset(source_to_crate_docs(name, version, docs_str)).unwrap();
+    let mut store = Store::new(StoreFormat::default());
+    store.add_docset(get_crate_info(name, version), docset);
+    store
+}
+
+pub fn find_doc<'a>(docs: &'a [Documentation], path: &str) -> &'a Documentation {
+    docs.iter()
+        .find(|doc| doc.mod_path.to_string() == path)
+        .expect(&format!("No documentation found for {}", path))
+}
+
 pub fn print_paths(paths: &Vec<ModPath>) -> String {
     let strings: Vec<String> = paths.iter().cloned().map(|p| p.to_string()).collect();
     strings.join("\n")